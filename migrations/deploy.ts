@@ -44,7 +44,7 @@ module.exports = async function (provider: anchor.AnchorProvider) {
   }
   try {
     const tx = await program.methods
-      .initialize()
+      .initialize(admin.publicKey)
       .accounts({
         admin: admin.publicKey
       })
@@ -55,6 +55,18 @@ module.exports = async function (provider: anchor.AnchorProvider) {
     console.log('✅ Program initialized successfully!')
     console.log('📝 Transaction signature:', tx)
 
+    const jurisdictionTx = await program.methods
+      .initializeJurisdictionConfig({ blocklist: {} })
+      .accounts({
+        admin: admin.publicKey
+      })
+      .signers([admin])
+      .rpc({
+        commitment: 'confirmed'
+      })
+    console.log('✅ Jurisdiction blocklist initialized')
+    console.log('📝 Transaction signature:', jurisdictionTx)
+
     console.log('🎨 Fractional Art Investment Platform deployed!')
     console.log('🔗 Program ID:', program.programId.toString())
     console.log('📊 Program deployed to:', provider.connection.rpcEndpoint)
@@ -0,0 +1,258 @@
+// `#[program]` emits its IDL instructions, which still call the deprecated
+// `AccountInfo::realloc`, outside the annotated module, so an allow scoped to
+// the module does not reach them. Drop this once Anchor moves to `resize`.
+#![allow(deprecated)]
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_spl::token_2022::spl_token_2022::extension::transfer_hook::TransferHookAccount;
+use anchor_spl::token_2022::spl_token_2022::extension::{
+    BaseStateWithExtensions, StateWithExtensions,
+};
+use anchor_spl::token_2022::spl_token_2022::state::Account as SplTokenAccount;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use rwa::{JurisdictionConfig, SimpleKycAccount, TransactionType};
+use spl_discriminator::SplDiscriminate;
+use spl_tlv_account_resolution::account::ExtraAccountMeta;
+use spl_tlv_account_resolution::seeds::Seed;
+use spl_tlv_account_resolution::state::ExtraAccountMetaList;
+use spl_transfer_hook_interface::instruction::ExecuteInstruction;
+
+declare_id!("8ouWSqJEg5JiLtPQXRhWD9F9Qjq2F8osKcvbDCshqpvG");
+
+/// KYC Transfer Hook
+///
+/// Token-2022 transfer hook for fractional share mints created by the `rwa`
+/// program. Every transfer must be between wallets holding a valid
+/// `SimpleKycAccount`, except primary-sale transfers out of the vault, so
+/// shares stay inside the verified investor set on secondary markets.
+///
+/// Shares the `rwa` program re-issues (burn and mint) never reach this hook:
+/// primary sales of royalty-bearing mints and the royalty payouts of
+/// `harvest_royalties` to the vault creator and the platform treasury. The
+/// `rwa` program checks the KYC of those recipients itself.
+#[program]
+pub mod kyc_transfer_hook {
+    use super::*;
+
+    /// Create the extra account list Token-2022 resolves for every transfer
+    ///
+    /// The list is fixed (rwa program, sender and receiver KYC PDAs and the
+    /// jurisdiction list), so anyone may pay to initialize it for a mint that
+    /// uses this hook.
+    pub fn initialize_extra_account_meta_list(
+        ctx: Context<InitializeExtraAccountMetaList>,
+    ) -> Result<()> {
+        let extra_account_metas = extra_account_metas()?;
+        let mut data = ctx.accounts.extra_account_meta_list.try_borrow_mut_data()?;
+        ExtraAccountMetaList::init::<ExecuteInstruction>(&mut data, &extra_account_metas)?;
+
+        msg!("Extra account metas initialized");
+        msg!("Mint: {}", ctx.accounts.mint.key());
+
+        Ok(())
+    }
+
+    /// Check sender and receiver KYC on every transfer (invoked by Token-2022)
+    #[instruction(discriminator = ExecuteInstruction::SPL_DISCRIMINATOR_SLICE)]
+    pub fn transfer_hook(ctx: Context<TransferHook>, amount: u64) -> Result<()> {
+        assert_is_transferring(&ctx.accounts.source_token.to_account_info())?;
+        let now = Clock::get()?.unix_timestamp;
+
+        // The vault holds the mint authority and releases fractions in primary sales
+        let from_vault =
+            ctx.accounts.mint.mint_authority == COption::Some(ctx.accounts.source_token.owner);
+        if !from_vault {
+            let sender_kyc = load_kyc_account(&ctx.accounts.sender_kyc)
+                .ok_or(TransferHookError::SenderNotVerified)?;
+            require!(
+                sender_kyc.is_valid() && !sender_kyc.is_expired(now),
+                TransferHookError::SenderNotVerified
+            );
+        }
+
+        let receiver_kyc = load_kyc_account(&ctx.accounts.receiver_kyc)
+            .ok_or(TransferHookError::ReceiverNotVerified)?;
+        require!(
+            receiver_kyc
+                .check_for_transaction(
+                    TransactionType::Transfer,
+                    &ctx.accounts.jurisdiction_config,
+                    now,
+                )
+                .is_none(),
+            TransferHookError::ReceiverNotVerified
+        );
+
+        msg!("KYC transfer check passed");
+        msg!("Amount: {}", amount);
+
+        Ok(())
+    }
+}
+
+/// Extra accounts appended to `Execute`, after source (0), mint (1),
+/// destination (2), owner (3) and the extra account meta list (4)
+fn extra_account_metas() -> Result<Vec<ExtraAccountMeta>> {
+    const RWA_PROGRAM_INDEX: u8 = 5;
+
+    Ok(vec![
+        // 5: rwa program, owner of the KYC accounts
+        ExtraAccountMeta::new_with_pubkey(&rwa::ID, false, false)?,
+        // 6: sender KYC, derived from the source token account owner
+        ExtraAccountMeta::new_external_pda_with_seeds(
+            RWA_PROGRAM_INDEX,
+            &[
+                Seed::Literal {
+                    bytes: b"simple_kyc".to_vec(),
+                },
+                Seed::AccountData {
+                    account_index: 0,
+                    data_index: 32,
+                    length: 32,
+                },
+            ],
+            false,
+            false,
+        )?,
+        // 7: receiver KYC, derived from the destination token account owner
+        ExtraAccountMeta::new_external_pda_with_seeds(
+            RWA_PROGRAM_INDEX,
+            &[
+                Seed::Literal {
+                    bytes: b"simple_kyc".to_vec(),
+                },
+                Seed::AccountData {
+                    account_index: 2,
+                    data_index: 32,
+                    length: 32,
+                },
+            ],
+            false,
+            false,
+        )?,
+        // 8: platform jurisdiction list
+        ExtraAccountMeta::new_external_pda_with_seeds(
+            RWA_PROGRAM_INDEX,
+            &[Seed::Literal {
+                bytes: b"jurisdiction_config".to_vec(),
+            }],
+            false,
+            false,
+        )?,
+    ])
+}
+
+/// Reject direct calls: the hook only runs inside a Token-2022 transfer
+fn assert_is_transferring(source_token: &AccountInfo) -> Result<()> {
+    let data = source_token.try_borrow_data()?;
+    let account = StateWithExtensions::<SplTokenAccount>::unpack(&data)?;
+    let extension = account.get_extension::<TransferHookAccount>()?;
+
+    require!(
+        bool::from(extension.transferring),
+        TransferHookError::NotTransferring
+    );
+
+    Ok(())
+}
+
+/// Deserialize a KYC account, or `None` if the wallet never registered
+fn load_kyc_account(kyc_account: &AccountInfo) -> Option<SimpleKycAccount> {
+    if kyc_account.owner != &rwa::ID || kyc_account.data_is_empty() {
+        return None;
+    }
+
+    let data = kyc_account.try_borrow_data().ok()?;
+    SimpleKycAccount::try_deserialize(&mut &data[..]).ok()
+}
+
+/// Account validation struct for creating a mint's extra account meta list
+#[derive(Accounts)]
+pub struct InitializeExtraAccountMetaList<'info> {
+    /// Pays for the extra account meta list
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Extra account meta list PDA, written by the handler
+    #[account(
+        init,
+        payer = payer,
+        space = ExtraAccountMetaList::size_of(extra_account_metas()?.len())?,
+        seeds = [b"extra-account-metas", mint.key().as_ref()],
+        bump
+    )]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+
+    /// Fractional share mint using this hook
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+}
+
+/// Account validation struct for the transfer hook `Execute` instruction
+#[derive(Accounts)]
+pub struct TransferHook<'info> {
+    /// Token account fractions are sent from
+    #[account(token::mint = mint)]
+    pub source_token: InterfaceAccount<'info, TokenAccount>,
+
+    /// Fractional share mint
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Token account fractions are sent to
+    #[account(token::mint = mint)]
+    pub destination_token: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Owner or delegate of the source token account
+    pub owner: UncheckedAccount<'info>,
+
+    /// CHECK: Extra account meta list PDA for this mint
+    #[account(
+        seeds = [b"extra-account-metas", mint.key().as_ref()],
+        bump
+    )]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+
+    /// CHECK: The rwa program, owner of the KYC accounts
+    #[account(address = rwa::ID)]
+    pub rwa_program: UncheckedAccount<'info>,
+
+    /// CHECK: Sender's KYC PDA; may be uninitialized for unregistered wallets
+    #[account(
+        seeds = [b"simple_kyc", source_token.owner.as_ref()],
+        bump,
+        seeds::program = rwa_program.key()
+    )]
+    pub sender_kyc: UncheckedAccount<'info>,
+
+    /// CHECK: Receiver's KYC PDA; may be uninitialized for unregistered wallets
+    #[account(
+        seeds = [b"simple_kyc", destination_token.owner.as_ref()],
+        bump,
+        seeds::program = rwa_program.key()
+    )]
+    pub receiver_kyc: UncheckedAccount<'info>,
+
+    /// Platform jurisdiction list applied to the receiver
+    #[account(
+        seeds = [b"jurisdiction_config"],
+        bump,
+        seeds::program = rwa_program.key()
+    )]
+    pub jurisdiction_config: Account<'info, JurisdictionConfig>,
+}
+
+/// Errors returned by the KYC transfer hook
+#[error_code]
+pub enum TransferHookError {
+    #[msg("Sender has not passed KYC verification")]
+    SenderNotVerified,
+
+    #[msg("Receiver has not passed KYC verification")]
+    ReceiverNotVerified,
+
+    #[msg("The transfer hook can only be invoked during a transfer")]
+    NotTransferring,
+}
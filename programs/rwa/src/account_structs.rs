@@ -1,9 +1,16 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Mint, Token, TokenAccount};
 use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::metadata::{MasterEditionAccount, Metadata, MetadataAccount};
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use anchor_spl::token_interface::{self, Token2022, TokenInterface};
 
-use crate::kyc::SimpleKycAccount;
-use crate::state::{PlatformConfig, Vault};
+use crate::kyc::{
+    EnhancedKycAccount, JurisdictionConfig, KycAttestorRegistry, KycProviderConfig, KycStatus,
+    SimpleKycAccount, TransactionType,
+};
+use crate::state::{
+    AdminRole, InvestorPosition, PlatformConfig, Vault, KYC_TRANSFER_HOOK_PROGRAM_ID,
+};
 
 /// Account validation struct for the initialize instruction
 #[derive(Accounts)]
@@ -28,7 +35,7 @@ pub struct Initialize<'info> {
 
 /// Account validation struct for the fractionalize instruction (SIMPLIFIED FOR HACKATHON)
 #[derive(Accounts)]
-#[instruction(total_fractions: u64, price_per_fraction: u64)]
+#[instruction(total_fractions: u64, price_per_fraction: u64, fractional_decimals: u8)]
 pub struct Fractionalize<'info> {
     /// The original NFT owner who wants to fractionalize their asset
     #[account(mut)]
@@ -55,12 +62,51 @@ pub struct Fractionalize<'info> {
     #[account(
         init,
         payer = creator,
-        mint::decimals = 0,
+        mint::decimals = fractional_decimals,
         mint::authority = vault,
         mint::freeze_authority = vault,
     )]
     pub fractional_token_mint: Account<'info, Mint>,
 
+    /// Metaplex metadata of the original NFT, source of the fractional token's name
+    #[account(
+        mut,
+        seeds = [
+            b"metadata",
+            token_metadata_program.key().as_ref(),
+            original_nft_mint.key().as_ref()
+        ],
+        bump,
+        seeds::program = token_metadata_program.key()
+    )]
+    pub original_nft_metadata: Box<Account<'info, MetadataAccount>>,
+
+    /// Metaplex master edition of the original NFT, proving it is a one-of-one
+    #[account(
+        seeds = [
+            b"metadata",
+            token_metadata_program.key().as_ref(),
+            original_nft_mint.key().as_ref(),
+            b"edition"
+        ],
+        bump,
+        seeds::program = token_metadata_program.key()
+    )]
+    pub original_nft_master_edition: Box<Account<'info, MasterEditionAccount>>,
+
+    /// CHECK: Metaplex metadata of the fractional mint, created by the token metadata program
+    #[account(
+        mut,
+        seeds = [
+            b"metadata",
+            token_metadata_program.key().as_ref(),
+            fractional_token_mint.key().as_ref()
+        ],
+        bump,
+        seeds::program = token_metadata_program.key()
+    )]
+    pub fractional_token_metadata: UncheckedAccount<'info>,
+
     /// Fractional token account for the vault
     #[account(
         init_if_needed,
@@ -91,18 +137,61 @@ pub struct Fractionalize<'info> {
     )]
     pub vault_nft_account: Account<'info, TokenAccount>,
 
+    /// CHECK: Token record of the creator's NFT token account, validated by the
+    /// token metadata program (programmable NFTs only)
+    #[account(mut)]
+    pub creator_nft_token_record: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Token record of the vault's NFT token account, created by the
+    /// token metadata program (programmable NFTs only)
+    #[account(mut)]
+    pub vault_nft_token_record: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Rule set of the programmable NFT, validated by the token metadata program
+    pub authorization_rules: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Metaplex authorization rules program, required alongside a rule set
+    pub authorization_rules_program: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Instructions sysvar, read by the token metadata program (programmable NFTs only)
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub sysvar_instructions: Option<UncheckedAccount<'info>>,
+
+    /// Mint buyers will pay with, must be on the platform allowlist
+    #[account(
+        constraint = platform_config.accepts_payment_mint(&payment_mint.key()) @ crate::error::RwaError::PaymentMintNotAccepted
+    )]
+    pub payment_mint: Account<'info, Mint>,
+
     /// Creator's payment account for receiving proceeds from sales
     #[account(
-        constraint = creator_payment_account.owner == creator.key() @ crate::error::RwaError::OwnerMismatch
+        constraint = creator_payment_account.owner == creator.key() @ crate::error::RwaError::OwnerMismatch,
+        constraint = creator_payment_account.mint == payment_mint.key() @ crate::error::RwaError::InvalidPaymentMint
     )]
     pub creator_payment_account: Account<'info, TokenAccount>,
 
+    /// CHECK: Vault's escrow for the payment mint, created by the handler for
+    /// soft-capped sales (validated by the associated token program)
+    #[account(mut)]
+    pub escrow_payment_account: Option<UncheckedAccount<'info>>,
+
+    /// Platform configuration holding the payment mint allowlist
+    #[account(
+        seeds = [b"platform_config"],
+        bump,
+        constraint = platform_config.is_active @ crate::error::RwaError::PlatformNotActive
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
     /// Token program for standard SPL tokens
     pub token_program: Program<'info, Token>,
 
     /// Associated token program for creating ATAs
     pub associated_token_program: Program<'info, AssociatedToken>,
 
+    /// Metaplex token metadata program
+    pub token_metadata_program: Program<'info, Metadata>,
+
     /// System program for account creation
     pub system_program: Program<'info, System>,
 
@@ -110,6 +199,164 @@ pub struct Fractionalize<'info> {
     pub rent: Sysvar<'info, Rent>,
 }
 
+/// Account validation struct for fractionalizing into a Token-2022 mint
+///
+/// The fractional mint carries a transfer hook pointing at the
+/// `kyc-transfer-hook` program, so every transfer is KYC-checked, and
+/// optionally a transfer fee charging the vault's royalty on secondary trades.
+/// The mint and the vault's token account are created by the handler, as the
+/// transfer fee extension cannot be configured through `init` constraints.
+#[derive(Accounts)]
+#[instruction(total_fractions: u64, price_per_fraction: u64, fractional_decimals: u8)]
+pub struct FractionalizeToken2022<'info> {
+    /// The original NFT owner who wants to fractionalize their asset
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    /// The NFT mint being fractionalized
+    #[account(
+        constraint = original_nft_mint.supply == 1 @ crate::error::RwaError::NotAnNft,
+        constraint = original_nft_mint.decimals == 0 @ crate::error::RwaError::NotAnNft
+    )]
+    pub original_nft_mint: Box<Account<'info, Mint>>,
+
+    /// Vault account that will store the NFT and manage fractional tokens
+    #[account(
+        init,
+        payer = creator,
+        space = Vault::LEN,
+        seeds = [b"vault", original_nft_mint.key().as_ref()],
+        bump
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    /// Metaplex metadata of the original NFT, checked for an approved collection
+    #[account(
+        mut,
+        seeds = [
+            b"metadata",
+            token_metadata_program.key().as_ref(),
+            original_nft_mint.key().as_ref()
+        ],
+        bump,
+        seeds::program = token_metadata_program.key()
+    )]
+    pub original_nft_metadata: Box<Account<'info, MetadataAccount>>,
+
+    /// Metaplex master edition of the original NFT, proving it is a one-of-one
+    #[account(
+        seeds = [
+            b"metadata",
+            token_metadata_program.key().as_ref(),
+            original_nft_mint.key().as_ref(),
+            b"edition"
+        ],
+        bump,
+        seeds::program = token_metadata_program.key()
+    )]
+    pub original_nft_master_edition: Box<Account<'info, MasterEditionAccount>>,
+
+    /// Fractional token mint keypair, created by the handler (TOKEN-2022 WITH TRANSFER HOOK)
+    #[account(mut)]
+    pub fractional_token_mint: Signer<'info>,
+
+    /// CHECK: Vault's associated token account for the fractional mint, created by the handler
+    #[account(
+        mut,
+        seeds = [
+            vault.key().as_ref(),
+            fractional_token_program.key().as_ref(),
+            fractional_token_mint.key().as_ref()
+        ],
+        bump,
+        seeds::program = associated_token_program.key()
+    )]
+    pub vault_fractional_account: UncheckedAccount<'info>,
+
+    /// Creator's NFT token account
+    #[account(
+        mut,
+        constraint = creator_nft_account.mint == original_nft_mint.key() @ crate::error::RwaError::InvalidNftMint,
+        constraint = creator_nft_account.owner == creator.key() @ crate::error::RwaError::OwnerMismatch,
+        constraint = creator_nft_account.amount == 1 @ crate::error::RwaError::NotAnNft
+    )]
+    pub creator_nft_account: Box<Account<'info, TokenAccount>>,
+
+    /// Vault's NFT token account
+    #[account(
+        init_if_needed,
+        payer = creator,
+        associated_token::mint = original_nft_mint,
+        associated_token::authority = vault,
+    )]
+    pub vault_nft_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Token record of the creator's NFT token account, validated by the
+    /// token metadata program (programmable NFTs only)
+    #[account(mut)]
+    pub creator_nft_token_record: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Token record of the vault's NFT token account, created by the
+    /// token metadata program (programmable NFTs only)
+    #[account(mut)]
+    pub vault_nft_token_record: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Rule set of the programmable NFT, validated by the token metadata program
+    pub authorization_rules: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Metaplex authorization rules program, required alongside a rule set
+    pub authorization_rules_program: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Instructions sysvar, read by the token metadata program (programmable NFTs only)
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub sysvar_instructions: Option<UncheckedAccount<'info>>,
+
+    /// Mint buyers will pay with, must be on the platform allowlist
+    #[account(
+        constraint = platform_config.accepts_payment_mint(&payment_mint.key()) @ crate::error::RwaError::PaymentMintNotAccepted
+    )]
+    pub payment_mint: Box<Account<'info, Mint>>,
+
+    /// Creator's payment account for receiving proceeds from sales
+    #[account(
+        constraint = creator_payment_account.owner == creator.key() @ crate::error::RwaError::OwnerMismatch,
+        constraint = creator_payment_account.mint == payment_mint.key() @ crate::error::RwaError::InvalidPaymentMint
+    )]
+    pub creator_payment_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Vault's escrow for the payment mint, created by the handler for
+    /// soft-capped sales (validated by the associated token program)
+    #[account(mut)]
+    pub escrow_payment_account: Option<UncheckedAccount<'info>>,
+
+    /// Platform configuration holding the payment mint allowlist
+    #[account(
+        seeds = [b"platform_config"],
+        bump,
+        constraint = platform_config.is_active @ crate::error::RwaError::PlatformNotActive
+    )]
+    pub platform_config: Box<Account<'info, PlatformConfig>>,
+
+    /// CHECK: The KYC transfer hook program attached to the fractional mint
+    #[account(address = KYC_TRANSFER_HOOK_PROGRAM_ID)]
+    pub transfer_hook_program: UncheckedAccount<'info>,
+
+    /// Token program for the original NFT
+    pub token_program: Program<'info, Token>,
+
+    /// Token-2022 program for the fractional mint
+    pub fractional_token_program: Program<'info, Token2022>,
+
+    /// Associated token program for creating ATAs
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    /// Metaplex token metadata program
+    pub token_metadata_program: Program<'info, Metadata>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+}
+
 /// Account validation struct for the buy_fractions instruction (SIMPLIFIED)
 #[derive(Accounts)]
 #[instruction(num_fractions: u64)]
@@ -119,12 +366,17 @@ pub struct BuyFractions<'info> {
     pub buyer: Signer<'info>,
 
     /// The vault containing the fractionalized NFT
-    #[account(mut)]
+    #[account(
+        mut,
+        has_one = fractional_token_mint @ crate::error::RwaError::InvalidFractionalMint,
+        has_one = fractional_token_program @ crate::error::RwaError::InvalidFractionalTokenProgram,
+        constraint = !vault.is_halted @ crate::error::RwaError::VaultHalted
+    )]
     pub vault: Account<'info, Vault>,
 
     /// Fractional token mint
     #[account(mut)]
-    pub fractional_token_mint: Account<'info, Mint>,
+    pub fractional_token_mint: InterfaceAccount<'info, token_interface::Mint>,
 
     /// Buyer's fractional token account
     #[account(
@@ -132,38 +384,92 @@ pub struct BuyFractions<'info> {
         payer = buyer,
         associated_token::mint = fractional_token_mint,
         associated_token::authority = buyer,
+        associated_token::token_program = fractional_token_program,
     )]
-    pub buyer_fractional_account: Account<'info, TokenAccount>,
+    pub buyer_fractional_account: InterfaceAccount<'info, token_interface::TokenAccount>,
 
     /// Vault's fractional token account
-    #[account(mut)]
-    pub vault_fractional_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = fractional_token_mint,
+        associated_token::authority = vault,
+        associated_token::token_program = fractional_token_program,
+    )]
+    pub vault_fractional_account: InterfaceAccount<'info, token_interface::TokenAccount>,
 
     /// Buyer's payment token account (USDC)
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = buyer_payment_account.mint == vault.payment_mint @ crate::error::RwaError::InvalidPaymentMint
+    )]
     pub buyer_payment_account: Account<'info, TokenAccount>,
 
     /// Creator's payment token account for receiving proceeds
     #[account(
         mut,
-        constraint = creator_payment_account.key() == vault.creator_payment_account @ crate::error::RwaError::IncorrectPaymentAccount
+        constraint = creator_payment_account.key() == vault.creator_payment_account @ crate::error::RwaError::IncorrectPaymentAccount,
+        constraint = creator_payment_account.mint == vault.payment_mint @ crate::error::RwaError::InvalidPaymentMint
     )]
     pub creator_payment_account: Account<'info, TokenAccount>,
 
+    /// Vault's escrow receiving the payment of soft-capped sales
+    #[account(
+        mut,
+        constraint = escrow_payment_account.owner == vault.key() @ crate::error::RwaError::InvalidEscrowAccount,
+        constraint = escrow_payment_account.mint == vault.payment_mint @ crate::error::RwaError::InvalidPaymentMint
+    )]
+    pub escrow_payment_account: Option<Box<Account<'info, TokenAccount>>>,
+
     /// Buyer's KYC account for compliance verification
     #[account(
         constraint = kyc_account.user == buyer.key(),
-        constraint = kyc_account.is_valid() @ crate::error::RwaError::KycNotVerified
+        constraint = kyc_account.status != KycStatus::Suspended @ crate::error::RwaError::KycSuspended,
+        constraint = kyc_account.status != KycStatus::Revoked @ crate::error::RwaError::KycRevoked,
+        constraint = kyc_account.is_valid() @ crate::error::RwaError::KycNotVerified,
+        constraint = !kyc_account.is_expired(Clock::get()?.unix_timestamp) @ crate::error::RwaError::KycExpired
     )]
     pub kyc_account: Account<'info, SimpleKycAccount>,
 
-    /// Platform authority for payment distribution
-    /// CHECK: This is validated by the platform config
-    pub platform_authority: UncheckedAccount<'info>,
+    /// Platform configuration holding the fee ratio, treasury and investment limits
+    #[account(
+        seeds = [b"platform_config"],
+        bump,
+        constraint = platform_config.is_active @ crate::error::RwaError::PlatformNotActive
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    /// Jurisdiction list consulted against the buyer's country
+    #[account(
+        seeds = [b"jurisdiction_config"],
+        bump,
+        constraint = jurisdiction_config.is_allowed(kyc_account.country.as_ref()) @ crate::error::RwaError::JurisdictionRestricted
+    )]
+    pub jurisdiction_config: Account<'info, JurisdictionConfig>,
 
-    /// Token program for standard SPL tokens
+    /// Buyer's cumulative position in this vault
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = InvestorPosition::LEN,
+        seeds = [b"position", vault.key().as_ref(), buyer.key().as_ref()],
+        bump
+    )]
+    pub investor_position: Account<'info, InvestorPosition>,
+
+    /// Platform treasury token account receiving the platform fee
+    #[account(
+        mut,
+        constraint = treasury_payment_account.owner == platform_config.treasury @ crate::error::RwaError::InvalidTreasuryAccount,
+        constraint = treasury_payment_account.mint == vault.payment_mint @ crate::error::RwaError::InvalidPaymentMint
+    )]
+    pub treasury_payment_account: Account<'info, TokenAccount>,
+
+    /// Token program for the payment mint
     pub token_program: Program<'info, Token>,
 
+    /// Token program owning the fractional mint (SPL Token or Token-2022)
+    pub fractional_token_program: Interface<'info, TokenInterface>,
+
     /// Associated token program for creating ATAs
     pub associated_token_program: Program<'info, AssociatedToken>,
 
@@ -171,113 +477,1285 @@ pub struct BuyFractions<'info> {
     pub system_program: Program<'info, System>,
 }
 
-/// Account validation struct for the redeem instruction (SIMPLIFIED)
+/// Account validation struct for closing a vault's primary sale
+///
+/// Permissionless: anyone may close a sale once its window lapses or it sells out.
 #[derive(Accounts)]
-pub struct Redeem<'info> {
-    /// The user redeeming the NFT (must own all fractional tokens)
-    #[account(mut)]
-    pub redeemer: Signer<'info>,
-
-    /// The vault containing the NFT
-    #[account(mut)]
+pub struct CloseSale<'info> {
+    /// The vault whose sale is closed
+    #[account(
+        mut,
+        constraint = vault.is_sale_active @ crate::error::RwaError::SaleNotActive,
+        constraint = !vault.is_halted @ crate::error::RwaError::VaultHalted
+    )]
     pub vault: Account<'info, Vault>,
 
-    /// Original NFT mint
-    /// CHECK: This is validated by the vault account
-    pub original_nft_mint: UncheckedAccount<'info>,
-
-    /// Fractional token mint
-    #[account(mut)]
-    pub fractional_token_mint: Account<'info, Mint>,
-
-    /// Redeemer's fractional token account
+    /// Platform configuration, checked for an active platform
     #[account(
-        mut,
-        constraint = redeemer_fractional_account.mint == fractional_token_mint.key() @ crate::error::RwaError::InvalidFractionalMint,
-        constraint = redeemer_fractional_account.owner == redeemer.key() @ crate::error::RwaError::OwnerMismatch
+        seeds = [b"platform_config"],
+        bump,
+        constraint = platform_config.is_active @ crate::error::RwaError::PlatformNotActive
     )]
-    pub redeemer_fractional_account: Account<'info, TokenAccount>,
+    pub platform_config: Account<'info, PlatformConfig>,
+}
 
-    /// Vault's NFT token account
-    #[account(mut)]
-    pub vault_nft_account: Account<'info, TokenAccount>,
+/// Account validation struct for the creator repricing an active sale
+#[derive(Accounts)]
+pub struct UpdatePrice<'info> {
+    /// The vault creator
+    pub creator: Signer<'info>,
 
-    /// Redeemer's NFT token account
+    /// The vault to reprice
     #[account(
-        init_if_needed,
-        payer = redeemer,
-        associated_token::mint = original_nft_mint,
-        associated_token::authority = redeemer,
+        mut,
+        has_one = creator @ crate::error::RwaError::OwnerMismatch,
+        constraint = vault.is_sale_active @ crate::error::RwaError::SaleNotActive,
+        constraint = !vault.is_halted @ crate::error::RwaError::VaultHalted
     )]
-    pub redeemer_nft_account: Account<'info, TokenAccount>,
+    pub vault: Account<'info, Vault>,
 
-    /// Redeemer's KYC account for compliance verification
+    /// Platform configuration, checked for an active platform
     #[account(
-        constraint = kyc_account.user == redeemer.key(),
-        constraint = kyc_account.is_valid() @ crate::error::RwaError::KycNotVerified
+        seeds = [b"platform_config"],
+        bump,
+        constraint = platform_config.is_active @ crate::error::RwaError::PlatformNotActive
     )]
-    pub kyc_account: Account<'info, SimpleKycAccount>,
+    pub platform_config: Account<'info, PlatformConfig>,
+}
 
-    /// Token program for standard SPL tokens
-    pub token_program: Program<'info, Token>,
+/// Account validation struct for claiming the escrowed proceeds of a soft-capped sale
+#[derive(Accounts)]
+pub struct ClaimProceeds<'info> {
+    /// The vault creator claiming the proceeds
+    pub creator: Signer<'info>,
 
-    /// Associated token program for creating ATAs
-    pub associated_token_program: Program<'info, AssociatedToken>,
+    /// The vault whose sale met its soft cap or cleared its auction
+    #[account(
+        mut,
+        has_one = creator @ crate::error::RwaError::OwnerMismatch,
+        has_one = creator_payment_account @ crate::error::RwaError::IncorrectPaymentAccount,
+        constraint = vault.uses_escrow() @ crate::error::RwaError::EscrowNotEnabled,
+        constraint = !vault.is_halted @ crate::error::RwaError::VaultHalted
+    )]
+    pub vault: Account<'info, Vault>,
 
-    /// System program for account creation
-    pub system_program: Program<'info, System>,
-}
+    /// Vault's escrow holding the sale proceeds
+    #[account(
+        mut,
+        constraint = escrow_payment_account.owner == vault.key() @ crate::error::RwaError::InvalidEscrowAccount,
+        constraint = escrow_payment_account.mint == vault.payment_mint @ crate::error::RwaError::InvalidPaymentMint
+    )]
+    pub escrow_payment_account: Account<'info, TokenAccount>,
 
-/// Account validation struct for the register_kyc instruction
-#[derive(Accounts)]
-pub struct RegisterKyc<'info> {
-    /// The user registering for KYC verification
+    /// Creator's payment token account for receiving proceeds
     #[account(mut)]
-    pub user: Signer<'info>,
+    pub creator_payment_account: Account<'info, TokenAccount>,
 
-    /// KYC account to be created for the user
+    /// Platform treasury token account receiving the platform fee
     #[account(
-        init,
-        payer = user,
-        space = SimpleKycAccount::LEN,
-        seeds = [b"simple_kyc", user.key().as_ref()],
-        bump
+        mut,
+        constraint = treasury_payment_account.owner == platform_config.treasury @ crate::error::RwaError::InvalidTreasuryAccount,
+        constraint = treasury_payment_account.mint == vault.payment_mint @ crate::error::RwaError::InvalidPaymentMint
     )]
-    pub kyc_account: Account<'info, SimpleKycAccount>,
+    pub treasury_payment_account: Account<'info, TokenAccount>,
 
-    /// System program for account creation
-    pub system_program: Program<'info, System>,
+    /// Platform configuration holding the fee ratio and treasury
+    #[account(
+        seeds = [b"platform_config"],
+        bump,
+        constraint = platform_config.is_active @ crate::error::RwaError::PlatformNotActive
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    /// Token program for the payment mint
+    pub token_program: Program<'info, Token>,
 }
 
-/// Account validation struct for the verify_kyc instruction
+/// Account validation struct for refunding a buyer of a sale that missed its soft cap
 #[derive(Accounts)]
-pub struct VerifyKyc<'info> {
-    /// The admin verifying the KYC status
-    #[account(mut)]
-    pub admin: Signer<'info>,
+pub struct RefundFractions<'info> {
+    /// The buyer burning their fractions for a refund
+    pub buyer: Signer<'info>,
 
-    /// The KYC account to be verified
-    #[account(mut)]
-    pub kyc_account: Account<'info, SimpleKycAccount>,
+    /// The vault whose sale ended below its soft cap
+    #[account(
+        mut,
+        has_one = fractional_token_mint @ crate::error::RwaError::InvalidFractionalMint,
+        has_one = fractional_token_program @ crate::error::RwaError::InvalidFractionalTokenProgram,
+        constraint = !vault.is_halted @ crate::error::RwaError::VaultHalted
+    )]
+    pub vault: Account<'info, Vault>,
 
-    /// Platform configuration for admin authority validation
+    /// Platform configuration, checked for an active platform
     #[account(
-        constraint = platform_config.admin == admin.key() @ crate::error::RwaError::UnauthorizedAccess
+        seeds = [b"platform_config"],
+        bump,
+        constraint = platform_config.is_active @ crate::error::RwaError::PlatformNotActive
     )]
     pub platform_config: Account<'info, PlatformConfig>,
-}
 
-/// Account validation struct for updating platform configuration
-#[derive(Accounts)]
-pub struct UpdatePlatformConfig<'info> {
-    /// The platform admin updating configuration
+    /// Fractional token mint
     #[account(mut)]
-    pub admin: Signer<'info>,
+    pub fractional_token_mint: InterfaceAccount<'info, token_interface::Mint>,
 
-    /// Platform configuration account
+    /// Buyer's fractional token account the purchased fractions are burned from
     #[account(
         mut,
-        constraint = platform_config.admin == admin.key() @ crate::error::RwaError::UnauthorizedAccess
+        constraint = buyer_fractional_account.mint == fractional_token_mint.key() @ crate::error::RwaError::InvalidFractionalMint,
+        constraint = buyer_fractional_account.owner == buyer.key() @ crate::error::RwaError::OwnerMismatch
+    )]
+    pub buyer_fractional_account: InterfaceAccount<'info, token_interface::TokenAccount>,
+
+    /// Buyer's position recording the fractions bought and the amount paid
+    #[account(
+        mut,
+        seeds = [b"position", vault.key().as_ref(), buyer.key().as_ref()],
+        bump
+    )]
+    pub investor_position: Account<'info, InvestorPosition>,
+
+    /// Vault's escrow holding the sale proceeds
+    #[account(
+        mut,
+        constraint = escrow_payment_account.owner == vault.key() @ crate::error::RwaError::InvalidEscrowAccount,
+        constraint = escrow_payment_account.mint == vault.payment_mint @ crate::error::RwaError::InvalidPaymentMint
+    )]
+    pub escrow_payment_account: Account<'info, TokenAccount>,
+
+    /// Buyer's payment token account receiving the refund
+    #[account(
+        mut,
+        constraint = buyer_payment_account.mint == vault.payment_mint @ crate::error::RwaError::InvalidPaymentMint
+    )]
+    pub buyer_payment_account: Account<'info, TokenAccount>,
+
+    /// Token program for the payment mint
+    pub token_program: Program<'info, Token>,
+
+    /// Token program owning the fractional mint (SPL Token or Token-2022)
+    pub fractional_token_program: Interface<'info, TokenInterface>,
+}
+
+/// Account validation struct for an early buyer claiming the difference to
+/// an auction's clearing price
+#[derive(Accounts)]
+pub struct ClaimAuctionRefund<'info> {
+    /// The buyer claiming the refund
+    pub buyer: Signer<'info>,
+
+    /// The vault whose auction closed
+    #[account(
+        constraint = vault.has_uniform_clearing_price() @ crate::error::RwaError::ClearingPriceNotEnabled,
+        constraint = !vault.is_halted @ crate::error::RwaError::VaultHalted
+    )]
+    pub vault: Account<'info, Vault>,
+
+    /// Platform configuration, checked for an active platform
+    #[account(
+        seeds = [b"platform_config"],
+        bump,
+        constraint = platform_config.is_active @ crate::error::RwaError::PlatformNotActive
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    /// Buyer's position recording the fractions bought and the amount paid
+    #[account(
+        mut,
+        seeds = [b"position", vault.key().as_ref(), buyer.key().as_ref()],
+        bump
+    )]
+    pub investor_position: Account<'info, InvestorPosition>,
+
+    /// Vault's escrow holding the auction proceeds
+    #[account(
+        mut,
+        constraint = escrow_payment_account.owner == vault.key() @ crate::error::RwaError::InvalidEscrowAccount,
+        constraint = escrow_payment_account.mint == vault.payment_mint @ crate::error::RwaError::InvalidPaymentMint
+    )]
+    pub escrow_payment_account: Account<'info, TokenAccount>,
+
+    /// Buyer's payment token account receiving the refund
+    #[account(
+        mut,
+        constraint = buyer_payment_account.mint == vault.payment_mint @ crate::error::RwaError::InvalidPaymentMint
+    )]
+    pub buyer_payment_account: Account<'info, TokenAccount>,
+
+    /// Token program for the payment mint
+    pub token_program: Program<'info, Token>,
+}
+
+/// Account validation struct for the creator taking back the NFT of a sale
+/// that missed its soft cap
+#[derive(Accounts)]
+pub struct ReclaimNft<'info> {
+    /// The vault creator reclaiming the NFT
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    /// The vault whose sale ended below its soft cap
+    #[account(
+        mut,
+        has_one = creator @ crate::error::RwaError::OwnerMismatch,
+        has_one = original_nft_mint @ crate::error::RwaError::InvalidNftMint,
+        constraint = !vault.is_halted @ crate::error::RwaError::VaultHalted
+    )]
+    pub vault: Account<'info, Vault>,
+
+    /// Platform configuration, checked for an active platform
+    #[account(
+        seeds = [b"platform_config"],
+        bump,
+        constraint = platform_config.is_active @ crate::error::RwaError::PlatformNotActive
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    /// Original NFT mint
+    pub original_nft_mint: Account<'info, Mint>,
+
+    /// Vault's NFT token account
+    #[account(
+        mut,
+        associated_token::mint = original_nft_mint,
+        associated_token::authority = vault,
+    )]
+    pub vault_nft_account: Account<'info, TokenAccount>,
+
+    /// Creator's NFT token account
+    #[account(
+        init_if_needed,
+        payer = creator,
+        associated_token::mint = original_nft_mint,
+        associated_token::authority = creator,
+    )]
+    pub creator_nft_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Metaplex metadata of the original NFT, validated by the token
+    /// metadata program (programmable NFTs only)
+    #[account(mut)]
+    pub original_nft_metadata: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Metaplex master edition of the original NFT, validated by the
+    /// token metadata program (programmable NFTs only)
+    pub original_nft_master_edition: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Token record of the creator's NFT token account, created by the
+    /// token metadata program (programmable NFTs only)
+    #[account(mut)]
+    pub creator_nft_token_record: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Token record of the vault's NFT token account, validated by the
+    /// token metadata program (programmable NFTs only)
+    #[account(mut)]
+    pub vault_nft_token_record: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Rule set of the programmable NFT, validated by the token metadata program
+    pub authorization_rules: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Metaplex authorization rules program, required alongside a rule set
+    pub authorization_rules_program: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Instructions sysvar, read by the token metadata program (programmable NFTs only)
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub sysvar_instructions: Option<UncheckedAccount<'info>>,
+
+    /// Token program for the original NFT
+    pub token_program: Program<'info, Token>,
+
+    /// Associated token program for creating ATAs
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    /// Metaplex token metadata program (programmable NFTs only)
+    pub token_metadata_program: Option<Program<'info, Metadata>>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+}
+
+/// Account validation struct for the creator cancelling a vault before any
+/// fractions are sold
+#[derive(Accounts)]
+pub struct CancelVault<'info> {
+    /// The vault creator cancelling the vault and receiving the rent
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    /// The vault to cancel, closed to the creator
+    #[account(
+        mut,
+        close = creator,
+        has_one = creator @ crate::error::RwaError::OwnerMismatch,
+        has_one = original_nft_mint @ crate::error::RwaError::InvalidNftMint,
+        has_one = fractional_token_mint @ crate::error::RwaError::InvalidFractionalMint,
+        has_one = fractional_token_program @ crate::error::RwaError::InvalidFractionalTokenProgram,
+        has_one = creator_payment_account @ crate::error::RwaError::IncorrectPaymentAccount,
+        constraint = vault.fractions_sold == 0 @ crate::error::RwaError::FractionsAlreadySold,
+        constraint = vault.amount_raised == 0 @ crate::error::RwaError::ProceedsNotRefunded,
+        constraint = !vault.is_halted @ crate::error::RwaError::VaultHalted
+    )]
+    pub vault: Account<'info, Vault>,
+
+    /// Platform configuration, checked for an active platform
+    #[account(
+        seeds = [b"platform_config"],
+        bump,
+        constraint = platform_config.is_active @ crate::error::RwaError::PlatformNotActive
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    /// Original NFT mint
+    pub original_nft_mint: Box<Account<'info, Mint>>,
+
+    /// Fractional token mint
+    #[account(mut)]
+    pub fractional_token_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+
+    /// Vault's fractional token account holding the unsold fractions
+    #[account(
+        mut,
+        associated_token::mint = fractional_token_mint,
+        associated_token::authority = vault,
+        associated_token::token_program = fractional_token_program,
+    )]
+    pub vault_fractional_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    /// Vault's NFT token account, recreated if the token metadata program
+    /// closed it when a pNFT was reclaimed
+    #[account(
+        init_if_needed,
+        payer = creator,
+        associated_token::mint = original_nft_mint,
+        associated_token::authority = vault,
+    )]
+    pub vault_nft_account: Box<Account<'info, TokenAccount>>,
+
+    /// Creator's NFT token account
+    #[account(
+        init_if_needed,
+        payer = creator,
+        associated_token::mint = original_nft_mint,
+        associated_token::authority = creator,
+    )]
+    pub creator_nft_account: Box<Account<'info, TokenAccount>>,
+
+    /// Vault's escrow of a soft-capped sale, closed alongside the vault
+    #[account(
+        mut,
+        constraint = escrow_payment_account.owner == vault.key() @ crate::error::RwaError::InvalidEscrowAccount,
+        constraint = escrow_payment_account.mint == vault.payment_mint @ crate::error::RwaError::InvalidPaymentMint
+    )]
+    pub escrow_payment_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// Creator's payment token account, receiving any stray escrow balance
+    #[account(mut)]
+    pub creator_payment_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Metaplex metadata of the original NFT, validated by the token
+    /// metadata program (programmable NFTs only)
+    #[account(mut)]
+    pub original_nft_metadata: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Metaplex master edition of the original NFT, validated by the
+    /// token metadata program (programmable NFTs only)
+    pub original_nft_master_edition: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Token record of the creator's NFT token account, created by the
+    /// token metadata program (programmable NFTs only)
+    #[account(mut)]
+    pub creator_nft_token_record: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Token record of the vault's NFT token account, validated by the
+    /// token metadata program (programmable NFTs only)
+    #[account(mut)]
+    pub vault_nft_token_record: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Rule set of the programmable NFT, validated by the token metadata program
+    pub authorization_rules: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Metaplex authorization rules program, required alongside a rule set
+    pub authorization_rules_program: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Instructions sysvar, read by the token metadata program (programmable NFTs only)
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub sysvar_instructions: Option<UncheckedAccount<'info>>,
+
+    /// Token program for the original NFT and the payment mint
+    pub token_program: Program<'info, Token>,
+
+    /// Token program owning the fractional mint (SPL Token or Token-2022)
+    pub fractional_token_program: Interface<'info, TokenInterface>,
+
+    /// Associated token program for creating ATAs
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    /// Metaplex token metadata program (programmable NFTs only)
+    pub token_metadata_program: Option<Program<'info, Metadata>>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+}
+
+/// Account validation struct for the redeem instruction (SIMPLIFIED)
+#[derive(Accounts)]
+pub struct Redeem<'info> {
+    /// The user redeeming the NFT (must own all fractional tokens)
+    #[account(mut)]
+    pub redeemer: Signer<'info>,
+
+    /// The vault containing the NFT
+    #[account(
+        mut,
+        has_one = original_nft_mint @ crate::error::RwaError::InvalidNftMint,
+        has_one = fractional_token_mint @ crate::error::RwaError::InvalidFractionalMint,
+        has_one = fractional_token_program @ crate::error::RwaError::InvalidFractionalTokenProgram,
+        constraint = !vault.is_halted @ crate::error::RwaError::VaultHalted
+    )]
+    pub vault: Account<'info, Vault>,
+
+    /// Original NFT mint
+    /// CHECK: Must be the vault's original NFT mint (`has_one` on the vault)
+    pub original_nft_mint: UncheckedAccount<'info>,
+
+    /// Fractional token mint
+    #[account(mut)]
+    pub fractional_token_mint: InterfaceAccount<'info, token_interface::Mint>,
+
+    /// Redeemer's fractional token account
+    #[account(
+        mut,
+        constraint = redeemer_fractional_account.mint == fractional_token_mint.key() @ crate::error::RwaError::InvalidFractionalMint,
+        constraint = redeemer_fractional_account.owner == redeemer.key() @ crate::error::RwaError::OwnerMismatch
+    )]
+    pub redeemer_fractional_account: InterfaceAccount<'info, token_interface::TokenAccount>,
+
+    /// Vault's fractional token account, collects the royalties withheld from
+    /// the redeemer's shares before they are burned
+    #[account(
+        mut,
+        associated_token::mint = fractional_token_mint,
+        associated_token::authority = vault,
+        associated_token::token_program = fractional_token_program,
+    )]
+    pub vault_fractional_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    /// Vault's NFT token account
+    #[account(
+        mut,
+        associated_token::mint = original_nft_mint,
+        associated_token::authority = vault,
+    )]
+    pub vault_nft_account: Account<'info, TokenAccount>,
+
+    /// Redeemer's NFT token account
+    #[account(
+        init_if_needed,
+        payer = redeemer,
+        associated_token::mint = original_nft_mint,
+        associated_token::authority = redeemer,
+    )]
+    pub redeemer_nft_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Metaplex metadata of the original NFT, validated by the token
+    /// metadata program (programmable NFTs only)
+    #[account(mut)]
+    pub original_nft_metadata: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Metaplex master edition of the original NFT, validated by the
+    /// token metadata program (programmable NFTs only)
+    pub original_nft_master_edition: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Token record of the redeemer's NFT token account, created by the
+    /// token metadata program (programmable NFTs only)
+    #[account(mut)]
+    pub redeemer_nft_token_record: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Token record of the vault's NFT token account, validated by the
+    /// token metadata program (programmable NFTs only)
+    #[account(mut)]
+    pub vault_nft_token_record: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Rule set of the programmable NFT, validated by the token metadata program
+    pub authorization_rules: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Metaplex authorization rules program, required alongside a rule set
+    pub authorization_rules_program: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Instructions sysvar, read by the token metadata program (programmable NFTs only)
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub sysvar_instructions: Option<UncheckedAccount<'info>>,
+
+    /// Redeemer's KYC account for compliance verification
+    #[account(
+        constraint = kyc_account.user == redeemer.key(),
+        constraint = kyc_account.status != KycStatus::Suspended @ crate::error::RwaError::KycSuspended,
+        constraint = kyc_account.status != KycStatus::Revoked @ crate::error::RwaError::KycRevoked,
+        constraint = kyc_account.is_valid() @ crate::error::RwaError::KycNotVerified,
+        constraint = !kyc_account.is_expired(Clock::get()?.unix_timestamp) @ crate::error::RwaError::KycExpired
+    )]
+    pub kyc_account: Account<'info, SimpleKycAccount>,
+
+    /// Platform configuration for the global kill switch
+    #[account(
+        seeds = [b"platform_config"],
+        bump,
+        constraint = platform_config.is_active @ crate::error::RwaError::PlatformNotActive
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    /// Token program for the original NFT
+    pub token_program: Program<'info, Token>,
+
+    /// Token program owning the fractional mint (SPL Token or Token-2022)
+    pub fractional_token_program: Interface<'info, TokenInterface>,
+
+    /// Associated token program for creating ATAs
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    /// Metaplex token metadata program (programmable NFTs only)
+    pub token_metadata_program: Option<Program<'info, Metadata>>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+}
+
+/// Account validation struct for the register_kyc instruction
+#[derive(Accounts)]
+pub struct RegisterKyc<'info> {
+    /// The user registering for KYC verification
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// KYC account to be created for the user
+    #[account(
+        init,
+        payer = user,
+        space = SimpleKycAccount::LEN,
+        seeds = [b"simple_kyc", user.key().as_ref()],
+        bump
+    )]
+    pub kyc_account: Account<'info, SimpleKycAccount>,
+
+    /// Platform configuration for the global kill switch
+    #[account(
+        seeds = [b"platform_config"],
+        bump,
+        constraint = platform_config.is_active @ crate::error::RwaError::PlatformNotActive
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+}
+
+/// Account validation struct for creating the jurisdiction list (super admin only)
+#[derive(Accounts)]
+pub struct InitializeJurisdictionConfig<'info> {
+    /// The super admin creating the list
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// Platform configuration for super admin validation
+    #[account(
+        seeds = [b"platform_config"],
+        bump,
+        constraint = platform_config.has_role(&admin.key(), AdminRole::SuperAdmin) @ crate::error::RwaError::UnauthorizedAccess
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    /// Jurisdiction list to be created
+    #[account(
+        init,
+        payer = admin,
+        space = JurisdictionConfig::LEN,
+        seeds = [b"jurisdiction_config"],
+        bump
+    )]
+    pub jurisdiction_config: Account<'info, JurisdictionConfig>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+}
+
+/// Account validation struct for managing the jurisdiction list (KYC officer only)
+#[derive(Accounts)]
+pub struct ManageJurisdictions<'info> {
+    /// The KYC officer updating the list
+    pub admin: Signer<'info>,
+
+    /// Platform configuration for KYC officer role validation
+    #[account(
+        seeds = [b"platform_config"],
+        bump,
+        constraint = platform_config.has_role(&admin.key(), AdminRole::KycOfficer) @ crate::error::RwaError::UnauthorizedAccess
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    /// Jurisdiction list being updated
+    #[account(
+        mut,
+        seeds = [b"jurisdiction_config"],
+        bump
+    )]
+    pub jurisdiction_config: Account<'info, JurisdictionConfig>,
+}
+
+/// Account validation struct for the verify_kyc instruction
+#[derive(Accounts)]
+pub struct VerifyKyc<'info> {
+    /// The admin verifying the KYC status
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// The KYC account to be verified
+    #[account(
+        mut,
+        constraint = kyc_account.status != KycStatus::Revoked @ crate::error::RwaError::KycRevoked
+    )]
+    pub kyc_account: Account<'info, SimpleKycAccount>,
+
+    /// Platform configuration for KYC officer role validation
+    #[account(
+        constraint = platform_config.has_role(&admin.key(), AdminRole::KycOfficer) @ crate::error::RwaError::UnauthorizedAccess
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    /// CHECK: Instructions sysvar, read to find the officer's Ed25519 signature instruction
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+}
+
+/// Account validation struct for creating the KYC provider registry (super admin only)
+#[derive(Accounts)]
+pub struct InitializeKycAttestorRegistry<'info> {
+    /// The super admin creating the registry
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// Platform configuration for super admin validation
+    #[account(
+        seeds = [b"platform_config"],
+        bump,
+        constraint = platform_config.has_role(&admin.key(), AdminRole::SuperAdmin) @ crate::error::RwaError::UnauthorizedAccess
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    /// KYC provider registry to be created
+    #[account(
+        init,
+        payer = admin,
+        space = KycAttestorRegistry::LEN,
+        seeds = [b"kyc_attestor_registry"],
+        bump
+    )]
+    pub attestor_registry: Account<'info, KycAttestorRegistry>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+}
+
+/// Account validation struct for trusting or distrusting a KYC provider (KYC officer only)
+#[derive(Accounts)]
+pub struct ManageKycAttestors<'info> {
+    /// The KYC officer updating the registry
+    pub admin: Signer<'info>,
+
+    /// Platform configuration for KYC officer role validation
+    #[account(
+        seeds = [b"platform_config"],
+        bump,
+        constraint = platform_config.has_role(&admin.key(), AdminRole::KycOfficer) @ crate::error::RwaError::UnauthorizedAccess
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    /// KYC provider registry being updated
+    #[account(
+        mut,
+        seeds = [b"kyc_attestor_registry"],
+        bump
+    )]
+    pub attestor_registry: Account<'info, KycAttestorRegistry>,
+}
+
+/// Account validation struct for submitting a provider-signed KYC attestation
+#[derive(Accounts)]
+pub struct SubmitKycAttestation<'info> {
+    /// The attested user
+    pub user: Signer<'info>,
+
+    /// The user's KYC account to be verified
+    #[account(
+        mut,
+        seeds = [b"simple_kyc", user.key().as_ref()],
+        bump,
+        constraint = kyc_account.status != KycStatus::Suspended @ crate::error::RwaError::KycSuspended,
+        constraint = kyc_account.status != KycStatus::Revoked @ crate::error::RwaError::KycRevoked
+    )]
+    pub kyc_account: Account<'info, SimpleKycAccount>,
+
+    /// Registry of trusted KYC providers
+    #[account(
+        seeds = [b"kyc_attestor_registry"],
+        bump
+    )]
+    pub attestor_registry: Account<'info, KycAttestorRegistry>,
+
+    /// Platform configuration for the kill switch and KYC validity period
+    #[account(
+        seeds = [b"platform_config"],
+        bump,
+        constraint = platform_config.is_active @ crate::error::RwaError::PlatformNotActive
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    /// CHECK: Instructions sysvar, read to find the Ed25519 signature instruction
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+}
+
+/// Account validation struct for the read-only cross-program KYC check
+#[derive(Accounts)]
+pub struct CheckKyc<'info> {
+    /// CHECK: Any wallet; only used to derive its KYC account address
+    pub wallet: UncheckedAccount<'info>,
+
+    /// CHECK: The wallet's KYC account PDA; may be uninitialized, in which
+    /// case the check reports `NotRegistered` instead of failing
+    #[account(
+        seeds = [b"simple_kyc", wallet.key().as_ref()],
+        bump
+    )]
+    pub kyc_account: UncheckedAccount<'info>,
+
+    /// Jurisdiction list applied to purchases and transfers
+    #[account(
+        seeds = [b"jurisdiction_config"],
+        bump
+    )]
+    pub jurisdiction_config: Account<'info, JurisdictionConfig>,
+}
+
+/// Account validation struct for auditing a KYC dossier commitment (KYC officer only)
+#[derive(Accounts)]
+pub struct AuditKycCommitment<'info> {
+    /// The KYC officer holding the off-chain dossier
+    pub auditor: Signer<'info>,
+
+    /// The KYC account whose commitment is being checked
+    pub kyc_account: Account<'info, SimpleKycAccount>,
+
+    /// Platform configuration for KYC officer role validation
+    #[account(
+        seeds = [b"platform_config"],
+        bump,
+        constraint = platform_config.has_role(&auditor.key(), AdminRole::KycOfficer) @ crate::error::RwaError::UnauthorizedAccess
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+}
+
+/// Account validation struct for rejecting, suspending or revoking a user's KYC
+#[derive(Accounts)]
+pub struct UpdateKycStatus<'info> {
+    /// The KYC officer changing the status
+    pub admin: Signer<'info>,
+
+    /// The KYC account being updated
+    #[account(
+        mut,
+        constraint = kyc_account.status != KycStatus::Revoked @ crate::error::RwaError::KycRevoked
+    )]
+    pub kyc_account: Account<'info, SimpleKycAccount>,
+
+    /// Platform configuration for KYC officer role validation
+    #[account(
+        seeds = [b"platform_config"],
+        bump,
+        constraint = platform_config.has_role(&admin.key(), AdminRole::KycOfficer) @ crate::error::RwaError::UnauthorizedAccess
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+}
+
+/// Account validation struct for a user requesting KYC re-verification
+#[derive(Accounts)]
+pub struct RequestKycReverification<'info> {
+    /// The user requesting re-verification
+    pub user: Signer<'info>,
+
+    /// The user's KYC account to be reset
+    #[account(
+        mut,
+        seeds = [b"simple_kyc", user.key().as_ref()],
+        bump,
+        constraint = kyc_account.status != KycStatus::Suspended @ crate::error::RwaError::KycSuspended,
+        constraint = kyc_account.status != KycStatus::Revoked @ crate::error::RwaError::KycRevoked
+    )]
+    pub kyc_account: Account<'info, SimpleKycAccount>,
+
+    /// Platform configuration for the global kill switch
+    #[account(
+        seeds = [b"platform_config"],
+        bump,
+        constraint = platform_config.is_active @ crate::error::RwaError::PlatformNotActive
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+}
+
+/// Account validation struct for updating KYC policy (KYC officer only)
+#[derive(Accounts)]
+pub struct UpdateKycPolicy<'info> {
+    /// The KYC officer updating the policy
+    pub admin: Signer<'info>,
+
+    /// Platform configuration account
+    #[account(
+        mut,
+        seeds = [b"platform_config"],
+        bump,
+        constraint = platform_config.has_role(&admin.key(), AdminRole::KycOfficer) @ crate::error::RwaError::UnauthorizedAccess
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+}
+
+/// Account validation struct for updating the KYC level purchase limits (KYC officer only)
+#[derive(Accounts)]
+pub struct UpdateKycLevelLimits<'info> {
+    /// The KYC officer updating the limits
+    pub admin: Signer<'info>,
+
+    /// Platform configuration account
+    #[account(
+        mut,
+        seeds = [b"platform_config"],
+        bump,
+        constraint = platform_config.has_role(&admin.key(), AdminRole::KycOfficer) @ crate::error::RwaError::UnauthorizedAccess
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+}
+
+/// Account validation struct for updating platform configuration
+#[derive(Accounts)]
+pub struct UpdatePlatformConfig<'info> {
+    /// The platform admin updating configuration
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// Platform configuration account
+    #[account(
+        mut,
+        constraint = platform_config.has_role(&admin.key(), AdminRole::FeeManager) @ crate::error::RwaError::UnauthorizedAccess
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+}
+
+/// Account validation struct for pausing or unpausing the platform
+#[derive(Accounts)]
+pub struct SetPlatformStatus<'info> {
+    /// The pauser changing the platform status
+    pub admin: Signer<'info>,
+
+    /// Platform configuration account
+    #[account(
+        mut,
+        seeds = [b"platform_config"],
+        bump,
+        constraint = platform_config.has_role(&admin.key(), AdminRole::Pauser) @ crate::error::RwaError::UnauthorizedAccess
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+}
+
+/// Account validation struct for super admin actions (admin transfer, roles and
+/// approved collections)
+#[derive(Accounts)]
+pub struct ManageAdmin<'info> {
+    /// The current super admin
+    pub admin: Signer<'info>,
+
+    /// Platform configuration account
+    #[account(
+        mut,
+        seeds = [b"platform_config"],
+        bump,
+        constraint = platform_config.has_role(&admin.key(), AdminRole::SuperAdmin) @ crate::error::RwaError::UnauthorizedAccess
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+}
+
+/// Account validation struct for accepting a proposed admin transfer
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    /// The proposed admin accepting the transfer
+    pub new_admin: Signer<'info>,
+
+    /// Platform configuration account
+    #[account(
+        mut,
+        seeds = [b"platform_config"],
+        bump,
+        constraint = platform_config.pending_admin.is_some() @ crate::error::RwaError::NoPendingAdmin,
+        constraint = platform_config.pending_admin == Some(new_admin.key()) @ crate::error::RwaError::UnauthorizedAccess
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+}
+
+/// Account validation struct for halting or resuming a single vault (pauser only)
+#[derive(Accounts)]
+pub struct SetVaultHalted<'info> {
+    /// The pauser halting or resuming the vault
+    pub admin: Signer<'info>,
+
+    /// Platform configuration for pauser role validation
+    #[account(
+        seeds = [b"platform_config"],
+        bump,
+        constraint = platform_config.has_role(&admin.key(), AdminRole::Pauser) @ crate::error::RwaError::UnauthorizedAccess
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    /// The vault being halted or resumed
+    #[account(mut)]
+    pub vault: Account<'info, Vault>,
+}
+
+/// Account validation struct for freezing or thawing a holder's fractional tokens (pauser only)
+#[derive(Accounts)]
+pub struct FreezeHolderAccount<'info> {
+    /// The pauser freezing or thawing the account
+    pub admin: Signer<'info>,
+
+    /// Platform configuration for pauser role validation
+    #[account(
+        seeds = [b"platform_config"],
+        bump,
+        constraint = platform_config.has_role(&admin.key(), AdminRole::Pauser) @ crate::error::RwaError::UnauthorizedAccess
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    /// The vault acting as freeze authority of the fractional mint
+    #[account(
+        seeds = [b"vault", vault.original_nft_mint.as_ref()],
+        bump,
+        has_one = fractional_token_program @ crate::error::RwaError::InvalidFractionalTokenProgram
+    )]
+    pub vault: Account<'info, Vault>,
+
+    /// Fractional token mint
+    #[account(
+        constraint = fractional_token_mint.key() == vault.fractional_token_mint @ crate::error::RwaError::InvalidFractionalMint
+    )]
+    pub fractional_token_mint: InterfaceAccount<'info, token_interface::Mint>,
+
+    /// The holder's fractional token account to freeze or thaw
+    #[account(
+        mut,
+        constraint = holder_fractional_account.mint == fractional_token_mint.key() @ crate::error::RwaError::InvalidFractionalMint
+    )]
+    pub holder_fractional_account: InterfaceAccount<'info, token_interface::TokenAccount>,
+
+    /// Token program owning the fractional mint (SPL Token or Token-2022)
+    pub fractional_token_program: Interface<'info, TokenInterface>,
+}
+
+/// Account validation struct for creating the KYC provider configuration
+#[derive(Accounts)]
+pub struct InitializeKycProviderConfig<'info> {
+    /// The super admin creating the configuration
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// Platform configuration for super admin validation
+    #[account(
+        seeds = [b"platform_config"],
+        bump,
+        constraint = platform_config.has_role(&admin.key(), AdminRole::SuperAdmin) @ crate::error::RwaError::UnauthorizedAccess
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    /// KYC provider configuration account to be created
+    #[account(
+        init,
+        payer = admin,
+        space = KycProviderConfig::LEN,
+        seeds = [b"kyc_provider_config"],
+        bump
+    )]
+    pub provider_config: Account<'info, KycProviderConfig>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+}
+
+/// Account validation struct for the initiate_kyc_verification instruction
+#[derive(Accounts)]
+pub struct InitiateKycVerification<'info> {
+    /// The user starting enhanced KYC verification
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// Enhanced KYC account to be created for the user
+    #[account(
+        init,
+        payer = user,
+        space = EnhancedKycAccount::LEN,
+        seeds = [b"kyc", user.key().as_ref()],
+        bump
+    )]
+    pub kyc_account: Account<'info, EnhancedKycAccount>,
+
+    /// KYC provider configuration
+    #[account(
+        seeds = [b"kyc_provider_config"],
+        bump,
+        constraint = !provider_config.active_providers.is_empty() @ crate::error::RwaError::InvalidKycProvider
+    )]
+    pub provider_config: Account<'info, KycProviderConfig>,
+
+    /// Platform configuration for the global kill switch
+    #[account(
+        seeds = [b"platform_config"],
+        bump,
+        constraint = platform_config.is_active @ crate::error::RwaError::PlatformNotActive
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+}
+
+/// Account validation struct for the process_kyc_result instruction
+#[derive(Accounts)]
+pub struct ProcessKycResult<'info> {
+    /// The KYC officer relaying the provider result
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// The enhanced KYC account being updated
+    #[account(mut)]
+    pub kyc_account: Account<'info, EnhancedKycAccount>,
+
+    /// KYC provider configuration with the verification requirements
+    #[account(
+        seeds = [b"kyc_provider_config"],
+        bump
+    )]
+    pub provider_config: Account<'info, KycProviderConfig>,
+
+    /// Platform configuration for KYC officer role validation
+    #[account(
+        seeds = [b"platform_config"],
+        bump,
+        constraint = platform_config.has_role(&admin.key(), AdminRole::KycOfficer) @ crate::error::RwaError::UnauthorizedAccess
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+}
+
+/// Account validation struct for the manual_kyc_verification instruction
+#[derive(Accounts)]
+pub struct ManualKycVerification<'info> {
+    /// The KYC officer performing the review
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// The enhanced KYC account being reviewed
+    #[account(mut)]
+    pub kyc_account: Account<'info, EnhancedKycAccount>,
+
+    /// KYC provider configuration with the verification requirements
+    #[account(
+        seeds = [b"kyc_provider_config"],
+        bump
+    )]
+    pub provider_config: Account<'info, KycProviderConfig>,
+
+    /// Platform configuration for KYC officer role validation
+    #[account(
+        seeds = [b"platform_config"],
+        bump,
+        constraint = platform_config.has_role(&admin.key(), AdminRole::KycOfficer) @ crate::error::RwaError::UnauthorizedAccess
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+}
+
+/// Account validation struct for the suspend_kyc_verification instruction
+#[derive(Accounts)]
+pub struct SuspendKycVerification<'info> {
+    /// The KYC officer suspending the user
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// The enhanced KYC account being suspended
+    #[account(mut)]
+    pub kyc_account: Account<'info, EnhancedKycAccount>,
+
+    /// KYC provider configuration with the verification requirements
+    #[account(
+        seeds = [b"kyc_provider_config"],
+        bump
+    )]
+    pub provider_config: Account<'info, KycProviderConfig>,
+
+    /// Platform configuration for KYC officer role validation
+    #[account(
+        seeds = [b"platform_config"],
+        bump,
+        constraint = platform_config.has_role(&admin.key(), AdminRole::KycOfficer) @ crate::error::RwaError::UnauthorizedAccess
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+}
+
+/// Account validation struct for the refresh_kyc_verification instruction
+#[derive(Accounts)]
+pub struct RefreshKycVerification<'info> {
+    /// The user refreshing their verification
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// The user's enhanced KYC account
+    #[account(
+        mut,
+        constraint = kyc_account.user == user.key() @ crate::error::RwaError::UnauthorizedAccess
+    )]
+    pub kyc_account: Account<'info, EnhancedKycAccount>,
+
+    /// KYC provider configuration
+    #[account(
+        seeds = [b"kyc_provider_config"],
+        bump
+    )]
+    pub provider_config: Account<'info, KycProviderConfig>,
+
+    /// Platform configuration for the global kill switch
+    #[account(
+        seeds = [b"platform_config"],
+        bump,
+        constraint = platform_config.is_active @ crate::error::RwaError::PlatformNotActive
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+}
+
+/// Account validation struct for the update_kyc_provider_config instruction
+#[derive(Accounts)]
+pub struct UpdateKycProviderConfig<'info> {
+    /// The KYC officer updating the configuration
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// KYC provider configuration account
+    #[account(
+        mut,
+        seeds = [b"kyc_provider_config"],
+        bump
+    )]
+    pub provider_config: Account<'info, KycProviderConfig>,
+
+    /// Platform configuration for KYC officer role validation
+    #[account(
+        seeds = [b"platform_config"],
+        bump,
+        constraint = platform_config.has_role(&admin.key(), AdminRole::KycOfficer) @ crate::error::RwaError::UnauthorizedAccess
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+}
+
+/// Account validation struct for harvesting transfer-fee royalties
+///
+/// Permissionless: anyone may crank the harvest, proceeds always go to the
+/// vault creator and the platform treasury, both of which must pass KYC like
+/// any other receiver of shares. Holder token accounts with withheld fees are
+/// passed as remaining accounts.
+#[derive(Accounts)]
+pub struct HarvestRoyalties<'info> {
+    /// Pays for any recipient token account that does not exist yet
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The vault whose fractional mint charges royalties
+    #[account(
+        seeds = [b"vault", vault.original_nft_mint.as_ref()],
+        bump,
+        has_one = creator @ crate::error::RwaError::OwnerMismatch,
+        has_one = fractional_token_mint @ crate::error::RwaError::InvalidFractionalMint,
+        has_one = fractional_token_program @ crate::error::RwaError::InvalidFractionalTokenProgram,
+        constraint = vault.royalty_bps > 0 @ crate::error::RwaError::RoyaltiesNotEnabled,
+        constraint = !vault.is_halted @ crate::error::RwaError::VaultHalted
+    )]
+    pub vault: Account<'info, Vault>,
+
+    /// CHECK: Original creator of the vault, receives the creator share
+    pub creator: UncheckedAccount<'info>,
+
+    /// CHECK: Platform treasury wallet, receives the treasury share
+    #[account(address = platform_config.treasury @ crate::error::RwaError::InvalidTreasuryAccount)]
+    pub treasury: UncheckedAccount<'info>,
+
+    /// Fractional token mint holding harvested withheld fees
+    #[account(mut)]
+    pub fractional_token_mint: InterfaceAccount<'info, token_interface::Mint>,
+
+    /// Vault's fractional token account, collects the withheld fees before payout
+    #[account(
+        mut,
+        associated_token::mint = fractional_token_mint,
+        associated_token::authority = vault,
+        associated_token::token_program = fractional_token_program,
+    )]
+    pub vault_fractional_account: InterfaceAccount<'info, token_interface::TokenAccount>,
+
+    /// Creator's fractional token account
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = fractional_token_mint,
+        associated_token::authority = creator,
+        associated_token::token_program = fractional_token_program,
+    )]
+    pub creator_fractional_account: InterfaceAccount<'info, token_interface::TokenAccount>,
+
+    /// Treasury's fractional token account
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = fractional_token_mint,
+        associated_token::authority = treasury,
+        associated_token::token_program = fractional_token_program,
+    )]
+    pub treasury_fractional_account: InterfaceAccount<'info, token_interface::TokenAccount>,
+
+    /// Platform jurisdiction list applied to the royalty recipients
+    #[account(
+        seeds = [b"jurisdiction_config"],
+        bump
+    )]
+    pub jurisdiction_config: Account<'info, JurisdictionConfig>,
+
+    /// Creator's KYC account, royalty shares only go to verified wallets
+    #[account(
+        seeds = [b"simple_kyc", creator.key().as_ref()],
+        bump,
+        constraint = creator_kyc_account.check_for_transaction(TransactionType::Transfer, &jurisdiction_config, Clock::get()?.unix_timestamp).is_none() @ crate::error::RwaError::KycNotVerified
+    )]
+    pub creator_kyc_account: Box<Account<'info, SimpleKycAccount>>,
+
+    /// Treasury's KYC account, royalty shares only go to verified wallets
+    #[account(
+        seeds = [b"simple_kyc", treasury.key().as_ref()],
+        bump,
+        constraint = treasury_kyc_account.check_for_transaction(TransactionType::Transfer, &jurisdiction_config, Clock::get()?.unix_timestamp).is_none() @ crate::error::RwaError::KycNotVerified
+    )]
+    pub treasury_kyc_account: Box<Account<'info, SimpleKycAccount>>,
+
+    /// Platform configuration holding the treasury and fee ratio
+    #[account(
+        seeds = [b"platform_config"],
+        bump,
+        constraint = platform_config.is_active @ crate::error::RwaError::PlatformNotActive
     )]
     pub platform_config: Account<'info, PlatformConfig>,
+
+    /// Token-2022 program for the fractional mint
+    pub fractional_token_program: Program<'info, Token2022>,
+
+    /// Associated token program for creating ATAs
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
 }
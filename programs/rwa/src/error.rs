@@ -104,4 +104,202 @@ pub enum RwaError {
 
     #[msg("Invalid fractional mint - token account mint does not match expected fractional token mint")]
     InvalidFractionalMint,
+
+    #[msg("Invalid platform fee - numerator must be <= denominator and denominator > 0")]
+    InvalidPlatformFee,
+
+    #[msg("Invalid treasury account - token account must be owned by the platform treasury")]
+    InvalidTreasuryAccount,
+
+    #[msg("Payment mint is not accepted by the platform")]
+    PaymentMintNotAccepted,
+
+    #[msg("Invalid payment mint - token account mint does not match the vault's payment mint")]
+    InvalidPaymentMint,
+
+    #[msg("Payment mint is already accepted by the platform")]
+    PaymentMintAlreadyAccepted,
+
+    #[msg("Payment mint allowlist is full")]
+    PaymentMintListFull,
+
+    #[msg("Platform is not active")]
+    PlatformNotActive,
+
+    #[msg("Investment below the platform minimum")]
+    InvestmentBelowMinimum,
+
+    #[msg("Investment exceeds the platform maximum for this wallet")]
+    InvestmentAboveMaximum,
+
+    #[msg("Vault has been halted by the platform admin")]
+    VaultHalted,
+
+    #[msg("Invalid role - the super admin role can only be transferred")]
+    InvalidRole,
+
+    #[msg("Role is already granted to this authority")]
+    RoleAlreadyGranted,
+
+    #[msg("Role is not granted to this authority")]
+    RoleNotGranted,
+
+    #[msg("Role assignment list is full")]
+    RoleListFull,
+
+    #[msg("No pending admin to accept")]
+    NoPendingAdmin,
+
+    #[msg("KYC verification has expired")]
+    KycExpired,
+
+    #[msg("KYC metadata exceeds the reserved account space")]
+    KycMetadataTooLong,
+
+    #[msg("Invalid KYC validity period - must not be negative")]
+    InvalidKycValidityPeriod,
+
+    #[msg("KYC verification has been suspended")]
+    KycSuspended,
+
+    #[msg("KYC verification has been revoked")]
+    KycRevoked,
+
+    #[msg("Purchase exceeds the limit for the buyer's KYC verification level")]
+    KycLevelLimitExceeded,
+
+    #[msg("Invalid country code - expected an ISO 3166-1 alpha-2 code")]
+    InvalidCountryCode,
+
+    #[msg("Purchases are not permitted from the buyer's jurisdiction")]
+    JurisdictionRestricted,
+
+    #[msg("Country is already on the jurisdiction list")]
+    JurisdictionAlreadyListed,
+
+    #[msg("Country is not on the jurisdiction list")]
+    JurisdictionNotListed,
+
+    #[msg("Jurisdiction list is full")]
+    JurisdictionListFull,
+
+    #[msg("Invalid PII commitment - must not be empty")]
+    InvalidPiiCommitment,
+
+    #[msg("Dossier does not match the on-chain PII commitment")]
+    PiiCommitmentMismatch,
+
+    #[msg("KYC provider is already trusted")]
+    AttestorAlreadyTrusted,
+
+    #[msg("KYC provider is not trusted")]
+    AttestorNotTrusted,
+
+    #[msg("KYC provider registry is full")]
+    AttestorListFull,
+
+    #[msg("Missing or malformed Ed25519 attestation signature")]
+    InvalidAttestationSignature,
+
+    #[msg("Signed attestation does not match the submitted fields")]
+    AttestationMismatch,
+
+    #[msg("KYC attestation has already expired")]
+    AttestationExpired,
+
+    #[msg("KYC attestation nonce has already been used")]
+    AttestationNonceReused,
+
+    #[msg("Invalid verification level - must be between 1 and 3")]
+    InvalidVerificationLevel,
+
+    #[msg("Token program does not own this vault's fractional mint")]
+    InvalidFractionalTokenProgram,
+
+    #[msg("Vault fractional mint does not charge royalties")]
+    RoyaltiesNotEnabled,
+
+    #[msg("No withheld royalties to harvest")]
+    NoRoyaltiesToHarvest,
+
+    #[msg("Original NFT is not part of a verified collection")]
+    NftCollectionNotVerified,
+
+    #[msg("Original NFT's collection is not approved by the platform")]
+    NftCollectionNotApproved,
+
+    #[msg("Collection is already approved")]
+    CollectionAlreadyApproved,
+
+    #[msg("Approved collection list is full")]
+    CollectionListFull,
+
+    #[msg("Programmable NFT transfers require the token record accounts")]
+    MissingProgrammableNftAccounts,
+
+    #[msg("Fractional token decimals exceed the maximum")]
+    InvalidFractionalDecimals,
+
+    #[msg("Sale window must end in the future and after it starts")]
+    InvalidSaleWindow,
+
+    #[msg("Sale has not started yet")]
+    SaleNotStarted,
+
+    #[msg("Sale window has ended")]
+    SaleEnded,
+
+    #[msg("Sale can only be closed once its window lapses or it sells out")]
+    SaleStillOpen,
+
+    #[msg("Soft cap must be positive and at most the vault's total value")]
+    InvalidSoftCap,
+
+    #[msg("Soft-capped sales need a sale end timestamp")]
+    SoftCapRequiresSaleEnd,
+
+    #[msg("Soft-capped sales require the vault's escrow payment account")]
+    MissingEscrowAccount,
+
+    #[msg("Escrow payment account is not owned by the vault")]
+    InvalidEscrowAccount,
+
+    #[msg("Vault sale does not hold proceeds in escrow")]
+    EscrowNotEnabled,
+
+    #[msg("Soft cap has not been met")]
+    SoftCapNotMet,
+
+    #[msg("Refunds are only available once a sale ends below its soft cap")]
+    RefundsNotAvailable,
+
+    #[msg("No escrowed proceeds to claim")]
+    NothingToClaim,
+
+    #[msg("No purchase to refund")]
+    NothingToRefund,
+
+    #[msg("Vault can only be cancelled before any fractions are sold")]
+    FractionsAlreadySold,
+
+    #[msg("Price tiers need positive sizes and prices and must fit within the fractions for sale")]
+    InvalidPriceTiers,
+
+    #[msg("Dutch auctions need a start price above the floor, a positive duration and steps, and no price tiers or soft cap")]
+    InvalidDutchAuction,
+
+    #[msg("Uniform clearing price auctions need a sale end timestamp")]
+    ClearingPriceRequiresSaleEnd,
+
+    #[msg("Price of a Dutch auction follows its schedule")]
+    PriceSetByAuction,
+
+    #[msg("Vault sale is not a uniform clearing price auction")]
+    ClearingPriceNotEnabled,
+
+    #[msg("Auction has not closed yet")]
+    AuctionNotClosed,
+
+    #[msg("Vault can only be cancelled once every escrowed payment is refunded")]
+    ProceedsNotRefunded,
 }
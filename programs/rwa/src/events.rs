@@ -0,0 +1,653 @@
+//! Events emitted by the Fractional Art Investment Platform
+//!
+//! Every state transition emits one of these so indexers can build
+//! portfolio history from a stable schema instead of parsing `msg!` logs.
+
+use anchor_lang::prelude::*;
+
+use crate::kyc::{JurisdictionMode, KycProvider, KycReasonCode, KycStatus, VerificationMethod};
+use crate::state::{AdminRole, DutchAuction, PriceTier};
+
+/// Emitted when the platform configuration is first created
+#[event]
+pub struct PlatformInitialized {
+    /// The platform admin
+    pub admin: Pubkey,
+
+    /// The treasury wallet receiving platform fees
+    pub treasury: Pubkey,
+
+    /// Royalty in basis points for Token-2022 vaults that do not set their own
+    pub default_royalty_bps: u16,
+
+    /// Timestamp of initialization
+    pub timestamp: i64,
+}
+
+/// Emitted when an NFT is locked in a vault and its fractions are minted
+#[event]
+pub struct VaultFractionalized {
+    /// The vault holding the NFT
+    pub vault: Pubkey,
+
+    /// The creator who fractionalized the NFT
+    pub creator: Pubkey,
+
+    /// The original NFT mint
+    pub original_nft_mint: Pubkey,
+
+    /// The fractional token mint
+    pub fractional_token_mint: Pubkey,
+
+    /// Token program owning the fractional mint
+    pub fractional_token_program: Pubkey,
+
+    /// Mint buyers pay with
+    pub payment_mint: Pubkey,
+
+    /// Total number of fractions minted, in base units
+    pub total_fractions: u64,
+
+    /// Price per whole fraction in the payment mint's smallest unit
+    pub price_per_fraction: u64,
+
+    /// Decimals of the fractional mint
+    pub fractional_decimals: u8,
+
+    /// Timestamp the primary sale opens at, if scheduled
+    pub sale_starts_at: Option<i64>,
+
+    /// Timestamp the primary sale closes at, if time-limited
+    pub sale_ends_at: Option<i64>,
+
+    /// Soft cap held in escrow until met, if any
+    pub min_raise: Option<u64>,
+
+    /// Tiered pricing of the first fractions sold
+    pub price_tiers: Vec<PriceTier>,
+
+    /// Dutch auction terms, if the sale is an auction
+    pub dutch_auction: Option<DutchAuction>,
+
+    /// Royalty charged on secondary transfers (basis points)
+    pub royalty_bps: u16,
+
+    /// Verified collection of the original NFT
+    pub collection_mint: Pubkey,
+
+    /// Timestamp of fractionalization
+    pub timestamp: i64,
+}
+
+/// Emitted when a buyer purchases fractions from a vault's primary sale
+#[event]
+pub struct FractionsPurchased {
+    /// The vault sold from
+    pub vault: Pubkey,
+
+    /// The buyer paying for the fractions
+    pub buyer: Pubkey,
+
+    /// Creator payment account receiving the proceeds
+    pub creator_payment_account: Pubkey,
+
+    /// Treasury token account receiving the platform fee
+    pub treasury_payment_account: Pubkey,
+
+    /// Number of fractions purchased
+    pub num_fractions: u64,
+
+    /// Total amount paid by the buyer
+    pub total_cost: u64,
+
+    /// Portion of `total_cost` due to the treasury
+    pub platform_fee: u64,
+
+    /// Portion of `total_cost` due to the creator
+    pub creator_amount: u64,
+
+    /// Whether the payment went to the vault's escrow instead of being paid out
+    /// (the fee and creator split are then only settled by `claim_proceeds`)
+    pub escrowed: bool,
+
+    /// Fractions sold in the vault after this purchase
+    pub fractions_sold: u64,
+
+    /// Timestamp of the purchase
+    pub timestamp: i64,
+}
+
+/// Emitted when the creator claims escrowed proceeds of a soft-capped sale
+#[event]
+pub struct ProceedsClaimed {
+    /// The vault whose escrow was drained
+    pub vault: Pubkey,
+
+    /// The creator claiming the proceeds
+    pub creator: Pubkey,
+
+    /// Escrowed amount paid out
+    pub amount: u64,
+
+    /// Portion of `amount` sent to the treasury
+    pub platform_fee: u64,
+
+    /// Portion of `amount` sent to the creator
+    pub creator_amount: u64,
+
+    /// Timestamp of the claim
+    pub timestamp: i64,
+}
+
+/// Emitted when a buyer burns fractions of a failed sale for a refund
+#[event]
+pub struct FractionsRefunded {
+    /// The vault refunded from
+    pub vault: Pubkey,
+
+    /// The buyer receiving the refund
+    pub buyer: Pubkey,
+
+    /// Number of fractions burned
+    pub fractions_burned: u64,
+
+    /// Amount refunded from escrow
+    pub amount: u64,
+
+    /// Timestamp of the refund
+    pub timestamp: i64,
+}
+
+/// Emitted when the creator cancels a vault before any fractions are sold
+#[event]
+pub struct VaultCancelled {
+    /// The cancelled vault
+    pub vault: Pubkey,
+
+    /// The creator receiving the NFT and the rent
+    pub creator: Pubkey,
+
+    /// The original NFT mint
+    pub original_nft_mint: Pubkey,
+
+    /// Unsold fractions burned from the vault
+    pub fractions_burned: u64,
+
+    /// Timestamp of the cancellation
+    pub timestamp: i64,
+}
+
+/// Emitted when an early auction buyer is refunded down to the clearing price
+#[event]
+pub struct AuctionRefundClaimed {
+    /// The vault whose auction closed
+    pub vault: Pubkey,
+
+    /// The buyer receiving the refund
+    pub buyer: Pubkey,
+
+    /// Clearing price per whole fraction
+    pub clearing_price: u64,
+
+    /// Amount refunded from escrow
+    pub amount: u64,
+
+    /// Timestamp of the refund
+    pub timestamp: i64,
+}
+
+/// Emitted when the creator takes back the NFT of a failed sale
+#[event]
+pub struct NftReclaimed {
+    /// The vault the NFT left
+    pub vault: Pubkey,
+
+    /// The creator receiving the NFT
+    pub creator: Pubkey,
+
+    /// The original NFT mint
+    pub original_nft_mint: Pubkey,
+
+    /// Timestamp of the reclaim
+    pub timestamp: i64,
+}
+
+/// Emitted when the creator changes a vault's base price
+#[event]
+pub struct PriceUpdated {
+    /// The repriced vault
+    pub vault: Pubkey,
+
+    /// Previous price per whole fraction
+    pub old_price_per_fraction: u64,
+
+    /// New price per whole fraction
+    pub new_price_per_fraction: u64,
+
+    /// Timestamp of the update
+    pub timestamp: i64,
+}
+
+/// Emitted when a vault's primary sale is closed
+#[event]
+pub struct SaleClosed {
+    /// The vault whose sale closed
+    pub vault: Pubkey,
+
+    /// Fractions sold before the sale closed
+    pub fractions_sold: u64,
+
+    /// Total fractions offered
+    pub total_fractions: u64,
+
+    /// Whether the sale sold out (rather than its window lapsing)
+    pub sold_out: bool,
+
+    /// Timestamp the sale closed at
+    pub timestamp: i64,
+}
+
+/// Emitted when withheld transfer-fee royalties are paid out
+#[event]
+pub struct RoyaltiesHarvested {
+    /// The vault whose fractional mint charged the royalties
+    pub vault: Pubkey,
+
+    /// Creator receiving the creator share
+    pub creator: Pubkey,
+
+    /// Treasury receiving the platform share
+    pub treasury: Pubkey,
+
+    /// Total fractions withdrawn from withheld fees
+    pub amount_harvested: u64,
+
+    /// Fractions paid to the creator
+    pub creator_amount: u64,
+
+    /// Fractions paid to the treasury
+    pub treasury_amount: u64,
+
+    /// Timestamp of the harvest
+    pub timestamp: i64,
+}
+
+/// Emitted when the original NFT is redeemed by burning all fractions
+#[event]
+pub struct NftRedeemed {
+    /// The vault the NFT was released from
+    pub vault: Pubkey,
+
+    /// The redeemer receiving the NFT
+    pub redeemer: Pubkey,
+
+    /// The original NFT mint
+    pub original_nft_mint: Pubkey,
+
+    /// Number of fractions burned
+    pub fractions_burned: u64,
+
+    /// Timestamp of redemption
+    pub timestamp: i64,
+}
+
+/// Emitted when a user registers for KYC
+#[event]
+pub struct KycRegistered {
+    /// The user registering
+    pub user: Pubkey,
+
+    /// Timestamp of registration
+    pub timestamp: i64,
+}
+
+/// Emitted when a user's KYC is verified
+#[event]
+pub struct KycVerified {
+    /// The verified user
+    pub user: Pubkey,
+
+    /// The authority that performed the verification
+    pub verifier: Pubkey,
+
+    /// Verification method used
+    pub verification_method: VerificationMethod,
+
+    /// Verification level granted
+    pub verification_level: u8,
+
+    /// Commitment to the off-chain KYC dossier
+    pub pii_commitment: [u8; 32],
+
+    /// Timestamp when the verification expires (0 = never)
+    pub expires_at: i64,
+
+    /// Timestamp of verification
+    pub timestamp: i64,
+}
+
+/// Emitted when the trusted KYC provider registry is created
+#[event]
+pub struct KycAttestorRegistryInitialized {
+    /// The super admin that created the registry
+    pub admin: Pubkey,
+
+    /// Timestamp of creation
+    pub timestamp: i64,
+}
+
+/// Emitted when a KYC provider is added to or removed from the trusted registry
+#[event]
+pub struct KycAttestorUpdated {
+    /// The KYC officer that updated the registry
+    pub authority: Pubkey,
+
+    /// The provider's Ed25519 public key
+    pub attestor: Pubkey,
+
+    /// Whether the provider is now trusted
+    pub trusted: bool,
+
+    /// Timestamp of the update
+    pub timestamp: i64,
+}
+
+/// Emitted when an auditor records that a dossier matches a user's commitment
+#[event]
+pub struct KycCommitmentAudited {
+    /// The audited user
+    pub user: Pubkey,
+
+    /// The auditor that opened the commitment off-chain
+    pub auditor: Pubkey,
+
+    /// The verifier that attested the commitment
+    pub attested_by: Pubkey,
+
+    /// The commitment that was audited
+    pub pii_commitment: [u8; 32],
+
+    /// Timestamp of the audit
+    pub timestamp: i64,
+}
+
+/// Emitted when a KYC officer rejects, suspends or revokes a user
+#[event]
+pub struct KycStatusUpdated {
+    /// The affected user
+    pub user: Pubkey,
+
+    /// The KYC officer that changed the status
+    pub authority: Pubkey,
+
+    /// The new status
+    pub status: KycStatus,
+
+    /// Reason code for the change
+    pub reason: KycReasonCode,
+
+    /// Timestamp of the change
+    pub timestamp: i64,
+}
+
+/// Emitted when a user resets their KYC to request re-verification
+#[event]
+pub struct KycReverificationRequested {
+    /// The user requesting re-verification
+    pub user: Pubkey,
+
+    /// Timestamp of the request
+    pub timestamp: i64,
+}
+
+/// Emitted when the KYC policy on the platform configuration changes
+#[event]
+pub struct KycPolicyUpdated {
+    /// The authority that updated the policy
+    pub authority: Pubkey,
+
+    /// Seconds a KYC verification stays valid (0 = never expires)
+    pub kyc_validity_period: i64,
+
+    /// Timestamp of the update
+    pub timestamp: i64,
+}
+
+/// Emitted when fees, investment limits or the treasury are updated
+#[event]
+pub struct PlatformConfigUpdated {
+    /// The authority that updated the configuration
+    pub authority: Pubkey,
+
+    /// New platform fee numerator
+    pub platform_fee_numerator: u16,
+
+    /// New platform fee denominator
+    pub platform_fee_denominator: u16,
+
+    /// New minimum investment amount
+    pub min_investment_amount: u64,
+
+    /// New maximum investment amount
+    pub max_investment_amount: u64,
+
+    /// New treasury wallet
+    pub treasury: Pubkey,
+
+    /// New default royalty in basis points
+    pub default_royalty_bps: u16,
+
+    /// Timestamp of the update
+    pub timestamp: i64,
+}
+
+/// Emitted when the per-level KYC purchase ceilings are updated
+#[event]
+pub struct KycLevelLimitsUpdated {
+    /// The KYC officer that made the change
+    pub authority: Pubkey,
+
+    /// Maximum cumulative fractions per vault for KYC levels 1-3 (0 = unlimited)
+    pub kyc_level_limits: [u64; 3],
+
+    /// Timestamp of the update
+    pub timestamp: i64,
+}
+
+/// Emitted when a payment mint is added to or removed from the allowlist
+#[event]
+pub struct PaymentMintUpdated {
+    /// The authority that updated the allowlist
+    pub authority: Pubkey,
+
+    /// The payment mint
+    pub payment_mint: Pubkey,
+
+    /// Whether the mint is now accepted
+    pub accepted: bool,
+
+    /// Timestamp of the update
+    pub timestamp: i64,
+}
+
+/// Emitted when an NFT collection is approved or removed for fractionalization
+#[event]
+pub struct CollectionApprovalUpdated {
+    /// The super admin that changed the list
+    pub authority: Pubkey,
+
+    /// The collection mint
+    pub collection_mint: Pubkey,
+
+    /// True if approved, false if removed
+    pub approved: bool,
+
+    /// Timestamp of the change
+    pub timestamp: i64,
+}
+
+/// Emitted when the jurisdiction list is created or its mode changes
+#[event]
+pub struct JurisdictionModeChanged {
+    /// The authority that changed the mode
+    pub authority: Pubkey,
+
+    /// The new mode
+    pub mode: JurisdictionMode,
+
+    /// Timestamp of the change
+    pub timestamp: i64,
+}
+
+/// Emitted when a country is added to or removed from the jurisdiction list
+#[event]
+pub struct JurisdictionListUpdated {
+    /// The authority that updated the list
+    pub authority: Pubkey,
+
+    /// ISO 3166-1 alpha-2 country code
+    pub country: String,
+
+    /// Whether the country is now listed
+    pub listed: bool,
+
+    /// Timestamp of the update
+    pub timestamp: i64,
+}
+
+/// Emitted when the platform is paused or unpaused
+#[event]
+pub struct PlatformStatusChanged {
+    /// The authority that changed the status
+    pub authority: Pubkey,
+
+    /// Whether the platform is now active
+    pub is_active: bool,
+
+    /// Timestamp of the change
+    pub timestamp: i64,
+}
+
+/// Emitted when a vault is halted or resumed
+#[event]
+pub struct VaultHaltStatusChanged {
+    /// The authority that changed the status
+    pub authority: Pubkey,
+
+    /// The vault
+    pub vault: Pubkey,
+
+    /// Whether the vault is now halted
+    pub is_halted: bool,
+
+    /// Timestamp of the change
+    pub timestamp: i64,
+}
+
+/// Emitted when a holder's fractional token account is frozen or thawed
+#[event]
+pub struct HolderAccountFreezeChanged {
+    /// The authority that froze or thawed the account
+    pub authority: Pubkey,
+
+    /// The vault whose fractional mint the account holds
+    pub vault: Pubkey,
+
+    /// The fractional token account
+    pub token_account: Pubkey,
+
+    /// The owner of the token account
+    pub holder: Pubkey,
+
+    /// Whether the account is now frozen
+    pub is_frozen: bool,
+
+    /// Timestamp of the change
+    pub timestamp: i64,
+}
+
+/// Emitted when the super admin proposes a new admin
+#[event]
+pub struct AdminTransferProposed {
+    /// The current admin
+    pub current_admin: Pubkey,
+
+    /// The proposed admin
+    pub proposed_admin: Pubkey,
+
+    /// Timestamp of the proposal
+    pub timestamp: i64,
+}
+
+/// Emitted when a proposed admin accepts the transfer
+#[event]
+pub struct AdminTransferred {
+    /// The previous admin
+    pub previous_admin: Pubkey,
+
+    /// The new admin
+    pub new_admin: Pubkey,
+
+    /// Timestamp of the transfer
+    pub timestamp: i64,
+}
+
+/// Emitted when a delegated role is granted or revoked
+#[event]
+pub struct RoleUpdated {
+    /// The super admin that changed the role
+    pub admin: Pubkey,
+
+    /// The authority whose role changed
+    pub authority: Pubkey,
+
+    /// The role
+    pub role: AdminRole,
+
+    /// Whether the role is now granted
+    pub granted: bool,
+
+    /// Timestamp of the change
+    pub timestamp: i64,
+}
+
+/// Emitted when an enhanced KYC account changes status
+#[event]
+pub struct EnhancedKycStatusChanged {
+    /// The user whose KYC changed
+    pub user: Pubkey,
+
+    /// The user or admin that triggered the change
+    pub authority: Pubkey,
+
+    /// The new status
+    pub status: KycStatus,
+
+    /// Provider handling the verification
+    pub provider: KycProvider,
+
+    /// Risk score reported by the provider
+    pub risk_score: Option<u8>,
+
+    /// Timestamp when the verification expires
+    pub expires_at: Option<i64>,
+
+    /// Timestamp of the change
+    pub timestamp: i64,
+}
+
+/// Emitted when the KYC provider configuration is created or updated
+#[event]
+pub struct KycProviderConfigUpdated {
+    /// The admin that updated the configuration
+    pub admin: Pubkey,
+
+    /// Providers users may verify with
+    pub active_providers: Vec<KycProvider>,
+
+    /// Provider used when a user does not choose one
+    pub default_provider: KycProvider,
+
+    /// Timestamp of the update
+    pub timestamp: i64,
+}
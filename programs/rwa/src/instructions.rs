@@ -1,26 +1,70 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{burn, mint_to, transfer, Burn, MintTo, Transfer};
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+use anchor_lang::system_program::{create_account, CreateAccount};
+use anchor_spl::associated_token::{
+    create as create_associated_token_account,
+    create_idempotent as create_associated_token_account_idempotent, Create,
+};
+use anchor_spl::metadata::{
+    create_metadata_accounts_v3, CreateMetadataAccountsV3, MetadataAccount,
+};
+use anchor_spl::token::{mint_to, transfer, MintTo, Transfer};
+use anchor_spl::token_2022::spl_token_2022::extension::ExtensionType;
+use anchor_spl::token_2022::spl_token_2022::onchain::invoke_transfer_checked;
+use anchor_spl::token_2022::spl_token_2022::state::Mint as Token2022Mint;
+use anchor_spl::token_interface;
+use anchor_spl::token_interface::spl_pod::optional_keys::OptionalNonZeroPubkey;
+use anchor_spl::token_interface::spl_token_metadata_interface::state::TokenMetadata;
+use mpl_token_metadata::instructions::TransferV1CpiBuilder;
 
 use crate::account_structs::*;
 use crate::error::RwaError;
-use crate::kyc::VerificationMethod;
+use crate::events::*;
+use crate::kyc::{
+    JurisdictionConfig, JurisdictionMode, KycAttestorRegistry, KycCheckFailure, KycCheckResult,
+    KycReasonCode, KycStatus, SimpleKycAccount, TransactionType, VerificationMethod,
+};
+use crate::state::{AdminRole, PlatformConfig, RoleAssignment, SaleTerms, Vault};
+use crate::utils::{
+    fractional_token_metadata, parse_ed25519_instruction, validate_royalty_fee,
+    validate_sale_window,
+};
 
 /// Initialize the platform with default configuration
-pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+pub fn initialize(
+    ctx: Context<Initialize>,
+    treasury: Pubkey,
+    default_royalty_bps: u16,
+) -> Result<()> {
     let platform_config = &mut ctx.accounts.platform_config;
     let clock = Clock::get()?;
 
+    validate_royalty_fee(default_royalty_bps, Vault::ROYALTY_BPS_DENOMINATOR)?;
+
     // Initialize platform configuration
     platform_config.admin = ctx.accounts.admin.key();
+    platform_config.treasury = treasury;
     platform_config.platform_fee_numerator = 5; // 5%
     platform_config.platform_fee_denominator = 100;
     platform_config.min_investment_amount = 1; // Minimum 1 token
     platform_config.max_investment_amount = 10000; // Maximum 10,000 tokens
+    platform_config.default_royalty_bps = default_royalty_bps;
     platform_config.is_active = true;
     platform_config.created_at = clock.unix_timestamp;
 
     msg!("Platform initialized successfully");
     msg!("Admin: {}", platform_config.admin);
+    msg!("Treasury: {}", platform_config.treasury);
+    msg!("Default royalty: {} bps", default_royalty_bps);
+
+    emit!(PlatformInitialized {
+        admin: platform_config.admin,
+        treasury: platform_config.treasury,
+        default_royalty_bps,
+        timestamp: clock.unix_timestamp,
+    });
 
     Ok(())
 }
@@ -29,49 +73,282 @@ pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
 ///
 /// This instruction takes an NFT and creates fractional ownership tokens.
 /// Uses standard SPL Token for simplicity and speed.
+/// The NFT must be a Metaplex master edition in a verified collection
+/// approved by the platform.
 pub fn fractionalize(
     ctx: Context<Fractionalize>,
     total_fractions: u64,
     price_per_fraction: u64,
+    fractional_decimals: u8,
+    sale_terms: SaleTerms,
 ) -> Result<()> {
     let vault = &mut ctx.accounts.vault;
+    let clock = Clock::get()?;
 
-    // Validate inputs
-    require!(total_fractions > 0, RwaError::InvalidTotalFractions);
-    require!(price_per_fraction > 0, RwaError::InvalidPrice);
+    let collection_mint = open_vault(
+        vault,
+        VaultOpening {
+            creator: ctx.accounts.creator.to_account_info(),
+            original_nft_mint: ctx.accounts.original_nft_mint.to_account_info(),
+            original_nft_metadata: &ctx.accounts.original_nft_metadata,
+            original_nft_master_edition: ctx.accounts.original_nft_master_edition.to_account_info(),
+            creator_nft_account: ctx.accounts.creator_nft_account.to_account_info(),
+            vault_nft_account: ctx.accounts.vault_nft_account.to_account_info(),
+            creator_nft_token_record: optional_account(&ctx.accounts.creator_nft_token_record),
+            vault_nft_token_record: optional_account(&ctx.accounts.vault_nft_token_record),
+            authorization_rules: optional_account(&ctx.accounts.authorization_rules),
+            authorization_rules_program: optional_account(
+                &ctx.accounts.authorization_rules_program,
+            ),
+            sysvar_instructions: optional_account(&ctx.accounts.sysvar_instructions),
+            fractional_token_mint: ctx.accounts.fractional_token_mint.to_account_info(),
+            fractional_token_program: ctx.accounts.token_program.to_account_info(),
+            payment_mint: ctx.accounts.payment_mint.to_account_info(),
+            creator_payment_account: ctx.accounts.creator_payment_account.to_account_info(),
+            escrow_payment_account: optional_account(&ctx.accounts.escrow_payment_account),
+            platform_config: &ctx.accounts.platform_config,
+            token_program: ctx.accounts.token_program.to_account_info(),
+            associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+            token_metadata_program: ctx.accounts.token_metadata_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+        },
+        VaultTerms {
+            total_fractions,
+            price_per_fraction,
+            fractional_decimals,
+            sale_terms,
+            royalty_bps: 0,
+        },
+        clock.unix_timestamp,
+    )?;
 
-    // Initialize vault
-    vault.creator = ctx.accounts.creator.key();
-    vault.original_nft_mint = ctx.accounts.original_nft_mint.key();
-    vault.fractional_token_mint = ctx.accounts.fractional_token_mint.key();
-    vault.total_fractions = total_fractions;
-    vault.price_per_fraction = price_per_fraction;
-    vault.fractions_sold = 0;
-    vault.is_sale_active = true;
-    vault.creator_payment_account = ctx.accounts.creator_payment_account.key();
+    // Mint all fractional tokens to vault account
+    let mint_key = ctx.accounts.original_nft_mint.key();
+    let bump = ctx.bumps.vault;
+    let seeds = &[b"vault", mint_key.as_ref(), &[bump]];
 
-    // Transfer NFT from creator to vault using CPI
-    transfer(
-        CpiContext::new(
+    mint_to(
+        CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.creator_nft_account.to_account_info(),
-                to: ctx.accounts.vault_nft_account.to_account_info(),
-                authority: ctx.accounts.creator.to_account_info(),
+            MintTo {
+                mint: ctx.accounts.fractional_token_mint.to_account_info(),
+                to: ctx.accounts.vault_fractional_account.to_account_info(),
+                authority: vault.to_account_info(),
+            },
+            &[seeds],
+        ),
+        total_fractions,
+    )?;
+
+    // Name the shares after the original NFT, with the vault as update authority
+    let original_metadata = &ctx.accounts.original_nft_metadata;
+    let metadata = fractional_token_metadata(
+        &original_metadata.name,
+        &original_metadata.symbol,
+        &original_metadata.uri,
+    );
+    let fractional_token_name = metadata.name.clone();
+
+    create_metadata_accounts_v3(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_metadata_program.to_account_info(),
+            CreateMetadataAccountsV3 {
+                metadata: ctx.accounts.fractional_token_metadata.to_account_info(),
+                mint: ctx.accounts.fractional_token_mint.to_account_info(),
+                mint_authority: vault.to_account_info(),
+                payer: ctx.accounts.creator.to_account_info(),
+                update_authority: vault.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                rent: ctx.accounts.rent.to_account_info(),
+            },
+            &[seeds],
+        ),
+        metadata,
+        true,
+        true,
+        None,
+    )?;
+
+    msg!("NFT fractionalized successfully");
+    msg!("Fractional token: {}", fractional_token_name);
+    emit_vault_fractionalized(vault, collection_mint, clock.unix_timestamp);
+
+    Ok(())
+}
+
+/// Fractionalize an NFT into Token-2022 shares guarded by the KYC transfer hook
+///
+/// Same flow as `fractionalize`, but the fractional mint carries a transfer
+/// hook so secondary transfers are only possible between KYC-verified wallets.
+/// The hook's extra account meta list must be initialized before the first sale.
+/// The share metadata is stored on the mint with the token metadata extension.
+///
+/// With a non-zero royalty (falling back to the platform default), the mint
+/// also carries the transfer fee extension, withholding the royalty from every
+/// secondary transfer until `harvest_royalties` pays it out.
+pub fn fractionalize_token_2022(
+    ctx: Context<FractionalizeToken2022>,
+    total_fractions: u64,
+    price_per_fraction: u64,
+    fractional_decimals: u8,
+    sale_terms: SaleTerms,
+    royalty_bps: Option<u16>,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let clock = Clock::get()?;
+
+    let royalty_bps = royalty_bps.unwrap_or(ctx.accounts.platform_config.default_royalty_bps);
+    validate_royalty_fee(royalty_bps, Vault::ROYALTY_BPS_DENOMINATOR)?;
+
+    let collection_mint = open_vault(
+        vault,
+        VaultOpening {
+            creator: ctx.accounts.creator.to_account_info(),
+            original_nft_mint: ctx.accounts.original_nft_mint.to_account_info(),
+            original_nft_metadata: &ctx.accounts.original_nft_metadata,
+            original_nft_master_edition: ctx.accounts.original_nft_master_edition.to_account_info(),
+            creator_nft_account: ctx.accounts.creator_nft_account.to_account_info(),
+            vault_nft_account: ctx.accounts.vault_nft_account.to_account_info(),
+            creator_nft_token_record: optional_account(&ctx.accounts.creator_nft_token_record),
+            vault_nft_token_record: optional_account(&ctx.accounts.vault_nft_token_record),
+            authorization_rules: optional_account(&ctx.accounts.authorization_rules),
+            authorization_rules_program: optional_account(
+                &ctx.accounts.authorization_rules_program,
+            ),
+            sysvar_instructions: optional_account(&ctx.accounts.sysvar_instructions),
+            fractional_token_mint: ctx.accounts.fractional_token_mint.to_account_info(),
+            fractional_token_program: ctx.accounts.fractional_token_program.to_account_info(),
+            payment_mint: ctx.accounts.payment_mint.to_account_info(),
+            creator_payment_account: ctx.accounts.creator_payment_account.to_account_info(),
+            escrow_payment_account: optional_account(&ctx.accounts.escrow_payment_account),
+            platform_config: &ctx.accounts.platform_config,
+            token_program: ctx.accounts.token_program.to_account_info(),
+            associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+            token_metadata_program: ctx.accounts.token_metadata_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+        },
+        VaultTerms {
+            total_fractions,
+            price_per_fraction,
+            fractional_decimals,
+            sale_terms,
+            royalty_bps,
+        },
+        clock.unix_timestamp,
+    )?;
+
+    // Name the shares after the original NFT, stored on the mint itself
+    let original_metadata = &ctx.accounts.original_nft_metadata;
+    let metadata = fractional_token_metadata(
+        &original_metadata.name,
+        &original_metadata.symbol,
+        &original_metadata.uri,
+    );
+    let token_metadata = TokenMetadata {
+        update_authority: OptionalNonZeroPubkey::try_from(Some(vault.key()))?,
+        mint: ctx.accounts.fractional_token_mint.key(),
+        name: metadata.name,
+        symbol: metadata.symbol,
+        uri: metadata.uri,
+        additional_metadata: vec![],
+    };
+
+    // Create the Token-2022 mint with its extensions
+    let mut extensions = vec![ExtensionType::TransferHook, ExtensionType::MetadataPointer];
+    if royalty_bps > 0 {
+        extensions.push(ExtensionType::TransferFeeConfig);
+    }
+    let mint_space = ExtensionType::try_calculate_account_len::<Token2022Mint>(&extensions)?;
+    // The metadata is reallocated onto the mint later, so fund its rent upfront
+    let mint_rent = Rent::get()?.minimum_balance(mint_space + token_metadata.tlv_size_of()?);
+
+    create_account(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            CreateAccount {
+                from: ctx.accounts.creator.to_account_info(),
+                to: ctx.accounts.fractional_token_mint.to_account_info(),
+            },
+        ),
+        mint_rent,
+        mint_space as u64,
+        ctx.accounts.fractional_token_program.key,
+    )?;
+
+    token_interface::transfer_hook_initialize(
+        CpiContext::new(
+            ctx.accounts.fractional_token_program.to_account_info(),
+            token_interface::TransferHookInitialize {
+                token_program_id: ctx.accounts.fractional_token_program.to_account_info(),
+                mint: ctx.accounts.fractional_token_mint.to_account_info(),
+            },
+        ),
+        Some(vault.key()),
+        Some(ctx.accounts.transfer_hook_program.key()),
+    )?;
+
+    token_interface::metadata_pointer_initialize(
+        CpiContext::new(
+            ctx.accounts.fractional_token_program.to_account_info(),
+            token_interface::MetadataPointerInitialize {
+                token_program_id: ctx.accounts.fractional_token_program.to_account_info(),
+                mint: ctx.accounts.fractional_token_mint.to_account_info(),
+            },
+        ),
+        Some(vault.key()),
+        Some(ctx.accounts.fractional_token_mint.key()),
+    )?;
+
+    if royalty_bps > 0 {
+        // The vault controls the fee and is the only one able to withdraw it
+        token_interface::transfer_fee_initialize(
+            CpiContext::new(
+                ctx.accounts.fractional_token_program.to_account_info(),
+                token_interface::TransferFeeInitialize {
+                    token_program_id: ctx.accounts.fractional_token_program.to_account_info(),
+                    mint: ctx.accounts.fractional_token_mint.to_account_info(),
+                },
+            ),
+            Some(&vault.key()),
+            Some(&vault.key()),
+            royalty_bps,
+            u64::MAX,
+        )?;
+    }
+
+    token_interface::initialize_mint2(
+        CpiContext::new(
+            ctx.accounts.fractional_token_program.to_account_info(),
+            token_interface::InitializeMint2 {
+                mint: ctx.accounts.fractional_token_mint.to_account_info(),
             },
         ),
-        1, // amount (1 NFT)
+        fractional_decimals,
+        &vault.key(),
+        Some(&vault.key()),
     )?;
 
+    create_associated_token_account(CpiContext::new(
+        ctx.accounts.associated_token_program.to_account_info(),
+        Create {
+            payer: ctx.accounts.creator.to_account_info(),
+            associated_token: ctx.accounts.vault_fractional_account.to_account_info(),
+            authority: vault.to_account_info(),
+            mint: ctx.accounts.fractional_token_mint.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            token_program: ctx.accounts.fractional_token_program.to_account_info(),
+        },
+    ))?;
+
     // Mint all fractional tokens to vault account
     let mint_key = ctx.accounts.original_nft_mint.key();
     let bump = ctx.bumps.vault;
     let seeds = &[b"vault", mint_key.as_ref(), &[bump]];
 
-    mint_to(
+    token_interface::mint_to(
         CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            MintTo {
+            ctx.accounts.fractional_token_program.to_account_info(),
+            token_interface::MintTo {
                 mint: ctx.accounts.fractional_token_mint.to_account_info(),
                 to: ctx.accounts.vault_fractional_account.to_account_info(),
                 authority: vault.to_account_info(),
@@ -81,56 +358,316 @@ pub fn fractionalize(
         total_fractions,
     )?;
 
-    msg!("NFT fractionalized successfully");
-    msg!("Total fractions: {}", total_fractions);
-    msg!("Price per fraction: {}", price_per_fraction);
-    msg!("Vault: {}", vault.key());
+    // Write the share metadata into the mint, with the vault as update authority
+    let fractional_token_name = token_metadata.name.clone();
+    token_interface::token_metadata_initialize(
+        CpiContext::new_with_signer(
+            ctx.accounts.fractional_token_program.to_account_info(),
+            token_interface::TokenMetadataInitialize {
+                program_id: ctx.accounts.fractional_token_program.to_account_info(),
+                metadata: ctx.accounts.fractional_token_mint.to_account_info(),
+                update_authority: vault.to_account_info(),
+                mint_authority: vault.to_account_info(),
+                mint: ctx.accounts.fractional_token_mint.to_account_info(),
+            },
+            &[seeds],
+        ),
+        token_metadata.name,
+        token_metadata.symbol,
+        token_metadata.uri,
+    )?;
+
+    msg!("NFT fractionalized into Token-2022 shares");
+    msg!("Fractional token: {}", fractional_token_name);
+    msg!("Royalty: {} bps", royalty_bps);
+    msg!(
+        "Transfer hook: {}",
+        ctx.accounts.transfer_hook_program.key()
+    );
+    emit_vault_fractionalized(vault, collection_mint, clock.unix_timestamp);
 
     Ok(())
 }
 
+/// Accounts shared by both fractionalize instructions
+struct VaultOpening<'a, 'info> {
+    creator: AccountInfo<'info>,
+    original_nft_mint: AccountInfo<'info>,
+    original_nft_metadata: &'a Account<'info, MetadataAccount>,
+    original_nft_master_edition: AccountInfo<'info>,
+    creator_nft_account: AccountInfo<'info>,
+    vault_nft_account: AccountInfo<'info>,
+    creator_nft_token_record: Option<AccountInfo<'info>>,
+    vault_nft_token_record: Option<AccountInfo<'info>>,
+    authorization_rules: Option<AccountInfo<'info>>,
+    authorization_rules_program: Option<AccountInfo<'info>>,
+    sysvar_instructions: Option<AccountInfo<'info>>,
+    fractional_token_mint: AccountInfo<'info>,
+    fractional_token_program: AccountInfo<'info>,
+    payment_mint: AccountInfo<'info>,
+    creator_payment_account: AccountInfo<'info>,
+    escrow_payment_account: Option<AccountInfo<'info>>,
+    platform_config: &'a PlatformConfig,
+    token_program: AccountInfo<'info>,
+    associated_token_program: AccountInfo<'info>,
+    token_metadata_program: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+}
+
+/// Shares and sale terms of a new vault
+struct VaultTerms {
+    total_fractions: u64,
+    price_per_fraction: u64,
+    fractional_decimals: u8,
+    sale_terms: SaleTerms,
+    royalty_bps: u16,
+}
+
+/// Vault setup shared by both fractionalize instructions
+///
+/// Validates the terms and the original NFT's collection, records the vault,
+/// creates the escrow of sales that need one and moves the NFT into the
+/// vault. Returns the NFT's verified collection; minting the shares is left
+/// to the caller.
+fn open_vault<'info>(
+    vault: &mut Account<'info, Vault>,
+    accounts: VaultOpening<'_, 'info>,
+    terms: VaultTerms,
+    now: i64,
+) -> Result<Pubkey> {
+    let sale_terms = terms.sale_terms;
+
+    // Validate inputs
+    require!(terms.total_fractions > 0, RwaError::InvalidTotalFractions);
+    require!(terms.price_per_fraction > 0, RwaError::InvalidPrice);
+    require!(
+        terms.fractional_decimals <= Vault::MAX_FRACTIONAL_DECIMALS,
+        RwaError::InvalidFractionalDecimals
+    );
+    validate_sale_window(sale_terms.starts_at, sale_terms.ends_at, now)?;
+    let collection_mint = accounts
+        .platform_config
+        .validate_nft_collection(accounts.original_nft_metadata)?;
+
+    // Initialize vault
+    vault.creator = accounts.creator.key();
+    vault.original_nft_mint = accounts.original_nft_mint.key();
+    vault.fractional_token_mint = accounts.fractional_token_mint.key();
+    vault.total_fractions = terms.total_fractions;
+    vault.price_per_fraction = terms.price_per_fraction;
+    vault.fractional_decimals = terms.fractional_decimals;
+    vault.sale_starts_at = sale_terms.starts_at;
+    vault.sale_ends_at = sale_terms.ends_at;
+    vault.min_raise = sale_terms.min_raise;
+    vault.amount_raised = 0;
+    vault.price_tiers = sale_terms.price_tiers;
+    vault.dutch_auction = sale_terms.dutch_auction;
+    vault.clearing_price = 0;
+    vault.proceeds_claimed = 0;
+    vault.validate_price_tiers()?;
+    vault.validate_soft_cap()?;
+    vault.validate_dutch_auction()?;
+    vault.fractions_sold = 0;
+    vault.is_sale_active = true;
+    vault.is_halted = false;
+    vault.created_at = now;
+    vault.creator_payment_account = accounts.creator_payment_account.key();
+    vault.payment_mint = accounts.payment_mint.key();
+    vault.record_original_nft(accounts.original_nft_metadata, collection_mint);
+    vault.fractional_token_program = accounts.fractional_token_program.key();
+    vault.royalty_bps = terms.royalty_bps;
+
+    // Soft-capped and uniform clearing price sales hold proceeds in the vault's escrow
+    if vault.uses_escrow() {
+        let escrow_payment_account = accounts
+            .escrow_payment_account
+            .ok_or(RwaError::MissingEscrowAccount)?;
+        create_associated_token_account_idempotent(CpiContext::new(
+            accounts.associated_token_program.clone(),
+            Create {
+                payer: accounts.creator.clone(),
+                associated_token: escrow_payment_account,
+                authority: vault.to_account_info(),
+                mint: accounts.payment_mint,
+                system_program: accounts.system_program.clone(),
+                token_program: accounts.token_program.clone(),
+            },
+        ))?;
+    }
+
+    // Transfer NFT from creator to vault, through the token metadata program for pNFTs
+    transfer_original_nft(
+        OriginalNftTransfer {
+            is_programmable: vault.is_programmable,
+            mint: accounts.original_nft_mint,
+            source: accounts.creator_nft_account,
+            source_owner: accounts.creator.clone(),
+            source_token_record: accounts.creator_nft_token_record,
+            destination: accounts.vault_nft_account,
+            destination_owner: vault.to_account_info(),
+            destination_token_record: accounts.vault_nft_token_record,
+            payer: accounts.creator,
+            metadata: Some(accounts.original_nft_metadata.to_account_info()),
+            edition: Some(accounts.original_nft_master_edition),
+            token_metadata_program: Some(accounts.token_metadata_program),
+            sysvar_instructions: accounts.sysvar_instructions,
+            authorization_rules: accounts.authorization_rules,
+            authorization_rules_program: accounts.authorization_rules_program,
+            token_program: accounts.token_program,
+            associated_token_program: accounts.associated_token_program,
+            system_program: accounts.system_program,
+        },
+        &[],
+    )?;
+
+    Ok(collection_mint)
+}
+
+/// Log and emit the fractionalization of a newly opened vault
+fn emit_vault_fractionalized(vault: &Account<Vault>, collection_mint: Pubkey, timestamp: i64) {
+    msg!("Total fractions: {}", vault.total_fractions);
+    msg!("Price per fraction: {}", vault.price_per_fraction);
+    msg!("Fractional decimals: {}", vault.fractional_decimals);
+    msg!("Payment mint: {}", vault.payment_mint);
+    msg!("Vault: {}", vault.key());
+
+    emit!(VaultFractionalized {
+        vault: vault.key(),
+        creator: vault.creator,
+        original_nft_mint: vault.original_nft_mint,
+        fractional_token_mint: vault.fractional_token_mint,
+        fractional_token_program: vault.fractional_token_program,
+        payment_mint: vault.payment_mint,
+        total_fractions: vault.total_fractions,
+        price_per_fraction: vault.price_per_fraction,
+        fractional_decimals: vault.fractional_decimals,
+        sale_starts_at: vault.sale_starts_at,
+        sale_ends_at: vault.sale_ends_at,
+        min_raise: vault.min_raise,
+        price_tiers: vault.price_tiers.clone(),
+        dutch_auction: vault.dutch_auction.clone(),
+        royalty_bps: vault.royalty_bps,
+        collection_mint,
+        timestamp,
+    });
+}
+
 /// Buy fractional tokens (SIMPLIFIED FOR HACKATHON)
 ///
 /// This instruction allows KYC-verified users to purchase fractional tokens.
-/// Uses standard SPL Token transfers for simplicity.
-pub fn buy_fractions(ctx: Context<BuyFractions>, num_fractions: u64) -> Result<()> {
+/// Token-2022 vaults must pass the transfer hook's extra accounts as
+/// remaining accounts.
+pub fn buy_fractions<'info>(
+    ctx: Context<'_, '_, '_, 'info, BuyFractions<'info>>,
+    num_fractions: u64,
+) -> Result<()> {
     let vault = &mut ctx.accounts.vault;
+    let clock = Clock::get()?;
 
     // Validate inputs
     require!(num_fractions > 0, RwaError::InvalidAmount);
     require!(vault.is_sale_active, RwaError::SaleNotActive);
     require!(
-        vault.fractions_sold + num_fractions <= vault.total_fractions,
+        vault.has_sale_started(clock.unix_timestamp),
+        RwaError::SaleNotStarted
+    );
+    require!(
+        !vault.has_sale_window_lapsed(clock.unix_timestamp),
+        RwaError::SaleEnded
+    );
+    let fractions_after_purchase = vault
+        .fractions_sold
+        .checked_add(num_fractions)
+        .ok_or(RwaError::MathOverflow)?;
+    require!(
+        fractions_after_purchase <= vault.total_fractions,
         RwaError::InsufficientFractions
     );
 
-    // Calculate total cost
-    let total_cost = num_fractions
-        .checked_mul(vault.price_per_fraction)
+    // Enforce platform investment limits against the buyer's cumulative position
+    let position = &mut ctx.accounts.investor_position;
+    let cumulative_fractions = position
+        .fractions_purchased
+        .checked_add(num_fractions)
         .ok_or(RwaError::MathOverflow)?;
+    let base_units_per_share = vault.base_units_per_share();
+    ctx.accounts.platform_config.validate_investment(
+        num_fractions,
+        cumulative_fractions,
+        base_units_per_share,
+    )?;
+    ctx.accounts.platform_config.validate_kyc_level_limit(
+        ctx.accounts.kyc_account.verification_level,
+        cumulative_fractions,
+        base_units_per_share,
+    )?;
 
-    // Calculate platform fee (5%)
-    let platform_fee = total_cost
-        .checked_mul(5)
-        .and_then(|x| x.checked_div(100))
-        .ok_or(RwaError::MathOverflow)?;
+    // Calculate total cost, rounded up for partial shares
+    let total_cost = vault.purchase_cost(num_fractions, clock.unix_timestamp)?;
+
+    // Calculate platform fee from the configured ratio
+    let platform_fee = ctx
+        .accounts
+        .platform_config
+        .calculate_purchase_fee(total_cost)?;
 
     let creator_amount = total_cost
         .checked_sub(platform_fee)
         .ok_or(RwaError::MathOverflow)?;
 
-    // Transfer payment from buyer to creator
-    transfer(
-        CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.buyer_payment_account.to_account_info(),
-                to: ctx.accounts.creator_payment_account.to_account_info(),
-                authority: ctx.accounts.buyer.to_account_info(),
-            },
-        ),
-        creator_amount,
-    )?;
+    let escrowed = vault.uses_escrow();
+    if escrowed {
+        // Hold the full payment in escrow until the soft cap is met or the
+        // auction clears
+        let escrow_payment_account = ctx
+            .accounts
+            .escrow_payment_account
+            .as_ref()
+            .ok_or(RwaError::MissingEscrowAccount)?;
+        transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.buyer_payment_account.to_account_info(),
+                    to: escrow_payment_account.to_account_info(),
+                    authority: ctx.accounts.buyer.to_account_info(),
+                },
+            ),
+            total_cost,
+        )?;
+        vault.amount_raised = vault
+            .amount_raised
+            .checked_add(total_cost)
+            .ok_or(RwaError::MathOverflow)?;
+    } else {
+        // Transfer payment from buyer to creator
+        transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.buyer_payment_account.to_account_info(),
+                    to: ctx.accounts.creator_payment_account.to_account_info(),
+                    authority: ctx.accounts.buyer.to_account_info(),
+                },
+            ),
+            creator_amount,
+        )?;
+
+        // Transfer platform fee from buyer to treasury
+        if platform_fee > 0 {
+            transfer(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.buyer_payment_account.to_account_info(),
+                        to: ctx.accounts.treasury_payment_account.to_account_info(),
+                        authority: ctx.accounts.buyer.to_account_info(),
+                    },
+                ),
+                platform_fee,
+            )?;
+        }
+    }
 
     // Transfer fractional tokens from vault to buyer
     let (_vault_pda, vault_bump) = Pubkey::find_program_address(
@@ -139,180 +676,1955 @@ pub fn buy_fractions(ctx: Context<BuyFractions>, num_fractions: u64) -> Result<(
     );
     let seeds = &[b"vault", vault.original_nft_mint.as_ref(), &[vault_bump]];
 
-    transfer(
-        CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.vault_fractional_account.to_account_info(),
-                to: ctx.accounts.buyer_fractional_account.to_account_info(),
-                authority: vault.to_account_info(),
-            },
+    if vault.royalty_bps > 0 {
+        // Primary sales are royalty-free: re-issue the fractions instead of
+        // transferring them, so the transfer fee only applies to secondary trades
+        reissue_fractions(
+            ctx.accounts.fractional_token_program.to_account_info(),
+            ctx.accounts.fractional_token_mint.to_account_info(),
+            ctx.accounts.vault_fractional_account.to_account_info(),
+            ctx.accounts.buyer_fractional_account.to_account_info(),
+            vault.to_account_info(),
+            seeds,
+            num_fractions,
+        )?;
+    } else {
+        // Resolves the transfer hook's extra accounts from the remaining accounts
+        invoke_transfer_checked(
+            ctx.accounts.fractional_token_program.key,
+            ctx.accounts.vault_fractional_account.to_account_info(),
+            ctx.accounts.fractional_token_mint.to_account_info(),
+            ctx.accounts.buyer_fractional_account.to_account_info(),
+            vault.to_account_info(),
+            ctx.remaining_accounts,
+            num_fractions,
+            ctx.accounts.fractional_token_mint.decimals,
             &[seeds],
-        ),
-        num_fractions,
-    )?;
+        )?;
+    }
 
     // Update vault state
-    vault.fractions_sold = vault
-        .fractions_sold
-        .checked_add(num_fractions)
+    vault.fractions_sold = fractions_after_purchase;
+    if let Some(price) = vault.auction_price(clock.unix_timestamp) {
+        vault.clearing_price = price;
+    }
+
+    // Update investor position
+    position.owner = ctx.accounts.buyer.key();
+    position.vault = vault.key();
+    position.fractions_purchased = cumulative_fractions;
+    position.total_paid = position
+        .total_paid
+        .checked_add(total_cost)
         .ok_or(RwaError::MathOverflow)?;
+    position.last_purchase_at = clock.unix_timestamp;
 
     msg!("Fractions purchased successfully");
     msg!("Buyer: {}", ctx.accounts.buyer.key());
     msg!("Fractions purchased: {}", num_fractions);
     msg!("Total cost: {}", total_cost);
+    msg!("Platform fee: {}", platform_fee);
+    msg!("Escrowed: {}", escrowed);
+
+    emit!(FractionsPurchased {
+        vault: vault.key(),
+        buyer: ctx.accounts.buyer.key(),
+        creator_payment_account: ctx.accounts.creator_payment_account.key(),
+        treasury_payment_account: ctx.accounts.treasury_payment_account.key(),
+        num_fractions,
+        total_cost,
+        platform_fee,
+        creator_amount,
+        escrowed,
+        fractions_sold: vault.fractions_sold,
+        timestamp: clock.unix_timestamp,
+    });
 
     Ok(())
 }
 
-/// Redeem NFT by burning all fractional tokens (SIMPLIFIED FOR HACKATHON)
+/// Close a vault's primary sale once its window lapses or it sells out
 ///
-/// This instruction allows a user who owns all fractional tokens to redeem the original NFT.
-pub fn redeem(ctx: Context<Redeem>) -> Result<()> {
+/// Permissionless, so a lapsed sale can be closed without the creator.
+pub fn close_sale(ctx: Context<CloseSale>) -> Result<()> {
     let vault = &mut ctx.accounts.vault;
+    let clock = Clock::get()?;
 
-    // Check if user owns all fractional tokens
+    let sold_out = vault.is_fully_sold();
     require!(
-        ctx.accounts.redeemer_fractional_account.amount == vault.total_fractions,
-        RwaError::InsufficientTokens
+        sold_out || vault.has_sale_window_lapsed(clock.unix_timestamp),
+        RwaError::SaleStillOpen
     );
 
-    // Burn all fractional tokens
+    vault.is_sale_active = false;
+    vault.sale_ended_at = Some(clock.unix_timestamp);
+
+    msg!("Sale closed for vault: {}", vault.key());
+    msg!(
+        "Fractions sold: {} of {}",
+        vault.fractions_sold,
+        vault.total_fractions
+    );
+
+    emit!(SaleClosed {
+        vault: vault.key(),
+        fractions_sold: vault.fractions_sold,
+        total_fractions: vault.total_fractions,
+        sold_out,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Change the base price of a vault's active sale (creator only)
+///
+/// Applies to fractions past the pricing tiers; a soft cap must stay within
+/// the vault's value at the new price.
+pub fn update_price(ctx: Context<UpdatePrice>, new_price_per_fraction: u64) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let clock = Clock::get()?;
+
+    require!(new_price_per_fraction > 0, RwaError::InvalidPrice);
+    require!(vault.dutch_auction.is_none(), RwaError::PriceSetByAuction);
+
+    let old_price_per_fraction = vault.price_per_fraction;
+    vault.price_per_fraction = new_price_per_fraction;
+    vault.validate_soft_cap()?;
+
+    msg!("Price updated for vault: {}", vault.key());
+    msg!(
+        "Price per fraction: {} -> {}",
+        old_price_per_fraction,
+        new_price_per_fraction
+    );
+
+    emit!(PriceUpdated {
+        vault: vault.key(),
+        old_price_per_fraction,
+        new_price_per_fraction,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Claim the escrowed proceeds of a soft-capped or uniform clearing price sale
+///
+/// Soft-capped sales pay out once the cap is met, and can be claimed again as
+/// further purchases land in escrow. Uniform clearing price auctions pay out
+/// the sold fractions at the clearing price once the auction closes, leaving
+/// the rest in escrow for buyers' refunds. The platform fee is taken from the
+/// claimed amount.
+pub fn claim_proceeds(ctx: Context<ClaimProceeds>) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let clock = Clock::get()?;
+
+    require!(vault.is_soft_cap_met(), RwaError::SoftCapNotMet);
+    let amount = if vault.has_uniform_clearing_price() {
+        require!(!vault.is_sale_active, RwaError::AuctionNotClosed);
+        vault
+            .clearing_cost(vault.fractions_sold)?
+            .saturating_sub(vault.proceeds_claimed)
+    } else {
+        ctx.accounts.escrow_payment_account.amount
+    };
+    require!(amount > 0, RwaError::NothingToClaim);
+
+    let platform_fee = ctx
+        .accounts
+        .platform_config
+        .calculate_purchase_fee(amount)?;
+    let creator_amount = amount
+        .checked_sub(platform_fee)
+        .ok_or(RwaError::MathOverflow)?;
+
     let (_vault_pda, vault_bump) = Pubkey::find_program_address(
         &[b"vault", vault.original_nft_mint.as_ref()],
         ctx.program_id,
     );
     let seeds = &[b"vault", vault.original_nft_mint.as_ref(), &[vault_bump]];
 
-    burn(
-        CpiContext::new(
+    transfer(
+        CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
-            Burn {
+            Transfer {
+                from: ctx.accounts.escrow_payment_account.to_account_info(),
+                to: ctx.accounts.creator_payment_account.to_account_info(),
+                authority: vault.to_account_info(),
+            },
+            &[seeds],
+        ),
+        creator_amount,
+    )?;
+
+    if platform_fee > 0 {
+        transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.escrow_payment_account.to_account_info(),
+                    to: ctx.accounts.treasury_payment_account.to_account_info(),
+                    authority: vault.to_account_info(),
+                },
+                &[seeds],
+            ),
+            platform_fee,
+        )?;
+    }
+
+    vault.proceeds_claimed = vault
+        .proceeds_claimed
+        .checked_add(amount)
+        .ok_or(RwaError::MathOverflow)?;
+
+    msg!("Proceeds claimed for vault: {}", vault.key());
+    msg!("Creator amount: {}", creator_amount);
+    msg!("Platform fee: {}", platform_fee);
+
+    emit!(ProceedsClaimed {
+        vault: vault.key(),
+        creator: vault.creator,
+        amount,
+        platform_fee,
+        creator_amount,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Burn a buyer's fractions of a sale that ended below its soft cap and
+/// refund everything they paid from escrow
+pub fn refund_fractions(ctx: Context<RefundFractions>) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let position = &mut ctx.accounts.investor_position;
+    let clock = Clock::get()?;
+
+    require!(
+        vault.is_refunding(clock.unix_timestamp),
+        RwaError::RefundsNotAvailable
+    );
+    let fractions = position.fractions_purchased;
+    let amount = position.total_paid;
+    require!(fractions > 0, RwaError::NothingToRefund);
+
+    token_interface::burn(
+        CpiContext::new(
+            ctx.accounts.fractional_token_program.to_account_info(),
+            token_interface::Burn {
                 mint: ctx.accounts.fractional_token_mint.to_account_info(),
-                from: ctx.accounts.redeemer_fractional_account.to_account_info(),
-                authority: ctx.accounts.redeemer.to_account_info(),
+                from: ctx.accounts.buyer_fractional_account.to_account_info(),
+                authority: ctx.accounts.buyer.to_account_info(),
             },
         ),
-        vault.total_fractions,
+        fractions,
     )?;
 
-    // Transfer NFT from vault to redeemer
+    let (_vault_pda, vault_bump) = Pubkey::find_program_address(
+        &[b"vault", vault.original_nft_mint.as_ref()],
+        ctx.program_id,
+    );
+    let seeds = &[b"vault", vault.original_nft_mint.as_ref(), &[vault_bump]];
+
     transfer(
         CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
             Transfer {
-                from: ctx.accounts.vault_nft_account.to_account_info(),
-                to: ctx.accounts.redeemer_nft_account.to_account_info(),
+                from: ctx.accounts.escrow_payment_account.to_account_info(),
+                to: ctx.accounts.buyer_payment_account.to_account_info(),
                 authority: vault.to_account_info(),
             },
             &[seeds],
         ),
-        1, // Transfer 1 NFT
+        amount,
     )?;
 
-    // Close vault account
-    vault.is_sale_active = false;
+    // Unwind the purchase
+    position.fractions_purchased = 0;
+    position.total_paid = 0;
+    vault.fractions_sold = vault
+        .fractions_sold
+        .checked_sub(fractions)
+        .ok_or(RwaError::MathOverflow)?;
+    vault.amount_raised = vault
+        .amount_raised
+        .checked_sub(amount)
+        .ok_or(RwaError::MathOverflow)?;
 
-    msg!("NFT redeemed successfully");
-    msg!("Redeemer: {}", ctx.accounts.redeemer.key());
-    msg!("Vault closed: {}", vault.key());
+    msg!("Fractions refunded");
+    msg!("Buyer: {}", ctx.accounts.buyer.key());
+    msg!("Fractions burned: {}", fractions);
+    msg!("Amount refunded: {}", amount);
+
+    emit!(FractionsRefunded {
+        vault: vault.key(),
+        buyer: ctx.accounts.buyer.key(),
+        fractions_burned: fractions,
+        amount,
+        timestamp: clock.unix_timestamp,
+    });
 
     Ok(())
 }
 
-/// Register a user for KYC verification (hackathon-friendly)
-///
-/// This instruction creates a simple KYC account for a user with minimal requirements.
-/// Perfect for hackathon demos where you want to show the flow without barriers.
-pub fn register_kyc(ctx: Context<RegisterKyc>) -> Result<()> {
-    let kyc_account = &mut ctx.accounts.kyc_account;
+/// Refund an early buyer of a uniform clearing price auction the difference
+/// between what they paid and their fractions' cost at the clearing price
+pub fn claim_auction_refund(ctx: Context<ClaimAuctionRefund>) -> Result<()> {
+    let vault = &ctx.accounts.vault;
+    let position = &mut ctx.accounts.investor_position;
     let clock = Clock::get()?;
 
-    // Initialize simple KYC account
-    kyc_account.user = ctx.accounts.user.key();
-    kyc_account.is_verified = false;
-    kyc_account.verification_method = VerificationMethod::AdminApproval;
-    kyc_account.verified_at = 0;
-    kyc_account.email = None;
-    kyc_account.country = None;
-    kyc_account.verification_level = 0;
+    require!(!vault.is_sale_active, RwaError::AuctionNotClosed);
+    let clearing_cost = vault.clearing_cost(position.fractions_purchased)?;
+    let amount = position.total_paid.saturating_sub(clearing_cost);
+    require!(amount > 0, RwaError::NothingToRefund);
 
-    msg!("KYC registration initiated");
-    msg!("User: {}", kyc_account.user);
+    let (_vault_pda, vault_bump) = Pubkey::find_program_address(
+        &[b"vault", vault.original_nft_mint.as_ref()],
+        ctx.program_id,
+    );
+    let seeds = &[b"vault", vault.original_nft_mint.as_ref(), &[vault_bump]];
+
+    transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.escrow_payment_account.to_account_info(),
+                to: ctx.accounts.buyer_payment_account.to_account_info(),
+                authority: vault.to_account_info(),
+            },
+            &[seeds],
+        ),
+        amount,
+    )?;
+
+    // The buyer has now paid the clearing price
+    position.total_paid = clearing_cost;
+
+    msg!("Auction refund claimed");
+    msg!("Buyer: {}", ctx.accounts.buyer.key());
+    msg!("Clearing price: {}", vault.clearing_price);
+    msg!("Amount refunded: {}", amount);
+
+    emit!(AuctionRefundClaimed {
+        vault: vault.key(),
+        buyer: ctx.accounts.buyer.key(),
+        clearing_price: vault.clearing_price,
+        amount,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Return the NFT of a sale that ended below its soft cap to its creator
+pub fn reclaim_nft(ctx: Context<ReclaimNft>) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let clock = Clock::get()?;
+
+    require!(
+        vault.is_refunding(clock.unix_timestamp),
+        RwaError::RefundsNotAvailable
+    );
+
+    let (_vault_pda, vault_bump) = Pubkey::find_program_address(
+        &[b"vault", vault.original_nft_mint.as_ref()],
+        ctx.program_id,
+    );
+    let seeds = &[b"vault", vault.original_nft_mint.as_ref(), &[vault_bump]];
+
+    // Transfer NFT from vault to creator, through the token metadata program for pNFTs
+    transfer_original_nft(
+        OriginalNftTransfer {
+            is_programmable: vault.is_programmable,
+            mint: ctx.accounts.original_nft_mint.to_account_info(),
+            source: ctx.accounts.vault_nft_account.to_account_info(),
+            source_owner: vault.to_account_info(),
+            source_token_record: optional_account(&ctx.accounts.vault_nft_token_record),
+            destination: ctx.accounts.creator_nft_account.to_account_info(),
+            destination_owner: ctx.accounts.creator.to_account_info(),
+            destination_token_record: optional_account(&ctx.accounts.creator_nft_token_record),
+            payer: ctx.accounts.creator.to_account_info(),
+            metadata: optional_account(&ctx.accounts.original_nft_metadata),
+            edition: optional_account(&ctx.accounts.original_nft_master_edition),
+            token_metadata_program: optional_account(&ctx.accounts.token_metadata_program),
+            sysvar_instructions: optional_account(&ctx.accounts.sysvar_instructions),
+            authorization_rules: optional_account(&ctx.accounts.authorization_rules),
+            authorization_rules_program: optional_account(
+                &ctx.accounts.authorization_rules_program,
+            ),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+        },
+        &[seeds],
+    )?;
+
+    vault.is_sale_active = false;
+    vault.sale_ended_at.get_or_insert(clock.unix_timestamp);
+
+    msg!("NFT reclaimed by creator: {}", vault.creator);
+    msg!("Vault: {}", vault.key());
+
+    emit!(NftReclaimed {
+        vault: vault.key(),
+        creator: vault.creator,
+        original_nft_mint: vault.original_nft_mint,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Cancel a vault before any fractions are sold
+///
+/// Burns the unsold fractions, returns the NFT to the creator and closes the
+/// vault's token accounts and the vault itself, refunding their rent to the
+/// creator. The vault is also the fractional mint's authority, so the mint is
+/// left without one.
+pub fn cancel_vault(ctx: Context<CancelVault>) -> Result<()> {
+    let vault = &ctx.accounts.vault;
+    let clock = Clock::get()?;
+
+    let (_vault_pda, vault_bump) = Pubkey::find_program_address(
+        &[b"vault", vault.original_nft_mint.as_ref()],
+        ctx.program_id,
+    );
+    let seeds = &[b"vault", vault.original_nft_mint.as_ref(), &[vault_bump]];
+
+    // Burn the unsold fractions and close the vault's fractional account
+    let fractions_burned = ctx.accounts.vault_fractional_account.amount;
+    if fractions_burned > 0 {
+        token_interface::burn(
+            CpiContext::new_with_signer(
+                ctx.accounts.fractional_token_program.to_account_info(),
+                token_interface::Burn {
+                    mint: ctx.accounts.fractional_token_mint.to_account_info(),
+                    from: ctx.accounts.vault_fractional_account.to_account_info(),
+                    authority: vault.to_account_info(),
+                },
+                &[seeds],
+            ),
+            fractions_burned,
+        )?;
+    }
+    token_interface::close_account(CpiContext::new_with_signer(
+        ctx.accounts.fractional_token_program.to_account_info(),
+        token_interface::CloseAccount {
+            account: ctx.accounts.vault_fractional_account.to_account_info(),
+            destination: ctx.accounts.creator.to_account_info(),
+            authority: vault.to_account_info(),
+        },
+        &[seeds],
+    ))?;
+
+    // Transfer NFT from vault to creator, unless a failed soft-capped sale
+    // already returned it
+    let nft_in_vault = ctx.accounts.vault_nft_account.amount > 0;
+    if nft_in_vault {
+        transfer_original_nft(
+            OriginalNftTransfer {
+                is_programmable: vault.is_programmable,
+                mint: ctx.accounts.original_nft_mint.to_account_info(),
+                source: ctx.accounts.vault_nft_account.to_account_info(),
+                source_owner: vault.to_account_info(),
+                source_token_record: optional_account(&ctx.accounts.vault_nft_token_record),
+                destination: ctx.accounts.creator_nft_account.to_account_info(),
+                destination_owner: ctx.accounts.creator.to_account_info(),
+                destination_token_record: optional_account(&ctx.accounts.creator_nft_token_record),
+                payer: ctx.accounts.creator.to_account_info(),
+                metadata: optional_account(&ctx.accounts.original_nft_metadata),
+                edition: optional_account(&ctx.accounts.original_nft_master_edition),
+                token_metadata_program: optional_account(&ctx.accounts.token_metadata_program),
+                sysvar_instructions: optional_account(&ctx.accounts.sysvar_instructions),
+                authorization_rules: optional_account(&ctx.accounts.authorization_rules),
+                authorization_rules_program: optional_account(
+                    &ctx.accounts.authorization_rules_program,
+                ),
+                token_program: ctx.accounts.token_program.to_account_info(),
+                associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+            },
+            &[seeds],
+        )?;
+    }
+
+    // The token metadata program closes the vault's pNFT token account itself
+    // when moving the NFT out; an account recreated empty is closed here
+    if !vault.is_programmable || !nft_in_vault {
+        token_interface::close_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            token_interface::CloseAccount {
+                account: ctx.accounts.vault_nft_account.to_account_info(),
+                destination: ctx.accounts.creator.to_account_info(),
+                authority: vault.to_account_info(),
+            },
+            &[seeds],
+        ))?;
+    }
+
+    // Close the escrow of a soft-capped or uniform clearing price sale. Every
+    // payment was refunded, so any balance left is a stray deposit for the creator
+    if vault.uses_escrow() {
+        let escrow_payment_account = ctx
+            .accounts
+            .escrow_payment_account
+            .as_ref()
+            .ok_or(RwaError::MissingEscrowAccount)?;
+        if escrow_payment_account.amount > 0 {
+            transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: escrow_payment_account.to_account_info(),
+                        to: ctx.accounts.creator_payment_account.to_account_info(),
+                        authority: vault.to_account_info(),
+                    },
+                    &[seeds],
+                ),
+                escrow_payment_account.amount,
+            )?;
+        }
+        token_interface::close_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            token_interface::CloseAccount {
+                account: escrow_payment_account.to_account_info(),
+                destination: ctx.accounts.creator.to_account_info(),
+                authority: vault.to_account_info(),
+            },
+            &[seeds],
+        ))?;
+    }
+
+    msg!("Vault cancelled: {}", vault.key());
+    msg!("Creator: {}", vault.creator);
+    msg!("Fractions burned: {}", fractions_burned);
+
+    emit!(VaultCancelled {
+        vault: vault.key(),
+        creator: vault.creator,
+        original_nft_mint: vault.original_nft_mint,
+        fractions_burned,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Redeem NFT by burning all fractional tokens (SIMPLIFIED FOR HACKATHON)
+///
+/// This instruction allows a user who owns all fractional tokens to redeem the original NFT.
+///
+/// Every secondary transfer of a royalty vault's shares withholds part of them,
+/// so no wallet could ever hold all of them again. Instead, royalties still
+/// withheld on the redeemer's account, on the holder accounts passed as
+/// remaining accounts and on the mint count towards the redeemer's shares and
+/// are burned with them. Royalties `harvest_royalties` already paid out must be
+/// bought back like any other shares.
+pub fn redeem<'info>(ctx: Context<'_, '_, '_, 'info, Redeem<'info>>) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let clock = Clock::get()?;
+
+    let (_vault_pda, vault_bump) = Pubkey::find_program_address(
+        &[b"vault", vault.original_nft_mint.as_ref()],
+        ctx.program_id,
+    );
+    let seeds = &[b"vault", vault.original_nft_mint.as_ref(), &[vault_bump]];
+
+    // Collect the withheld royalties into the vault
+    let withheld = if vault.royalty_bps > 0 {
+        let mut sources = vec![ctx.accounts.redeemer_fractional_account.to_account_info()];
+        sources.extend(ctx.remaining_accounts.iter().cloned());
+        withdraw_withheld_fractions(
+            ctx.accounts.fractional_token_program.to_account_info(),
+            ctx.accounts.fractional_token_mint.to_account_info(),
+            &mut ctx.accounts.vault_fractional_account,
+            vault.to_account_info(),
+            seeds,
+            sources,
+        )?
+    } else {
+        0
+    };
+
+    // Check if user owns all fractional tokens
+    let redeemer_fractions = ctx.accounts.redeemer_fractional_account.amount;
+    require!(
+        redeemer_fractions.checked_add(withheld) == Some(vault.total_fractions),
+        RwaError::InsufficientTokens
+    );
+
+    // Burn all fractional tokens
+    token_interface::burn(
+        CpiContext::new(
+            ctx.accounts.fractional_token_program.to_account_info(),
+            token_interface::Burn {
+                mint: ctx.accounts.fractional_token_mint.to_account_info(),
+                from: ctx.accounts.redeemer_fractional_account.to_account_info(),
+                authority: ctx.accounts.redeemer.to_account_info(),
+            },
+        ),
+        redeemer_fractions,
+    )?;
+
+    if withheld > 0 {
+        token_interface::burn(
+            CpiContext::new_with_signer(
+                ctx.accounts.fractional_token_program.to_account_info(),
+                token_interface::Burn {
+                    mint: ctx.accounts.fractional_token_mint.to_account_info(),
+                    from: ctx.accounts.vault_fractional_account.to_account_info(),
+                    authority: vault.to_account_info(),
+                },
+                &[seeds],
+            ),
+            withheld,
+        )?;
+    }
+
+    // Transfer NFT from vault to redeemer, through the token metadata program for pNFTs
+    transfer_original_nft(
+        OriginalNftTransfer {
+            is_programmable: vault.is_programmable,
+            mint: ctx.accounts.original_nft_mint.to_account_info(),
+            source: ctx.accounts.vault_nft_account.to_account_info(),
+            source_owner: vault.to_account_info(),
+            source_token_record: optional_account(&ctx.accounts.vault_nft_token_record),
+            destination: ctx.accounts.redeemer_nft_account.to_account_info(),
+            destination_owner: ctx.accounts.redeemer.to_account_info(),
+            destination_token_record: optional_account(&ctx.accounts.redeemer_nft_token_record),
+            payer: ctx.accounts.redeemer.to_account_info(),
+            metadata: optional_account(&ctx.accounts.original_nft_metadata),
+            edition: optional_account(&ctx.accounts.original_nft_master_edition),
+            token_metadata_program: optional_account(&ctx.accounts.token_metadata_program),
+            sysvar_instructions: optional_account(&ctx.accounts.sysvar_instructions),
+            authorization_rules: optional_account(&ctx.accounts.authorization_rules),
+            authorization_rules_program: optional_account(
+                &ctx.accounts.authorization_rules_program,
+            ),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+        },
+        &[seeds],
+    )?;
+
+    // Close vault account
+    vault.is_sale_active = false;
+
+    msg!("NFT redeemed successfully");
+    msg!("Redeemer: {}", ctx.accounts.redeemer.key());
+    msg!("Vault closed: {}", vault.key());
+
+    emit!(NftRedeemed {
+        vault: vault.key(),
+        redeemer: ctx.accounts.redeemer.key(),
+        original_nft_mint: vault.original_nft_mint,
+        fractions_burned: vault.total_fractions,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Register a user for KYC verification (hackathon-friendly)
+///
+/// This instruction creates a simple KYC account for a user with minimal requirements.
+/// Perfect for hackathon demos where you want to show the flow without barriers.
+pub fn register_kyc(ctx: Context<RegisterKyc>, country: String) -> Result<()> {
+    let kyc_account = &mut ctx.accounts.kyc_account;
+    let clock = Clock::get()?;
+
+    let country_code = JurisdictionConfig::parse_country_code(&country)?;
+
+    // Initialize simple KYC account
+    kyc_account.user = ctx.accounts.user.key();
+    kyc_account.status = KycStatus::Pending;
+    kyc_account.status_reason = None;
+    kyc_account.status_updated_at = clock.unix_timestamp;
+    kyc_account.verification_method = VerificationMethod::AdminApproval;
+    kyc_account.verified_at = 0;
+    kyc_account.pii_commitment = [0; 32];
+    kyc_account.attestation_signature = [0; 64];
+    kyc_account.attested_by = Pubkey::default();
+    kyc_account.country = Some(country_code);
+    kyc_account.verification_level = 0;
+    kyc_account.expires_at = 0;
+    kyc_account.last_attestation_nonce = 0;
+
+    msg!("KYC registration initiated");
+    msg!("User: {}", kyc_account.user);
+    msg!("Country: {}", country);
     msg!("Registered at: {}", clock.unix_timestamp);
 
+    emit!(KycRegistered {
+        user: kyc_account.user,
+        timestamp: clock.unix_timestamp,
+    });
+
     Ok(())
 }
 
-/// Verify a user's KYC status (admin only)
+/// Verify a user's KYC status (KYC officer only)
 ///
 /// This instruction allows platform admins to manually verify users.
 /// This is the main verification method for hackathon demos.
+/// The officer records a commitment to the off-chain dossier and their
+/// signature over `user || pii_commitment` instead of any personal data. The
+/// signature is checked by the Ed25519 instruction preceding this one.
 pub fn verify_kyc(
     ctx: Context<VerifyKyc>,
     verification_method: VerificationMethod,
     verification_level: u8,
+    pii_commitment: [u8; 32],
 ) -> Result<()> {
-    let kyc_account = &mut ctx.accounts.kyc_account;
     let clock = Clock::get()?;
 
+    require!(pii_commitment != [0; 32], RwaError::InvalidPiiCommitment);
+
+    // The Ed25519 program has already checked the signature; confirm the
+    // officer signed this user's commitment
+    let (signer, attestation_signature, message) =
+        preceding_ed25519_signature(&ctx.accounts.instructions_sysvar.to_account_info())?;
+    require_keys_eq!(
+        signer,
+        ctx.accounts.admin.key(),
+        RwaError::InvalidAttestationSignature
+    );
+    require!(
+        message == [ctx.accounts.kyc_account.user.as_ref(), &pii_commitment].concat(),
+        RwaError::AttestationMismatch
+    );
+
+    let kyc_account = &mut ctx.accounts.kyc_account;
+
     // Verify user
-    kyc_account.is_verified = true;
+    kyc_account.status = KycStatus::Verified;
+    kyc_account.status_reason = None;
+    kyc_account.status_updated_at = clock.unix_timestamp;
     kyc_account.verification_method = verification_method.clone();
     kyc_account.verified_at = clock.unix_timestamp;
     kyc_account.verification_level = verification_level.min(3); // Max level 3
+    kyc_account.pii_commitment = pii_commitment;
+    kyc_account.attestation_signature = attestation_signature;
+    kyc_account.attested_by = ctx.accounts.admin.key();
+    kyc_account.expires_at = ctx
+        .accounts
+        .platform_config
+        .kyc_expiry_from(clock.unix_timestamp)?;
 
     msg!("KYC verification completed");
     msg!("User: {}", kyc_account.user);
     msg!("Method: {:?}", verification_method);
     msg!("Level: {}", verification_level);
+    msg!("Expires at: {}", kyc_account.expires_at);
     msg!("Admin: {}", ctx.accounts.admin.key());
 
+    emit!(KycVerified {
+        user: kyc_account.user,
+        verifier: ctx.accounts.admin.key(),
+        verification_method,
+        verification_level: kyc_account.verification_level,
+        pii_commitment,
+        expires_at: kyc_account.expires_at,
+        timestamp: clock.unix_timestamp,
+    });
+
     Ok(())
 }
 
-/// Update platform configuration (admin only)
-pub fn update_platform_config(
-    ctx: Context<UpdatePlatformConfig>,
-    platform_fee_numerator: u16,
-    platform_fee_denominator: u16,
-    min_investment_amount: u64,
-    max_investment_amount: u64,
+/// Reject a user's KYC application (KYC officer only)
+///
+/// The user may submit a new application through `request_kyc_reverification`.
+pub fn reject_kyc(ctx: Context<UpdateKycStatus>, reason: KycReasonCode) -> Result<()> {
+    set_kyc_status(ctx, KycStatus::Rejected, reason)
+}
+
+/// Suspend a user's KYC (KYC officer only)
+///
+/// A suspended user can neither buy nor redeem, and cannot reset their own
+/// record; a KYC officer lifts the suspension by verifying them again.
+pub fn suspend_kyc(ctx: Context<UpdateKycStatus>, reason: KycReasonCode) -> Result<()> {
+    set_kyc_status(ctx, KycStatus::Suspended, reason)
+}
+
+/// Permanently revoke a user's KYC (KYC officer only)
+pub fn revoke_kyc(ctx: Context<UpdateKycStatus>, reason: KycReasonCode) -> Result<()> {
+    set_kyc_status(ctx, KycStatus::Revoked, reason)
+}
+
+/// Record a rejection, suspension or revocation on a KYC account
+fn set_kyc_status(
+    ctx: Context<UpdateKycStatus>,
+    status: KycStatus,
+    reason: KycReasonCode,
 ) -> Result<()> {
-    let platform_config = &mut ctx.accounts.platform_config;
+    let kyc_account = &mut ctx.accounts.kyc_account;
+    let clock = Clock::get()?;
+
+    kyc_account.status = status;
+    kyc_account.status_reason = Some(reason);
+    kyc_account.status_updated_at = clock.unix_timestamp;
+
+    msg!("KYC status updated");
+    msg!("User: {}", kyc_account.user);
+    msg!("Status: {:?}", status);
+    msg!("Reason: {:?}", reason);
+    msg!("Admin: {}", ctx.accounts.admin.key());
+
+    emit!(KycStatusUpdated {
+        user: kyc_account.user,
+        authority: ctx.accounts.admin.key(),
+        status,
+        reason,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Request KYC re-verification
+///
+/// Resets the caller's KYC record in place so an expired (or soon to expire)
+/// verification can be renewed without closing the PDA.
+pub fn request_kyc_reverification(ctx: Context<RequestKycReverification>) -> Result<()> {
+    let kyc_account = &mut ctx.accounts.kyc_account;
+    let clock = Clock::get()?;
+
+    kyc_account.status = KycStatus::Pending;
+    kyc_account.status_reason = None;
+    kyc_account.status_updated_at = clock.unix_timestamp;
+    kyc_account.verified_at = 0;
+    kyc_account.verification_level = 0;
+    kyc_account.expires_at = 0;
+
+    msg!("KYC re-verification requested");
+    msg!("User: {}", kyc_account.user);
+
+    emit!(KycReverificationRequested {
+        user: kyc_account.user,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Create the registry of trusted KYC providers (super admin only)
+pub fn initialize_kyc_attestor_registry(ctx: Context<InitializeKycAttestorRegistry>) -> Result<()> {
+    let attestor_registry = &mut ctx.accounts.attestor_registry;
+    let clock = Clock::get()?;
+
+    attestor_registry.attestors = Vec::new();
+    attestor_registry.updated_at = clock.unix_timestamp;
+
+    msg!("KYC provider registry initialized");
+
+    emit!(KycAttestorRegistryInitialized {
+        admin: ctx.accounts.admin.key(),
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Trust a KYC provider's Ed25519 key for attestations (KYC officer only)
+pub fn add_kyc_attestor(ctx: Context<ManageKycAttestors>, attestor: Pubkey) -> Result<()> {
+    let attestor_registry = &mut ctx.accounts.attestor_registry;
     let clock = Clock::get()?;
 
-    // Validate inputs
-    require!(platform_fee_denominator > 0, RwaError::InvalidPrice);
     require!(
-        platform_fee_numerator <= platform_fee_denominator,
-        RwaError::InvalidPrice
+        !attestor_registry.is_trusted(&attestor),
+        RwaError::AttestorAlreadyTrusted
     );
-    require!(min_investment_amount > 0, RwaError::InvalidAmount);
     require!(
-        max_investment_amount > min_investment_amount,
-        RwaError::InvalidAmount
+        attestor_registry.attestors.len() < KycAttestorRegistry::MAX_ATTESTORS,
+        RwaError::AttestorListFull
     );
 
-    // Update configuration
-    platform_config.platform_fee_numerator = platform_fee_numerator;
-    platform_config.platform_fee_denominator = platform_fee_denominator;
-    platform_config.min_investment_amount = min_investment_amount;
-    platform_config.max_investment_amount = max_investment_amount;
-    platform_config.updated_at = clock.unix_timestamp;
+    attestor_registry.attestors.push(attestor);
+    attestor_registry.updated_at = clock.unix_timestamp;
 
-    msg!("Platform configuration updated");
-    msg!(
-        "Fee: {}/{}",
-        platform_fee_numerator,
-        platform_fee_denominator
+    msg!("KYC provider trusted: {}", attestor);
+
+    emit!(KycAttestorUpdated {
+        authority: ctx.accounts.admin.key(),
+        attestor,
+        trusted: true,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Stop trusting a KYC provider's key (KYC officer only)
+///
+/// Verifications the provider already attested stay in place until they
+/// expire or are revoked.
+pub fn remove_kyc_attestor(ctx: Context<ManageKycAttestors>, attestor: Pubkey) -> Result<()> {
+    let attestor_registry = &mut ctx.accounts.attestor_registry;
+    let clock = Clock::get()?;
+
+    require!(
+        attestor_registry.is_trusted(&attestor),
+        RwaError::AttestorNotTrusted
     );
-    msg!(
-        "Investment range: {} - {}",
-        min_investment_amount,
-        max_investment_amount
+
+    attestor_registry
+        .attestors
+        .retain(|trusted| trusted != &attestor);
+    attestor_registry.updated_at = clock.unix_timestamp;
+
+    msg!("KYC provider removed: {}", attestor);
+
+    emit!(KycAttestorUpdated {
+        authority: ctx.accounts.admin.key(),
+        attestor,
+        trusted: false,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Verify the caller's KYC from a trusted provider's signed attestation
+///
+/// The transaction must carry, immediately before this instruction, an
+/// Ed25519 program instruction in which a trusted provider signs
+/// `KycAttestorRegistry::attestation_message` for these fields. Nonces must
+/// increase per user so an attestation cannot be replayed.
+pub fn submit_kyc_attestation(
+    ctx: Context<SubmitKycAttestation>,
+    verification_level: u8,
+    expires_at: i64,
+    nonce: u64,
+) -> Result<()> {
+    let clock = Clock::get()?;
+
+    require!(
+        (1..=3).contains(&verification_level),
+        RwaError::InvalidVerificationLevel
+    );
+    require!(
+        expires_at > clock.unix_timestamp,
+        RwaError::AttestationExpired
+    );
+    require!(
+        nonce > ctx.accounts.kyc_account.last_attestation_nonce,
+        RwaError::AttestationNonceReused
+    );
+
+    // The Ed25519 program has already checked the signature; confirm who
+    // signed and what was signed
+    let (attestor, signature, message) =
+        preceding_ed25519_signature(&ctx.accounts.instructions_sysvar.to_account_info())?;
+
+    require!(
+        ctx.accounts.attestor_registry.is_trusted(&attestor),
+        RwaError::AttestorNotTrusted
+    );
+    require!(
+        message
+            == KycAttestorRegistry::attestation_message(
+                &ctx.accounts.user.key(),
+                verification_level,
+                expires_at,
+                nonce,
+            ),
+        RwaError::AttestationMismatch
     );
 
+    // Never extend a verification past the platform's validity period
+    let platform_expiry = ctx
+        .accounts
+        .platform_config
+        .kyc_expiry_from(clock.unix_timestamp)?;
+    let expires_at = if platform_expiry == 0 {
+        expires_at
+    } else {
+        expires_at.min(platform_expiry)
+    };
+
+    let kyc_account = &mut ctx.accounts.kyc_account;
+    kyc_account.status = KycStatus::Verified;
+    kyc_account.status_reason = None;
+    kyc_account.status_updated_at = clock.unix_timestamp;
+    kyc_account.verification_method = VerificationMethod::ProviderAttestation;
+    kyc_account.verified_at = clock.unix_timestamp;
+    kyc_account.verification_level = verification_level;
+    kyc_account.expires_at = expires_at;
+    kyc_account.attestation_signature = signature;
+    kyc_account.attested_by = attestor;
+    kyc_account.last_attestation_nonce = nonce;
+
+    msg!("KYC attestation accepted");
+    msg!("User: {}", kyc_account.user);
+    msg!("Provider: {}", attestor);
+    msg!("Level: {}", verification_level);
+    msg!("Expires at: {}", expires_at);
+
+    emit!(KycVerified {
+        user: kyc_account.user,
+        verifier: attestor,
+        verification_method: VerificationMethod::ProviderAttestation,
+        verification_level,
+        pii_commitment: kyc_account.pii_commitment,
+        expires_at,
+        timestamp: clock.unix_timestamp,
+    });
+
     Ok(())
 }
+
+/// Check whether a wallet passes KYC for a transaction type (read-only)
+///
+/// Meant to be called by other programs over CPI: the outcome is returned
+/// through `set_return_data` rather than as an error, so callers decide how
+/// to handle a failed check.
+pub fn check_kyc(
+    ctx: Context<CheckKyc>,
+    transaction_type: TransactionType,
+) -> Result<KycCheckResult> {
+    let clock = Clock::get()?;
+    let kyc_info = ctx.accounts.kyc_account.to_account_info();
+
+    let kyc_account = if kyc_info.owner == ctx.program_id && !kyc_info.data_is_empty() {
+        Some(SimpleKycAccount::try_deserialize(
+            &mut &kyc_info.try_borrow_data()?[..],
+        )?)
+    } else {
+        None
+    };
+
+    let result = match kyc_account {
+        Some(kyc_account) => {
+            let failure = kyc_account.check_for_transaction(
+                transaction_type,
+                &ctx.accounts.jurisdiction_config,
+                clock.unix_timestamp,
+            );
+            KycCheckResult {
+                wallet: ctx.accounts.wallet.key(),
+                transaction_type,
+                passed: failure.is_none(),
+                failure,
+                verification_level: kyc_account.verification_level,
+                expires_at: kyc_account.expires_at,
+            }
+        }
+        None => KycCheckResult {
+            wallet: ctx.accounts.wallet.key(),
+            transaction_type,
+            passed: false,
+            failure: Some(KycCheckFailure::NotRegistered),
+            verification_level: 0,
+            expires_at: 0,
+        },
+    };
+
+    msg!("KYC check for {}", result.wallet);
+    msg!("Transaction type: {:?}", transaction_type);
+    msg!("Passed: {}", result.passed);
+
+    Ok(result)
+}
+
+/// Record an audit of a user's KYC dossier against their on-chain commitment (KYC officer only)
+///
+/// The auditor opens the commitment off-chain with the dossier and salt (see
+/// `SimpleKycAccount::compute_pii_commitment`) and submits only the
+/// commitment they checked, so the opening never reaches the ledger. The
+/// instruction fails unless that commitment is still the one stored.
+pub fn audit_kyc_commitment(
+    ctx: Context<AuditKycCommitment>,
+    pii_commitment: [u8; 32],
+) -> Result<()> {
+    let kyc_account = &ctx.accounts.kyc_account;
+    let clock = Clock::get()?;
+
+    require!(
+        kyc_account.matches_pii_commitment(&pii_commitment),
+        RwaError::PiiCommitmentMismatch
+    );
+
+    msg!("KYC commitment audited");
+    msg!("User: {}", kyc_account.user);
+    msg!("Auditor: {}", ctx.accounts.auditor.key());
+
+    emit!(KycCommitmentAudited {
+        user: kyc_account.user,
+        auditor: ctx.accounts.auditor.key(),
+        attested_by: kyc_account.attested_by,
+        pii_commitment: kyc_account.pii_commitment,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Update how long KYC verifications stay valid (KYC officer only)
+///
+/// Only affects verifications performed after the update.
+pub fn update_kyc_validity_period(
+    ctx: Context<UpdateKycPolicy>,
+    kyc_validity_period: i64,
+) -> Result<()> {
+    let platform_config = &mut ctx.accounts.platform_config;
+    let clock = Clock::get()?;
+
+    require!(kyc_validity_period >= 0, RwaError::InvalidKycValidityPeriod);
+
+    platform_config.kyc_validity_period = kyc_validity_period;
+    platform_config.updated_at = clock.unix_timestamp;
+
+    msg!("KYC validity period updated");
+    msg!("Validity period: {} seconds", kyc_validity_period);
+
+    emit!(KycPolicyUpdated {
+        authority: ctx.accounts.admin.key(),
+        kyc_validity_period,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Update the per-vault purchase ceilings for KYC levels 1-3 (KYC officer only)
+///
+/// Each entry caps a buyer's cumulative fractions in a single vault; 0 means
+/// unlimited.
+pub fn update_kyc_level_limits(
+    ctx: Context<UpdateKycLevelLimits>,
+    kyc_level_limits: [u64; 3],
+) -> Result<()> {
+    let platform_config = &mut ctx.accounts.platform_config;
+    let clock = Clock::get()?;
+
+    platform_config.kyc_level_limits = kyc_level_limits;
+    platform_config.updated_at = clock.unix_timestamp;
+
+    msg!("KYC level purchase limits updated");
+    msg!(
+        "Limits: L1 {} / L2 {} / L3 {}",
+        kyc_level_limits[0],
+        kyc_level_limits[1],
+        kyc_level_limits[2]
+    );
+
+    emit!(KycLevelLimitsUpdated {
+        authority: ctx.accounts.admin.key(),
+        kyc_level_limits,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Update platform configuration (fee manager only)
+pub fn update_platform_config(
+    ctx: Context<UpdatePlatformConfig>,
+    platform_fee_numerator: u16,
+    platform_fee_denominator: u16,
+    min_investment_amount: u64,
+    max_investment_amount: u64,
+    treasury: Pubkey,
+    default_royalty_bps: u16,
+) -> Result<()> {
+    let platform_config = &mut ctx.accounts.platform_config;
+    let clock = Clock::get()?;
+
+    // Validate inputs
+    require!(platform_fee_denominator > 0, RwaError::InvalidPlatformFee);
+    require!(
+        platform_fee_numerator <= platform_fee_denominator,
+        RwaError::InvalidPlatformFee
+    );
+    require!(min_investment_amount > 0, RwaError::InvalidAmount);
+    require!(
+        max_investment_amount > min_investment_amount,
+        RwaError::InvalidAmount
+    );
+    validate_royalty_fee(default_royalty_bps, Vault::ROYALTY_BPS_DENOMINATOR)?;
+
+    // Update configuration
+    platform_config.platform_fee_numerator = platform_fee_numerator;
+    platform_config.platform_fee_denominator = platform_fee_denominator;
+    platform_config.min_investment_amount = min_investment_amount;
+    platform_config.max_investment_amount = max_investment_amount;
+    platform_config.treasury = treasury;
+    platform_config.default_royalty_bps = default_royalty_bps;
+    platform_config.updated_at = clock.unix_timestamp;
+
+    msg!("Platform configuration updated");
+    msg!(
+        "Fee: {}/{}",
+        platform_fee_numerator,
+        platform_fee_denominator
+    );
+    msg!(
+        "Investment range: {} - {}",
+        min_investment_amount,
+        max_investment_amount
+    );
+    msg!("Treasury: {}", treasury);
+    msg!("Default royalty: {} bps", default_royalty_bps);
+
+    emit!(PlatformConfigUpdated {
+        authority: ctx.accounts.admin.key(),
+        platform_fee_numerator,
+        platform_fee_denominator,
+        min_investment_amount,
+        max_investment_amount,
+        treasury,
+        default_royalty_bps,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Add a payment mint to the platform allowlist (fee manager only)
+pub fn add_payment_mint(ctx: Context<UpdatePlatformConfig>, payment_mint: Pubkey) -> Result<()> {
+    let platform_config = &mut ctx.accounts.platform_config;
+    let clock = Clock::get()?;
+
+    require!(
+        !platform_config.accepts_payment_mint(&payment_mint),
+        RwaError::PaymentMintAlreadyAccepted
+    );
+    require!(
+        platform_config.accepted_payment_mints.len() < PlatformConfig::MAX_ACCEPTED_PAYMENT_MINTS,
+        RwaError::PaymentMintListFull
+    );
+
+    platform_config.accepted_payment_mints.push(payment_mint);
+    platform_config.updated_at = clock.unix_timestamp;
+
+    msg!("Payment mint accepted: {}", payment_mint);
+
+    emit!(PaymentMintUpdated {
+        authority: ctx.accounts.admin.key(),
+        payment_mint,
+        accepted: true,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Remove a payment mint from the platform allowlist (fee manager only)
+///
+/// Existing vaults keep their payment mint; only new fractionalizations are affected.
+pub fn remove_payment_mint(ctx: Context<UpdatePlatformConfig>, payment_mint: Pubkey) -> Result<()> {
+    let platform_config = &mut ctx.accounts.platform_config;
+    let clock = Clock::get()?;
+
+    require!(
+        platform_config.accepts_payment_mint(&payment_mint),
+        RwaError::PaymentMintNotAccepted
+    );
+
+    platform_config
+        .accepted_payment_mints
+        .retain(|mint| mint != &payment_mint);
+    platform_config.updated_at = clock.unix_timestamp;
+
+    msg!("Payment mint removed: {}", payment_mint);
+
+    emit!(PaymentMintUpdated {
+        authority: ctx.accounts.admin.key(),
+        payment_mint,
+        accepted: false,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Approve a verified NFT collection for fractionalization (super admin only)
+pub fn add_approved_collection(ctx: Context<ManageAdmin>, collection_mint: Pubkey) -> Result<()> {
+    let platform_config = &mut ctx.accounts.platform_config;
+    let clock = Clock::get()?;
+
+    require!(
+        !platform_config.approves_collection(&collection_mint),
+        RwaError::CollectionAlreadyApproved
+    );
+    require!(
+        platform_config.approved_collections.len() < PlatformConfig::MAX_APPROVED_COLLECTIONS,
+        RwaError::CollectionListFull
+    );
+
+    platform_config.approved_collections.push(collection_mint);
+    platform_config.updated_at = clock.unix_timestamp;
+
+    msg!("Collection approved: {}", collection_mint);
+
+    emit!(CollectionApprovalUpdated {
+        authority: ctx.accounts.admin.key(),
+        collection_mint,
+        approved: true,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Remove an NFT collection from the approved list (super admin only)
+///
+/// Existing vaults are unaffected; only new fractionalizations are refused.
+pub fn remove_approved_collection(
+    ctx: Context<ManageAdmin>,
+    collection_mint: Pubkey,
+) -> Result<()> {
+    let platform_config = &mut ctx.accounts.platform_config;
+    let clock = Clock::get()?;
+
+    require!(
+        platform_config.approves_collection(&collection_mint),
+        RwaError::NftCollectionNotApproved
+    );
+
+    platform_config
+        .approved_collections
+        .retain(|collection| collection != &collection_mint);
+    platform_config.updated_at = clock.unix_timestamp;
+
+    msg!("Collection removed: {}", collection_mint);
+
+    emit!(CollectionApprovalUpdated {
+        authority: ctx.accounts.admin.key(),
+        collection_mint,
+        approved: false,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Create the jurisdiction list consulted by `buy_fractions` (super admin only)
+pub fn initialize_jurisdiction_config(
+    ctx: Context<InitializeJurisdictionConfig>,
+    mode: JurisdictionMode,
+) -> Result<()> {
+    let jurisdiction_config = &mut ctx.accounts.jurisdiction_config;
+    let clock = Clock::get()?;
+
+    jurisdiction_config.mode = mode;
+    jurisdiction_config.countries = Vec::new();
+    jurisdiction_config.updated_at = clock.unix_timestamp;
+
+    msg!("Jurisdiction list initialized");
+    msg!("Mode: {:?}", mode);
+
+    emit!(JurisdictionModeChanged {
+        authority: ctx.accounts.admin.key(),
+        mode,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Switch the jurisdiction list between blocklist and allowlist (KYC officer only)
+///
+/// The listed countries are kept, so switching modes inverts their meaning.
+pub fn set_jurisdiction_mode(
+    ctx: Context<ManageJurisdictions>,
+    mode: JurisdictionMode,
+) -> Result<()> {
+    let jurisdiction_config = &mut ctx.accounts.jurisdiction_config;
+    let clock = Clock::get()?;
+
+    jurisdiction_config.mode = mode;
+    jurisdiction_config.updated_at = clock.unix_timestamp;
+
+    msg!("Jurisdiction mode set to {:?}", mode);
+
+    emit!(JurisdictionModeChanged {
+        authority: ctx.accounts.admin.key(),
+        mode,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Add a country to the jurisdiction list (KYC officer only)
+pub fn add_jurisdiction(ctx: Context<ManageJurisdictions>, country: String) -> Result<()> {
+    let jurisdiction_config = &mut ctx.accounts.jurisdiction_config;
+    let clock = Clock::get()?;
+
+    let code = JurisdictionConfig::parse_country_code(&country)?;
+    require!(
+        !jurisdiction_config.is_listed(&code),
+        RwaError::JurisdictionAlreadyListed
+    );
+    require!(
+        jurisdiction_config.countries.len() < JurisdictionConfig::MAX_COUNTRIES,
+        RwaError::JurisdictionListFull
+    );
+
+    jurisdiction_config.countries.push(code);
+    jurisdiction_config.updated_at = clock.unix_timestamp;
+
+    msg!("Jurisdiction listed: {}", country);
+
+    emit!(JurisdictionListUpdated {
+        authority: ctx.accounts.admin.key(),
+        country,
+        listed: true,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Remove a country from the jurisdiction list (KYC officer only)
+pub fn remove_jurisdiction(ctx: Context<ManageJurisdictions>, country: String) -> Result<()> {
+    let jurisdiction_config = &mut ctx.accounts.jurisdiction_config;
+    let clock = Clock::get()?;
+
+    let code = JurisdictionConfig::parse_country_code(&country)?;
+    require!(
+        jurisdiction_config.is_listed(&code),
+        RwaError::JurisdictionNotListed
+    );
+
+    jurisdiction_config
+        .countries
+        .retain(|listed| listed != &code);
+    jurisdiction_config.updated_at = clock.unix_timestamp;
+
+    msg!("Jurisdiction unlisted: {}", country);
+
+    emit!(JurisdictionListUpdated {
+        authority: ctx.accounts.admin.key(),
+        country,
+        listed: false,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Pause the whole platform (pauser only)
+///
+/// While paused, fractionalization, purchases, redemptions, royalty harvests
+/// and KYC registration are all rejected.
+pub fn pause_platform(ctx: Context<SetPlatformStatus>) -> Result<()> {
+    let platform_config = &mut ctx.accounts.platform_config;
+    let clock = Clock::get()?;
+
+    platform_config.is_active = false;
+    platform_config.updated_at = clock.unix_timestamp;
+
+    msg!("Platform paused");
+    msg!("Admin: {}", ctx.accounts.admin.key());
+
+    emit!(PlatformStatusChanged {
+        authority: ctx.accounts.admin.key(),
+        is_active: false,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Unpause the platform (pauser only)
+pub fn unpause_platform(ctx: Context<SetPlatformStatus>) -> Result<()> {
+    let platform_config = &mut ctx.accounts.platform_config;
+    let clock = Clock::get()?;
+
+    platform_config.is_active = true;
+    platform_config.updated_at = clock.unix_timestamp;
+
+    msg!("Platform unpaused");
+    msg!("Admin: {}", ctx.accounts.admin.key());
+
+    emit!(PlatformStatusChanged {
+        authority: ctx.accounts.admin.key(),
+        is_active: true,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Halt or resume a single vault (pauser only)
+///
+/// A halted vault rejects purchases, redemptions and royalty harvests until resumed.
+pub fn set_vault_halted(ctx: Context<SetVaultHalted>, is_halted: bool) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let clock = Clock::get()?;
+
+    vault.is_halted = is_halted;
+
+    msg!("Vault halt status updated");
+    msg!("Vault: {}", vault.key());
+    msg!("Halted: {}", is_halted);
+    msg!("Admin: {}", ctx.accounts.admin.key());
+
+    emit!(VaultHaltStatusChanged {
+        authority: ctx.accounts.admin.key(),
+        vault: vault.key(),
+        is_halted,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Freeze a holder's fractional token account (pauser only)
+///
+/// Uses the vault PDA as freeze authority of the fractional mint, e.g. after a
+/// sanctions hit on the holder.
+pub fn freeze_holder_account(ctx: Context<FreezeHolderAccount>) -> Result<()> {
+    let vault = &ctx.accounts.vault;
+    let clock = Clock::get()?;
+    let bump = ctx.bumps.vault;
+    let seeds = &[b"vault", vault.original_nft_mint.as_ref(), &[bump]];
+
+    token_interface::freeze_account(CpiContext::new_with_signer(
+        ctx.accounts.fractional_token_program.to_account_info(),
+        token_interface::FreezeAccount {
+            account: ctx.accounts.holder_fractional_account.to_account_info(),
+            mint: ctx.accounts.fractional_token_mint.to_account_info(),
+            authority: vault.to_account_info(),
+        },
+        &[seeds],
+    ))?;
+
+    msg!("Fractional token account frozen");
+    msg!("Account: {}", ctx.accounts.holder_fractional_account.key());
+    msg!("Holder: {}", ctx.accounts.holder_fractional_account.owner);
+    msg!("Admin: {}", ctx.accounts.admin.key());
+
+    emit!(HolderAccountFreezeChanged {
+        authority: ctx.accounts.admin.key(),
+        vault: vault.key(),
+        token_account: ctx.accounts.holder_fractional_account.key(),
+        holder: ctx.accounts.holder_fractional_account.owner,
+        is_frozen: true,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Thaw a previously frozen holder's fractional token account (pauser only)
+pub fn thaw_holder_account(ctx: Context<FreezeHolderAccount>) -> Result<()> {
+    let vault = &ctx.accounts.vault;
+    let clock = Clock::get()?;
+    let bump = ctx.bumps.vault;
+    let seeds = &[b"vault", vault.original_nft_mint.as_ref(), &[bump]];
+
+    token_interface::thaw_account(CpiContext::new_with_signer(
+        ctx.accounts.fractional_token_program.to_account_info(),
+        token_interface::ThawAccount {
+            account: ctx.accounts.holder_fractional_account.to_account_info(),
+            mint: ctx.accounts.fractional_token_mint.to_account_info(),
+            authority: vault.to_account_info(),
+        },
+        &[seeds],
+    ))?;
+
+    msg!("Fractional token account thawed");
+    msg!("Account: {}", ctx.accounts.holder_fractional_account.key());
+    msg!("Holder: {}", ctx.accounts.holder_fractional_account.owner);
+    msg!("Admin: {}", ctx.accounts.admin.key());
+
+    emit!(HolderAccountFreezeChanged {
+        authority: ctx.accounts.admin.key(),
+        vault: vault.key(),
+        token_account: ctx.accounts.holder_fractional_account.key(),
+        holder: ctx.accounts.holder_fractional_account.owner,
+        is_frozen: false,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Propose a new super admin (super admin only)
+///
+/// The transfer only takes effect once the proposed admin calls `accept_admin`,
+/// so a typo in the new key cannot brick the platform.
+pub fn propose_admin(ctx: Context<ManageAdmin>, new_admin: Pubkey) -> Result<()> {
+    let platform_config = &mut ctx.accounts.platform_config;
+    let clock = Clock::get()?;
+
+    platform_config.pending_admin = Some(new_admin);
+    platform_config.updated_at = clock.unix_timestamp;
+
+    msg!("Admin transfer proposed");
+    msg!("Current admin: {}", platform_config.admin);
+    msg!("Proposed admin: {}", new_admin);
+
+    emit!(AdminTransferProposed {
+        current_admin: platform_config.admin,
+        proposed_admin: new_admin,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Accept a proposed super admin transfer (proposed admin only)
+pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+    let platform_config = &mut ctx.accounts.platform_config;
+    let clock = Clock::get()?;
+
+    let previous_admin = platform_config.admin;
+    platform_config.admin = ctx.accounts.new_admin.key();
+    platform_config.pending_admin = None;
+    platform_config.updated_at = clock.unix_timestamp;
+
+    msg!("Admin transfer accepted");
+    msg!("Previous admin: {}", previous_admin);
+    msg!("New admin: {}", platform_config.admin);
+
+    emit!(AdminTransferred {
+        previous_admin,
+        new_admin: platform_config.admin,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Grant a delegated role to an authority (super admin only)
+pub fn grant_role(ctx: Context<ManageAdmin>, authority: Pubkey, role: AdminRole) -> Result<()> {
+    let platform_config = &mut ctx.accounts.platform_config;
+    let clock = Clock::get()?;
+
+    require!(role != AdminRole::SuperAdmin, RwaError::InvalidRole);
+    require!(
+        !platform_config
+            .role_assignments
+            .iter()
+            .any(|assignment| assignment.authority == authority && assignment.role == role),
+        RwaError::RoleAlreadyGranted
+    );
+    require!(
+        platform_config.role_assignments.len() < PlatformConfig::MAX_ROLE_ASSIGNMENTS,
+        RwaError::RoleListFull
+    );
+
+    platform_config
+        .role_assignments
+        .push(RoleAssignment { authority, role });
+    platform_config.updated_at = clock.unix_timestamp;
+
+    msg!("Role granted");
+    msg!("Authority: {}", authority);
+    msg!("Role: {:?}", role);
+
+    emit!(RoleUpdated {
+        admin: ctx.accounts.admin.key(),
+        authority,
+        role,
+        granted: true,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Revoke a delegated role from an authority (super admin only)
+pub fn revoke_role(ctx: Context<ManageAdmin>, authority: Pubkey, role: AdminRole) -> Result<()> {
+    let platform_config = &mut ctx.accounts.platform_config;
+    let clock = Clock::get()?;
+
+    let assignments_before = platform_config.role_assignments.len();
+    platform_config
+        .role_assignments
+        .retain(|assignment| !(assignment.authority == authority && assignment.role == role));
+    require!(
+        platform_config.role_assignments.len() < assignments_before,
+        RwaError::RoleNotGranted
+    );
+    platform_config.updated_at = clock.unix_timestamp;
+
+    msg!("Role revoked");
+    msg!("Authority: {}", authority);
+    msg!("Role: {:?}", role);
+
+    emit!(RoleUpdated {
+        admin: ctx.accounts.admin.key(),
+        authority,
+        role,
+        granted: false,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Harvest withheld transfer-fee royalties and pay them out (permissionless)
+///
+/// Withdraws the fees withheld in the holder accounts passed as remaining
+/// accounts, plus any already harvested to the mint, into the vault. The
+/// treasury takes the platform fee ratio and the creator the rest.
+pub fn harvest_royalties<'info>(
+    ctx: Context<'_, '_, '_, 'info, HarvestRoyalties<'info>>,
+) -> Result<()> {
+    let vault = &ctx.accounts.vault;
+    let clock = Clock::get()?;
+    let bump = ctx.bumps.vault;
+    let seeds = &[b"vault", vault.original_nft_mint.as_ref(), &[bump]];
+
+    let amount_harvested = withdraw_withheld_fractions(
+        ctx.accounts.fractional_token_program.to_account_info(),
+        ctx.accounts.fractional_token_mint.to_account_info(),
+        &mut ctx.accounts.vault_fractional_account,
+        vault.to_account_info(),
+        seeds,
+        ctx.remaining_accounts.to_vec(),
+    )?;
+    require!(amount_harvested > 0, RwaError::NoRoyaltiesToHarvest);
+
+    // Treasury takes the same share of royalties as of primary sales
+    let treasury_amount = ctx
+        .accounts
+        .platform_config
+        .calculate_purchase_fee(amount_harvested)?;
+    let creator_amount = amount_harvested
+        .checked_sub(treasury_amount)
+        .ok_or(RwaError::MathOverflow)?;
+
+    // Re-issue rather than transfer, so payouts are not charged the fee again
+    reissue_fractions(
+        ctx.accounts.fractional_token_program.to_account_info(),
+        ctx.accounts.fractional_token_mint.to_account_info(),
+        ctx.accounts.vault_fractional_account.to_account_info(),
+        ctx.accounts.creator_fractional_account.to_account_info(),
+        vault.to_account_info(),
+        seeds,
+        creator_amount,
+    )?;
+
+    if treasury_amount > 0 {
+        reissue_fractions(
+            ctx.accounts.fractional_token_program.to_account_info(),
+            ctx.accounts.fractional_token_mint.to_account_info(),
+            ctx.accounts.vault_fractional_account.to_account_info(),
+            ctx.accounts.treasury_fractional_account.to_account_info(),
+            vault.to_account_info(),
+            seeds,
+            treasury_amount,
+        )?;
+    }
+
+    msg!("Royalties harvested");
+    msg!("Vault: {}", vault.key());
+    msg!("Harvested: {}", amount_harvested);
+    msg!("Creator amount: {}", creator_amount);
+    msg!("Treasury amount: {}", treasury_amount);
+
+    emit!(RoyaltiesHarvested {
+        vault: vault.key(),
+        creator: vault.creator,
+        treasury: ctx.accounts.treasury.key(),
+        amount_harvested,
+        creator_amount,
+        treasury_amount,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Withdraw the transfer fees withheld in `sources` and on the mint into the vault
+///
+/// Returns the number of fractions withdrawn.
+fn withdraw_withheld_fractions<'info>(
+    token_program: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
+    vault_fractional_account: &mut InterfaceAccount<'info, token_interface::TokenAccount>,
+    vault: AccountInfo<'info>,
+    seeds: &[&[u8]],
+    sources: Vec<AccountInfo<'info>>,
+) -> Result<u64> {
+    let balance_before = vault_fractional_account.amount;
+
+    if !sources.is_empty() {
+        token_interface::withdraw_withheld_tokens_from_accounts(
+            CpiContext::new_with_signer(
+                token_program.clone(),
+                token_interface::WithdrawWithheldTokensFromAccounts {
+                    token_program_id: token_program.clone(),
+                    mint: mint.clone(),
+                    destination: vault_fractional_account.to_account_info(),
+                    authority: vault.clone(),
+                },
+                &[seeds],
+            ),
+            sources,
+        )?;
+    }
+
+    token_interface::withdraw_withheld_tokens_from_mint(CpiContext::new_with_signer(
+        token_program.clone(),
+        token_interface::WithdrawWithheldTokensFromMint {
+            token_program_id: token_program,
+            mint,
+            destination: vault_fractional_account.to_account_info(),
+            authority: vault,
+        },
+        &[seeds],
+    ))?;
+
+    vault_fractional_account.reload()?;
+    vault_fractional_account
+        .amount
+        .checked_sub(balance_before)
+        .ok_or(RwaError::MathOverflow.into())
+}
+
+/// Read the Ed25519 program instruction right before the current one
+///
+/// Returns the (signer, signature, message) it verified.
+fn preceding_ed25519_signature(
+    instructions_sysvar: &AccountInfo,
+) -> Result<(Pubkey, [u8; 64], Vec<u8>)> {
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+    require!(current_index > 0, RwaError::InvalidAttestationSignature);
+    let signature_ix =
+        load_instruction_at_checked(current_index as usize - 1, instructions_sysvar)?;
+
+    parse_ed25519_instruction(&signature_ix)
+}
+
+/// Move fractions out of the vault by burning and minting them
+///
+/// Keeps the supply unchanged while bypassing the transfer fee (and transfer
+/// hook), for transfers the program has already authorized. The recipient's
+/// KYC is not checked here: buyers are checked by `buy_fractions`, and the
+/// creator and treasury royalty recipients by `harvest_royalties`.
+fn reissue_fractions<'info>(
+    token_program: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
+    vault_fractional_account: AccountInfo<'info>,
+    destination: AccountInfo<'info>,
+    vault: AccountInfo<'info>,
+    seeds: &[&[u8]],
+    amount: u64,
+) -> Result<()> {
+    token_interface::burn(
+        CpiContext::new_with_signer(
+            token_program.clone(),
+            token_interface::Burn {
+                mint: mint.clone(),
+                from: vault_fractional_account,
+                authority: vault.clone(),
+            },
+            &[seeds],
+        ),
+        amount,
+    )?;
+
+    token_interface::mint_to(
+        CpiContext::new_with_signer(
+            token_program,
+            token_interface::MintTo {
+                mint,
+                to: destination,
+                authority: vault,
+            },
+            &[seeds],
+        ),
+        amount,
+    )
+}
+
+/// Accounts for moving the original NFT between token accounts
+///
+/// The token records, metadata, edition, token metadata program, instructions
+/// sysvar and rule set accounts are only needed for programmable NFTs.
+struct OriginalNftTransfer<'info> {
+    is_programmable: bool,
+    mint: AccountInfo<'info>,
+    source: AccountInfo<'info>,
+    source_owner: AccountInfo<'info>,
+    source_token_record: Option<AccountInfo<'info>>,
+    destination: AccountInfo<'info>,
+    destination_owner: AccountInfo<'info>,
+    destination_token_record: Option<AccountInfo<'info>>,
+    payer: AccountInfo<'info>,
+    metadata: Option<AccountInfo<'info>>,
+    edition: Option<AccountInfo<'info>>,
+    token_metadata_program: Option<AccountInfo<'info>>,
+    sysvar_instructions: Option<AccountInfo<'info>>,
+    authorization_rules: Option<AccountInfo<'info>>,
+    authorization_rules_program: Option<AccountInfo<'info>>,
+    token_program: AccountInfo<'info>,
+    associated_token_program: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+}
+
+/// Move the original NFT, signed by its current owner
+///
+/// pNFT token accounts stay frozen, so pNFTs go through the token metadata
+/// `Transfer`, which thaws, transfers and re-freezes the NFT, keeping both
+/// token records in sync and enforcing the NFT's rule set. Other NFTs move
+/// with a plain token transfer.
+fn transfer_original_nft(
+    accounts: OriginalNftTransfer<'_>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    if !accounts.is_programmable {
+        return transfer(
+            CpiContext::new_with_signer(
+                accounts.token_program,
+                Transfer {
+                    from: accounts.source,
+                    to: accounts.destination,
+                    authority: accounts.source_owner,
+                },
+                signer_seeds,
+            ),
+            1, // Transfer 1 NFT
+        );
+    }
+
+    let token_metadata_program = programmable_nft_account(accounts.token_metadata_program)?;
+    TransferV1CpiBuilder::new(&token_metadata_program)
+        .token(&accounts.source)
+        .token_owner(&accounts.source_owner)
+        .token_record(Some(&programmable_nft_account(
+            accounts.source_token_record,
+        )?))
+        .destination_token(&accounts.destination)
+        .destination_owner(&accounts.destination_owner)
+        .destination_token_record(Some(&programmable_nft_account(
+            accounts.destination_token_record,
+        )?))
+        .mint(&accounts.mint)
+        .metadata(&programmable_nft_account(accounts.metadata)?)
+        .edition(Some(&programmable_nft_account(accounts.edition)?))
+        .authority(&accounts.source_owner)
+        .payer(&accounts.payer)
+        .system_program(&accounts.system_program)
+        .sysvar_instructions(&programmable_nft_account(accounts.sysvar_instructions)?)
+        .spl_token_program(&accounts.token_program)
+        .spl_ata_program(&accounts.associated_token_program)
+        .authorization_rules_program(accounts.authorization_rules_program.as_ref())
+        .authorization_rules(accounts.authorization_rules.as_ref())
+        .amount(1)
+        .invoke_signed(signer_seeds)?;
+
+    Ok(())
+}
+
+/// Account info of an optional instruction account, if passed
+fn optional_account<'info, T: ToAccountInfo<'info>>(
+    account: &Option<T>,
+) -> Option<AccountInfo<'info>> {
+    account.as_ref().map(ToAccountInfo::to_account_info)
+}
+
+/// Unwrap an optional account that programmable NFT transfers require
+fn programmable_nft_account(account: Option<AccountInfo<'_>>) -> Result<AccountInfo<'_>> {
+    account.ok_or_else(|| RwaError::MissingProgrammableNftAccounts.into())
+}
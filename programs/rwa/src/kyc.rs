@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
 
 /// Simplified KYC account for our own implementation
 ///
@@ -10,7 +11,13 @@ pub struct SimpleKycAccount {
     pub user: Pubkey,
 
     /// KYC verification status
-    pub is_verified: bool,
+    pub status: KycStatus,
+
+    /// Reason code for the last rejection, suspension or revocation
+    pub status_reason: Option<KycReasonCode>,
+
+    /// Timestamp of the last status change
+    pub status_updated_at: i64,
 
     /// Verification method used
     pub verification_method: VerificationMethod,
@@ -18,14 +25,30 @@ pub struct SimpleKycAccount {
     /// Timestamp when KYC was completed
     pub verified_at: i64,
 
-    /// Optional: User's email for notifications
-    pub email: Option<String>,
+    /// Commitment to the off-chain KYC dossier (see `compute_pii_commitment`)
+    ///
+    /// Personal data never touches the ledger; all zeros until verified.
+    pub pii_commitment: [u8; 32],
+
+    /// Verifier's Ed25519 signature, over `user || pii_commitment` for officer
+    /// verifications or over the attestation message for provider attestations
+    pub attestation_signature: [u8; 64],
 
-    /// Optional: User's country for compliance
-    pub country: Option<String>,
+    /// The verifier that attested the commitment
+    pub attested_by: Pubkey,
+
+    /// User's ISO 3166-1 alpha-2 country code, kept on chain only because
+    /// purchases are checked against the jurisdiction list
+    pub country: Option<[u8; 2]>,
 
     /// Verification level (1-3)
     pub verification_level: u8,
+
+    /// Timestamp when the verification expires (0 = never)
+    pub expires_at: i64,
+
+    /// Highest provider attestation nonce accepted, to prevent replays
+    pub last_attestation_nonce: u64,
 }
 
 /// Simple verification methods for our implementation
@@ -41,30 +64,82 @@ pub enum VerificationMethod {
     DocumentUpload,
     /// Phone verification
     PhoneVerification,
+    /// Signed attestation from a trusted KYC provider
+    ProviderAttestation,
 }
 
 impl SimpleKycAccount {
     /// Calculate the space required for SimpleKycAccount
     pub const LEN: usize = 8 + // discriminator
         32 + // user
-        1 +  // is_verified
+        1 +  // status
+        2 +  // status_reason (Option<KycReasonCode>)
+        8 +  // status_updated_at
         1 +  // verification_method
         8 +  // verified_at
-        4 +  // email length
-        64 + // email string (max 64 chars)
-        4 +  // country length
-        32 + // country string (max 32 chars)
+        32 + // pii_commitment
+        64 + // attestation_signature
+        32 + // attested_by
+        1 + 2 + // country (Option<[u8; 2]>)
         1 +  // verification_level
-        8; // padding
+        8 +  // expires_at
+        8; // last_attestation_nonce
 
     /// Check if KYC is valid for transactions
     pub fn is_valid(&self) -> bool {
-        self.is_verified && self.verification_level >= 1
+        self.status == KycStatus::Verified && self.verification_level >= 1
+    }
+
+    /// Check if the verification has expired at the given timestamp
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at > 0 && now >= self.expires_at
+    }
+
+    /// Compute the commitment for a dossier: `sha256(sha256(dossier) || salt)`
+    pub fn compute_pii_commitment(dossier_hash: &[u8; 32], salt: &[u8; 32]) -> [u8; 32] {
+        hashv(&[dossier_hash, salt]).to_bytes()
+    }
+
+    /// Check if a commitment opened off-chain is the one stored
+    pub fn matches_pii_commitment(&self, pii_commitment: &[u8; 32]) -> bool {
+        self.pii_commitment != [0; 32] && *pii_commitment == self.pii_commitment
+    }
+
+    /// Evaluate this account for a transaction type at the given timestamp
+    ///
+    /// Returns the first failing requirement, or `None` if the check passes.
+    pub fn check_for_transaction(
+        &self,
+        transaction_type: TransactionType,
+        jurisdiction_config: &JurisdictionConfig,
+        now: i64,
+    ) -> Option<KycCheckFailure> {
+        match self.status {
+            KycStatus::Suspended => return Some(KycCheckFailure::Suspended),
+            KycStatus::Revoked => return Some(KycCheckFailure::Revoked),
+            _ if !self.is_valid() => return Some(KycCheckFailure::NotVerified),
+            _ => {}
+        }
+
+        if self.is_expired(now) {
+            return Some(KycCheckFailure::Expired);
+        }
+
+        // Only acquiring fractions is restricted by jurisdiction
+        let acquires_fractions = matches!(
+            transaction_type,
+            TransactionType::Purchase | TransactionType::Transfer
+        );
+        if acquires_fractions && !jurisdiction_config.is_allowed(self.country.as_ref()) {
+            return Some(KycCheckFailure::JurisdictionRestricted);
+        }
+
+        None
     }
 
     /// Check if user can perform high-value transactions
     pub fn can_perform_high_value_transactions(&self) -> bool {
-        self.is_verified && self.verification_level >= 2
+        self.status == KycStatus::Verified && self.verification_level >= 2
     }
 
     /// Get verification method description
@@ -75,6 +150,425 @@ impl SimpleKycAccount {
             VerificationMethod::SocialVerification => "Social Media Verified".to_string(),
             VerificationMethod::DocumentUpload => "Document Verified".to_string(),
             VerificationMethod::PhoneVerification => "Phone Verified".to_string(),
+            VerificationMethod::ProviderAttestation => "Provider Attested".to_string(),
+        }
+    }
+}
+
+/// Enhanced KYC account for professional compliance
+///
+/// Tracks the external provider session, verification attempts, risk scoring,
+/// compliance screening results and expiry for a user.
+#[account]
+pub struct EnhancedKycAccount {
+    /// The user's wallet address
+    pub user: Pubkey,
+
+    /// Current verification status
+    pub status: KycStatus,
+
+    /// Provider handling the verification
+    pub provider: KycProvider,
+
+    /// Timestamp when KYC verification was initiated
+    pub registered_at: i64,
+
+    /// Number of verification attempts made
+    pub verification_attempts: u8,
+
+    /// Timestamp of the most recent verification attempt
+    pub last_attempt_at: Option<i64>,
+
+    /// Compliance screening results
+    pub compliance_flags: ComplianceFlags,
+
+    /// Provider-side verification session ID
+    pub provider_verification_id: Option<String>,
+
+    /// Salted hash of the contact details given to the provider
+    ///
+    /// The email and phone number stay with the provider, off the ledger.
+    pub contact_commitment: [u8; 32],
+
+    /// Risk score reported by the provider (0-100)
+    pub risk_score: Option<u8>,
+
+    /// Provider metadata or admin notes
+    pub provider_metadata: Option<String>,
+
+    /// Timestamp when KYC was verified
+    pub verified_at: Option<i64>,
+
+    /// Timestamp when the verification expires
+    pub expires_at: Option<i64>,
+}
+
+impl EnhancedKycAccount {
+    /// Maximum length of the provider verification ID
+    pub const MAX_VERIFICATION_ID_LEN: usize = 64;
+
+    /// Maximum length of provider metadata or admin notes
+    pub const MAX_METADATA_LEN: usize = 256;
+
+    /// Maximum number of verification attempts per user
+    pub const MAX_VERIFICATION_ATTEMPTS: u8 = 3;
+
+    /// Calculate the space required for EnhancedKycAccount
+    pub const LEN: usize = 8 + // discriminator
+        32 + // user
+        1 +  // status
+        1 +  // provider
+        8 +  // registered_at
+        1 +  // verification_attempts
+        9 +  // last_attempt_at (Option<i64>)
+        ComplianceFlags::LEN + // compliance_flags
+        1 + 4 + Self::MAX_VERIFICATION_ID_LEN + // provider_verification_id (Option<String>)
+        32 + // contact_commitment
+        2 +  // risk_score (Option<u8>)
+        1 + 4 + Self::MAX_METADATA_LEN + // provider_metadata (Option<String>)
+        9 +  // verified_at (Option<i64>)
+        9; // expires_at (Option<i64>)
+
+    /// Check if the user may start another verification attempt
+    pub fn can_attempt_verification(&self) -> bool {
+        self.verification_attempts < Self::MAX_VERIFICATION_ATTEMPTS
+    }
+
+    /// Check if KYC is valid for transactions
+    pub fn is_valid(&self) -> bool {
+        self.status == KycStatus::Verified && self.verified_at.is_some()
+    }
+
+    /// Check if the verification has expired at the given timestamp
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at.is_some_and(|expires_at| now >= expires_at)
+    }
+
+    /// Check if all mandatory compliance screenings have passed
+    pub fn is_fully_compliant(&self) -> bool {
+        self.compliance_flags.identity_verified
+            && self.compliance_flags.aml_cleared
+            && self.compliance_flags.sanctions_cleared
+    }
+
+    /// Get the risk level derived from the provider's risk score
+    pub fn get_risk_level(&self) -> RiskLevel {
+        match self.risk_score {
+            Some(score) if score <= 30 => RiskLevel::Low,
+            Some(score) if score <= 70 => RiskLevel::Medium,
+            Some(_) => RiskLevel::High,
+            None => RiskLevel::Medium,
+        }
+    }
+}
+
+/// External KYC providers supported by the platform
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum KycProvider {
+    /// Manual review by a platform admin
+    Manual,
+    /// Jumio identity verification
+    Jumio,
+    /// Onfido identity verification
+    Onfido,
+    /// Sumsub identity verification
+    Sumsub,
+    /// Persona identity verification
+    Persona,
+}
+
+impl std::fmt::Display for KycProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            KycProvider::Manual => "manual",
+            KycProvider::Jumio => "jumio",
+            KycProvider::Onfido => "onfido",
+            KycProvider::Sumsub => "sumsub",
+            KycProvider::Persona => "persona",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Status of an enhanced KYC verification
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum KycStatus {
+    /// Registered, waiting for the verification to start
+    Pending,
+    /// Verification session in progress with the provider
+    InProgress,
+    /// Verification passed
+    Verified,
+    /// Verification rejected by the provider or an admin
+    Rejected,
+    /// Verification could not be completed
+    Failed,
+    /// Verification expired and must be refreshed
+    Expired,
+    /// Verification suspended by an admin
+    Suspended,
+    /// Verification permanently revoked by an admin
+    Revoked,
+}
+
+/// Reason codes recorded when a KYC officer rejects, suspends or revokes a user
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum KycReasonCode {
+    /// Anti-money-laundering screening hit
+    AmlHit,
+    /// Sanctions list match
+    SanctionsHit,
+    /// Suspected fraud or identity theft
+    FraudSuspected,
+    /// Submitted documents were invalid or expired
+    InvalidDocuments,
+    /// Requested by the user
+    UserRequest,
+    /// Any other reason, documented off-chain
+    Other,
+}
+
+/// Compliance screening results reported for a user
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, PartialEq, Debug)]
+pub struct ComplianceFlags {
+    /// Identity document verified
+    pub identity_verified: bool,
+    /// Proof of address verified
+    pub address_verified: bool,
+    /// Anti-money-laundering screening cleared
+    pub aml_cleared: bool,
+    /// Sanctions list screening cleared
+    pub sanctions_cleared: bool,
+    /// Politically exposed person screening cleared
+    pub pep_cleared: bool,
+}
+
+impl ComplianceFlags {
+    /// Space required for ComplianceFlags
+    pub const LEN: usize = 5;
+}
+
+/// Risk level derived from a provider risk score
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RiskLevel {
+    /// Risk score up to 30
+    Low,
+    /// Risk score up to 70, or no score reported
+    Medium,
+    /// Risk score above 70
+    High,
+}
+
+/// Verification requirements applied to enhanced KYC
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Debug)]
+pub struct KycRequirements {
+    /// Days a verification stays valid (None = never expires)
+    pub expiration_days: Option<u16>,
+    /// Whether proof of address is required
+    pub require_address_verification: bool,
+    /// Whether PEP screening is required
+    pub require_pep_screening: bool,
+}
+
+impl KycRequirements {
+    /// Space required for KycRequirements
+    pub const LEN: usize = 3 + // expiration_days (Option<u16>)
+        1 + // require_address_verification
+        1; // require_pep_screening
+
+    /// Check if the optional screenings required here have passed
+    pub fn is_satisfied_by(&self, flags: &ComplianceFlags) -> bool {
+        (!self.require_address_verification || flags.address_verified)
+            && (!self.require_pep_screening || flags.pep_cleared)
+    }
+
+    /// Calculate the expiry timestamp for a verification completed at `now`
+    pub fn expiry_from(&self, now: i64) -> Option<i64> {
+        self.expiration_days
+            .map(|days| now + (days as i64 * 24 * 60 * 60))
+    }
+}
+
+/// Platform-wide configuration of accepted KYC providers
+#[account]
+pub struct KycProviderConfig {
+    /// Providers users may verify with
+    pub active_providers: Vec<KycProvider>,
+
+    /// Provider used when a user does not choose one
+    pub default_provider: KycProvider,
+
+    /// Verification requirements
+    pub requirements: KycRequirements,
+
+    /// Last update timestamp
+    pub updated_at: i64,
+}
+
+impl KycProviderConfig {
+    /// Maximum number of active providers
+    pub const MAX_ACTIVE_PROVIDERS: usize = 5;
+
+    /// Calculate the space required for KycProviderConfig
+    pub const LEN: usize = 8 + // discriminator
+        4 + Self::MAX_ACTIVE_PROVIDERS + // active_providers
+        1 +  // default_provider
+        KycRequirements::LEN + // requirements
+        8; // updated_at
+}
+
+/// Whether the jurisdiction list restricts or permits the listed countries
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum JurisdictionMode {
+    /// Residents of listed countries cannot buy
+    Blocklist,
+    /// Only residents of listed countries can buy
+    Allowlist,
+}
+
+/// Platform-managed list of restricted or permitted jurisdictions
+#[account]
+pub struct JurisdictionConfig {
+    /// How the country list is applied
+    pub mode: JurisdictionMode,
+
+    /// ISO 3166-1 alpha-2 country codes
+    pub countries: Vec<[u8; 2]>,
+
+    /// Last update timestamp
+    pub updated_at: i64,
+}
+
+impl JurisdictionConfig {
+    /// Maximum number of listed countries
+    pub const MAX_COUNTRIES: usize = 64;
+
+    /// Calculate the space required for JurisdictionConfig
+    pub const LEN: usize = 8 + // discriminator
+        1 +  // mode
+        4 + 2 * Self::MAX_COUNTRIES + // countries
+        8; // updated_at
+
+    /// Parse an ISO 3166-1 alpha-2 country code (two uppercase ASCII letters)
+    pub fn parse_country_code(country: &str) -> Result<[u8; 2]> {
+        let bytes = country.as_bytes();
+        require!(
+            bytes.len() == 2 && bytes.iter().all(u8::is_ascii_uppercase),
+            crate::error::RwaError::InvalidCountryCode
+        );
+
+        Ok([bytes[0], bytes[1]])
+    }
+
+    /// Check if a country is on the list
+    pub fn is_listed(&self, country: &[u8; 2]) -> bool {
+        self.countries.contains(country)
+    }
+
+    /// Check if a user from the given country may buy
+    ///
+    /// Users without a country on record are only rejected in allowlist mode.
+    pub fn is_allowed(&self, country: Option<&[u8; 2]>) -> bool {
+        let listed = country.is_some_and(|code| self.is_listed(code));
+
+        match self.mode {
+            JurisdictionMode::Blocklist => !listed,
+            JurisdictionMode::Allowlist => listed,
         }
     }
 }
+
+/// Registry of KYC providers whose signed attestations verify users
+#[account]
+pub struct KycAttestorRegistry {
+    /// Ed25519 public keys of the trusted providers
+    pub attestors: Vec<Pubkey>,
+
+    /// Last update timestamp
+    pub updated_at: i64,
+}
+
+impl KycAttestorRegistry {
+    /// Maximum number of trusted providers
+    pub const MAX_ATTESTORS: usize = 10;
+
+    /// Domain separator prefixed to every attestation message
+    pub const ATTESTATION_DOMAIN: &'static [u8] = b"rwa-kyc-attestation";
+
+    /// Calculate the space required for KycAttestorRegistry
+    pub const LEN: usize = 8 + // discriminator
+        4 + 32 * Self::MAX_ATTESTORS + // attestors
+        8; // updated_at
+
+    /// Check if a provider key is trusted
+    pub fn is_trusted(&self, attestor: &Pubkey) -> bool {
+        self.attestors.contains(attestor)
+    }
+
+    /// Build the message a provider signs:
+    /// `domain || user || level (u8) || expires_at (i64 LE) || nonce (u64 LE)`
+    pub fn attestation_message(
+        user: &Pubkey,
+        verification_level: u8,
+        expires_at: i64,
+        nonce: u64,
+    ) -> Vec<u8> {
+        let mut message = Vec::with_capacity(Self::ATTESTATION_DOMAIN.len() + 32 + 1 + 8 + 8);
+        message.extend_from_slice(Self::ATTESTATION_DOMAIN);
+        message.extend_from_slice(user.as_ref());
+        message.push(verification_level);
+        message.extend_from_slice(&expires_at.to_le_bytes());
+        message.extend_from_slice(&nonce.to_le_bytes());
+        message
+    }
+}
+
+/// Transaction types for KYC validation
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TransactionType {
+    /// Buying fractions in a primary sale
+    Purchase,
+    /// Redeeming the original NFT
+    Redemption,
+    /// Transferring fractions to another wallet
+    Transfer,
+}
+
+/// Outcome of a cross-program KYC check, returned through `set_return_data`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Debug)]
+pub struct KycCheckResult {
+    /// The wallet that was checked
+    pub wallet: Pubkey,
+
+    /// The transaction type the wallet was checked for
+    pub transaction_type: TransactionType,
+
+    /// Whether the wallet may perform the transaction
+    pub passed: bool,
+
+    /// Why the check failed, if it did
+    pub failure: Option<KycCheckFailure>,
+
+    /// Verification level (0 if not registered)
+    pub verification_level: u8,
+
+    /// Timestamp when the verification expires (0 = never)
+    pub expires_at: i64,
+}
+
+/// Reasons a cross-program KYC check can fail
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum KycCheckFailure {
+    /// The wallet has no KYC account
+    NotRegistered,
+    /// The wallet's KYC is not verified
+    NotVerified,
+    /// The wallet's KYC is suspended
+    Suspended,
+    /// The wallet's KYC is revoked
+    Revoked,
+    /// The wallet's verification has expired
+    Expired,
+    /// The wallet's jurisdiction may not acquire fractions
+    JurisdictionRestricted,
+}
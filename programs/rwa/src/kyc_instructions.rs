@@ -1,23 +1,66 @@
+//! Enhanced KYC instructions for professional compliance
+
 use anchor_lang::prelude::*;
 
 use crate::account_structs::*;
 use crate::error::RwaError;
+use crate::events::{EnhancedKycStatusChanged, KycProviderConfigUpdated};
+use crate::kyc::{
+    ComplianceFlags, EnhancedKycAccount, KycProvider, KycProviderConfig, KycRequirements,
+    KycStatus, RiskLevel,
+};
 
-/// Enhanced KYC instructions for professional compliance
+/// Create the KYC provider configuration (super admin only)
+///
+/// Provider results and the provider list are then managed by KYC officers.
+pub fn initialize_kyc_provider_config(
+    ctx: Context<InitializeKycProviderConfig>,
+    active_providers: Vec<KycProvider>,
+    default_provider: KycProvider,
+    requirements: KycRequirements,
+) -> Result<()> {
+    let provider_config = &mut ctx.accounts.provider_config;
+    let clock = Clock::get()?;
+
+    validate_provider_list(&active_providers, default_provider)?;
+
+    provider_config.active_providers = active_providers;
+    provider_config.default_provider = default_provider;
+    provider_config.requirements = requirements;
+    provider_config.updated_at = clock.unix_timestamp;
+
+    msg!("KYC provider configuration initialized");
+    msg!("Admin: {}", ctx.accounts.admin.key());
+    msg!("Default Provider: {:?}", default_provider);
+
+    emit!(KycProviderConfigUpdated {
+        admin: ctx.accounts.admin.key(),
+        active_providers: provider_config.active_providers.clone(),
+        default_provider,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
 
 /// Initialize KYC verification with a specific provider
 ///
 /// This instruction starts the KYC process with an external provider
-/// and stores the verification session information.
+/// and stores the verification session information. The email and phone
+/// number go to the provider off-chain; only a salted hash of them is stored.
 pub fn initiate_kyc_verification(
     ctx: Context<InitiateKycVerification>,
     provider: KycProvider,
-    user_email: String,
-    user_phone: Option<String>,
+    contact_commitment: [u8; 32],
 ) -> Result<()> {
     let kyc_account = &mut ctx.accounts.kyc_account;
     let clock = Clock::get()?;
 
+    require!(
+        contact_commitment != [0; 32],
+        RwaError::InvalidPiiCommitment
+    );
+
     // Validate provider is active
     require!(
         ctx.accounts
@@ -27,40 +70,40 @@ pub fn initiate_kyc_verification(
         RwaError::InvalidKycProvider
     );
 
-    // Check if user can attempt verification
-    require!(
-        kyc_account.can_attempt_verification(),
-        RwaError::KycVerificationLimitExceeded
-    );
-
     // Initialize KYC account
     kyc_account.user = ctx.accounts.user.key();
     kyc_account.status = KycStatus::InProgress;
-    kyc_account.provider = provider.clone();
+    kyc_account.provider = provider;
     kyc_account.registered_at = clock.unix_timestamp;
     kyc_account.verification_attempts = kyc_account.verification_attempts.saturating_add(1);
     kyc_account.last_attempt_at = Some(clock.unix_timestamp);
     kyc_account.compliance_flags = ComplianceFlags::default();
 
     // Generate provider-specific verification ID
-    let verification_id = format!(
-        "{}_{}_{}",
-        provider.to_string(),
-        ctx.accounts.user.key().to_string()[..8].to_string(),
-        clock.unix_timestamp
-    );
+    let user_key = ctx.accounts.user.key().to_string();
+    let verification_id = format!("{}_{}_{}", provider, &user_key[..8], clock.unix_timestamp);
     kyc_account.provider_verification_id = Some(verification_id.clone());
+    kyc_account.contact_commitment = contact_commitment;
 
     msg!("KYC verification initiated");
     msg!("User: {}", ctx.accounts.user.key());
     msg!("Provider: {:?}", provider);
     msg!("Verification ID: {}", verification_id);
-    msg!("Email: {}", user_email);
+
+    emit!(EnhancedKycStatusChanged {
+        user: kyc_account.user,
+        authority: ctx.accounts.user.key(),
+        status: kyc_account.status,
+        provider,
+        risk_score: None,
+        expires_at: None,
+        timestamp: clock.unix_timestamp,
+    });
 
     // In a real implementation, you would:
     // 1. Call the provider's API to initiate verification
     // 2. Store the session ID and redirect URL
-    // 3. Send verification link to user's email
+    // 3. Send verification link to the user's email, kept off-chain
 
     Ok(())
 }
@@ -85,22 +128,25 @@ pub fn process_kyc_result(
         kyc_account.provider_verification_id.as_ref() == Some(&verification_id),
         RwaError::InvalidVerificationId
     );
+    validate_metadata(&provider_metadata)?;
 
     // Update KYC status
-    kyc_account.status = status.clone();
+    kyc_account.status = status;
     kyc_account.risk_score = risk_score;
     kyc_account.compliance_flags = compliance_data;
     kyc_account.provider_metadata = provider_metadata;
 
     // Set verification timestamp if successful
     if status == KycStatus::Verified {
+        validate_compliance(kyc_account, &ctx.accounts.provider_config.requirements)?;
         kyc_account.verified_at = Some(clock.unix_timestamp);
 
         // Set expiration if configured
-        if let Some(expiration_days) = ctx.accounts.provider_config.requirements.expiration_days {
-            kyc_account.expires_at =
-                Some(clock.unix_timestamp + (expiration_days as i64 * 24 * 60 * 60));
-        }
+        kyc_account.expires_at = ctx
+            .accounts
+            .provider_config
+            .requirements
+            .expiry_from(clock.unix_timestamp);
     }
 
     msg!("KYC verification result processed");
@@ -109,10 +155,20 @@ pub fn process_kyc_result(
     msg!("Risk Score: {:?}", risk_score);
     msg!("Compliance Flags: {:?}", kyc_account.compliance_flags);
 
+    emit!(EnhancedKycStatusChanged {
+        user: kyc_account.user,
+        authority: ctx.accounts.admin.key(),
+        status,
+        provider: kyc_account.provider,
+        risk_score,
+        expires_at: kyc_account.expires_at,
+        timestamp: clock.unix_timestamp,
+    });
+
     Ok(())
 }
 
-/// Manually verify KYC (admin only)
+/// Manually verify KYC (KYC officer only)
 ///
 /// This instruction allows platform admins to manually verify users
 /// for cases where automated verification fails or is not available.
@@ -126,6 +182,8 @@ pub fn manual_kyc_verification(
     let kyc_account = &mut ctx.accounts.kyc_account;
     let clock = Clock::get()?;
 
+    validate_metadata(&notes)?;
+
     // Update KYC status
     kyc_account.status = if is_verified {
         KycStatus::Verified
@@ -139,13 +197,15 @@ pub fn manual_kyc_verification(
     kyc_account.provider_metadata = notes;
 
     if is_verified {
+        validate_compliance(kyc_account, &ctx.accounts.provider_config.requirements)?;
         kyc_account.verified_at = Some(clock.unix_timestamp);
 
         // Set expiration if configured
-        if let Some(expiration_days) = ctx.accounts.provider_config.requirements.expiration_days {
-            kyc_account.expires_at =
-                Some(clock.unix_timestamp + (expiration_days as i64 * 24 * 60 * 60));
-        }
+        kyc_account.expires_at = ctx
+            .accounts
+            .provider_config
+            .requirements
+            .expiry_from(clock.unix_timestamp);
     }
 
     msg!("Manual KYC verification completed");
@@ -154,10 +214,20 @@ pub fn manual_kyc_verification(
     msg!("Risk Score: {:?}", risk_score);
     msg!("Admin: {}", ctx.accounts.admin.key());
 
+    emit!(EnhancedKycStatusChanged {
+        user: kyc_account.user,
+        authority: ctx.accounts.admin.key(),
+        status: kyc_account.status,
+        provider: kyc_account.provider,
+        risk_score,
+        expires_at: kyc_account.expires_at,
+        timestamp: clock.unix_timestamp,
+    });
+
     Ok(())
 }
 
-/// Suspend KYC verification (admin only)
+/// Suspend KYC verification (KYC officer only)
 ///
 /// This instruction allows platform admins to suspend a user's
 /// KYC verification due to compliance issues or suspicious activity.
@@ -166,6 +236,12 @@ pub fn suspend_kyc_verification(
     reason: String,
 ) -> Result<()> {
     let kyc_account = &mut ctx.accounts.kyc_account;
+    let clock = Clock::get()?;
+
+    require!(
+        reason.len() <= EnhancedKycAccount::MAX_METADATA_LEN,
+        RwaError::KycMetadataTooLong
+    );
 
     // Update status to suspended
     kyc_account.status = KycStatus::Suspended;
@@ -176,6 +252,16 @@ pub fn suspend_kyc_verification(
     msg!("Reason: {}", reason);
     msg!("Admin: {}", ctx.accounts.admin.key());
 
+    emit!(EnhancedKycStatusChanged {
+        user: kyc_account.user,
+        authority: ctx.accounts.admin.key(),
+        status: KycStatus::Suspended,
+        provider: kyc_account.provider,
+        risk_score: kyc_account.risk_score,
+        expires_at: kyc_account.expires_at,
+        timestamp: clock.unix_timestamp,
+    });
+
     Ok(())
 }
 
@@ -188,10 +274,14 @@ pub fn refresh_kyc_verification(
     provider: Option<KycProvider>,
 ) -> Result<()> {
     let kyc_account = &mut ctx.accounts.kyc_account;
+    let clock = Clock::get()?;
 
     // Check if refresh is allowed
     require!(
-        kyc_account.status == KycStatus::Expired || kyc_account.status == KycStatus::Failed,
+        kyc_account.status == KycStatus::Expired
+            || kyc_account.status == KycStatus::Failed
+            || (kyc_account.status == KycStatus::Verified
+                && kyc_account.is_expired(clock.unix_timestamp)),
         RwaError::KycRefreshNotAllowed
     );
 
@@ -202,12 +292,20 @@ pub fn refresh_kyc_verification(
     );
 
     // Use provided provider or default
-    let selected_provider =
-        provider.unwrap_or_else(|| ctx.accounts.provider_config.default_provider.clone());
+    let selected_provider = provider.unwrap_or(ctx.accounts.provider_config.default_provider);
+    require!(
+        ctx.accounts
+            .provider_config
+            .active_providers
+            .contains(&selected_provider),
+        RwaError::InvalidKycProvider
+    );
 
     // Reset status to pending
     kyc_account.status = KycStatus::Pending;
-    kyc_account.provider = selected_provider.clone();
+    kyc_account.provider = selected_provider;
+    kyc_account.verification_attempts = kyc_account.verification_attempts.saturating_add(1);
+    kyc_account.last_attempt_at = Some(clock.unix_timestamp);
     kyc_account.verified_at = None;
     kyc_account.expires_at = None;
     kyc_account.risk_score = None;
@@ -218,10 +316,20 @@ pub fn refresh_kyc_verification(
     msg!("User: {}", kyc_account.user);
     msg!("Provider: {:?}", selected_provider);
 
+    emit!(EnhancedKycStatusChanged {
+        user: kyc_account.user,
+        authority: ctx.accounts.user.key(),
+        status: KycStatus::Pending,
+        provider: selected_provider,
+        risk_score: None,
+        expires_at: None,
+        timestamp: clock.unix_timestamp,
+    });
+
     Ok(())
 }
 
-/// Update KYC provider configuration (admin only)
+/// Update KYC provider configuration (KYC officer only)
 ///
 /// This instruction allows platform admins to update KYC provider
 /// configurations and requirements.
@@ -234,6 +342,8 @@ pub fn update_kyc_provider_config(
     let provider_config = &mut ctx.accounts.provider_config;
     let clock = Clock::get()?;
 
+    validate_provider_list(&active_providers, default_provider)?;
+
     // Update configuration
     provider_config.active_providers = active_providers.clone();
     provider_config.default_provider = default_provider;
@@ -245,163 +355,60 @@ pub fn update_kyc_provider_config(
     msg!("Default Provider: {:?}", default_provider);
     msg!("Admin: {}", ctx.accounts.admin.key());
 
+    emit!(KycProviderConfigUpdated {
+        admin: ctx.accounts.admin.key(),
+        active_providers,
+        default_provider,
+        timestamp: clock.unix_timestamp,
+    });
+
     Ok(())
 }
 
-/// Validate KYC status for transaction
-///
-/// This instruction validates that a user's KYC status allows them
-/// to perform transactions on the platform.
-pub fn validate_kyc_for_transaction(
-    ctx: Context<ValidateKycForTransaction>,
-    transaction_type: TransactionType,
+/// Validate that a user passes the screenings and risk limit to be verified
+fn validate_compliance(
+    kyc_account: &EnhancedKycAccount,
+    requirements: &KycRequirements,
 ) -> Result<()> {
-    let kyc_account = &ctx.accounts.kyc_account;
-
-    // Check if KYC is valid
-    require!(kyc_account.is_valid(), RwaError::KycNotVerified);
-
-    // Check compliance requirements based on transaction type
-    match transaction_type {
-        TransactionType::Purchase => {
-            require!(
-                kyc_account.is_fully_compliant(),
-                RwaError::KycComplianceIncomplete
-            );
-        }
-        TransactionType::Redemption => {
-            // Redemption might have different requirements
-            require!(
-                kyc_account.compliance_flags.aml_cleared,
-                RwaError::KycComplianceIncomplete
-            );
-        }
-        TransactionType::Transfer => {
-            // Transfer might have stricter requirements
-            require!(
-                kyc_account.is_fully_compliant(),
-                RwaError::KycComplianceIncomplete
-            );
-        }
-    }
-
-    // Check risk level
-    let risk_level = kyc_account.get_risk_level();
-    require!(risk_level != RiskLevel::High, RwaError::KycRiskLevelTooHigh);
-
-    msg!("KYC validation successful");
-    msg!("User: {}", kyc_account.user);
-    msg!("Transaction Type: {:?}", transaction_type);
-    msg!("Risk Level: {:?}", risk_level);
+    require!(
+        kyc_account.is_fully_compliant()
+            && requirements.is_satisfied_by(&kyc_account.compliance_flags),
+        RwaError::KycComplianceIncomplete
+    );
+    require!(
+        kyc_account.get_risk_level() != RiskLevel::High,
+        RwaError::KycRiskLevelTooHigh
+    );
 
     Ok(())
 }
 
-/// Transaction types for KYC validation
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq)]
-pub enum TransactionType {
-    Purchase,
-    Redemption,
-    Transfer,
-}
-
-// Account validation structs for enhanced KYC instructions
-
-#[derive(Accounts)]
-pub struct InitiateKycVerification<'info> {
-    #[account(mut)]
-    pub user: Signer<'info>,
-
-    #[account(
-        init,
-        payer = user,
-        space = EnhancedKycAccount::LEN,
-        seeds = [b"kyc", user.key().as_ref()],
-        bump
-    )]
-    pub kyc_account: Account<'info, EnhancedKycAccount>,
-
-    #[account(
-        constraint = provider_config.active_providers.len() > 0
-    )]
-    pub provider_config: Account<'info, KycProviderConfig>,
-
-    pub system_program: Program<'info, System>,
-}
-
-#[derive(Accounts)]
-pub struct ProcessKycResult<'info> {
-    #[account(mut)]
-    pub admin: Signer<'info>,
-
-    #[account(mut)]
-    pub kyc_account: Account<'info, EnhancedKycAccount>,
-
-    #[account(
-        constraint = provider_config.admin == admin.key()
-    )]
-    pub provider_config: Account<'info, KycProviderConfig>,
-}
-
-#[derive(Accounts)]
-pub struct ManualKycVerification<'info> {
-    #[account(mut)]
-    pub admin: Signer<'info>,
-
-    #[account(mut)]
-    pub kyc_account: Account<'info, EnhancedKycAccount>,
-
-    #[account(
-        constraint = provider_config.admin == admin.key()
-    )]
-    pub provider_config: Account<'info, KycProviderConfig>,
-}
-
-#[derive(Accounts)]
-pub struct SuspendKycVerification<'info> {
-    #[account(mut)]
-    pub admin: Signer<'info>,
-
-    #[account(mut)]
-    pub kyc_account: Account<'info, EnhancedKycAccount>,
-
-    #[account(
-        constraint = provider_config.admin == admin.key()
-    )]
-    pub provider_config: Account<'info, KycProviderConfig>,
-}
-
-#[derive(Accounts)]
-pub struct RefreshKycVerification<'info> {
-    #[account(mut)]
-    pub user: Signer<'info>,
-
-    #[account(mut)]
-    pub kyc_account: Account<'info, EnhancedKycAccount>,
-
-    pub provider_config: Account<'info, KycProviderConfig>,
-}
-
-#[derive(Accounts)]
-pub struct UpdateKycProviderConfig<'info> {
-    #[account(mut)]
-    pub admin: Signer<'info>,
+/// Validate the active provider list and default provider
+fn validate_provider_list(
+    active_providers: &[KycProvider],
+    default_provider: KycProvider,
+) -> Result<()> {
+    require!(
+        !active_providers.is_empty()
+            && active_providers.len() <= KycProviderConfig::MAX_ACTIVE_PROVIDERS,
+        RwaError::InvalidKycProvider
+    );
+    require!(
+        active_providers.contains(&default_provider),
+        RwaError::InvalidKycProvider
+    );
 
-    #[account(
-        mut,
-        constraint = provider_config.admin == admin.key()
-    )]
-    pub provider_config: Account<'info, KycProviderConfig>,
+    Ok(())
 }
 
-#[derive(Accounts)]
-pub struct ValidateKycForTransaction<'info> {
-    pub user: Signer<'info>,
-
-    #[account(
-        constraint = kyc_account.user == user.key()
-    )]
-    pub kyc_account: Account<'info, EnhancedKycAccount>,
+/// Validate that provider metadata fits in the reserved account space
+fn validate_metadata(metadata: &Option<String>) -> Result<()> {
+    require!(
+        metadata
+            .as_ref()
+            .is_none_or(|metadata| metadata.len() <= EnhancedKycAccount::MAX_METADATA_LEN),
+        RwaError::KycMetadataTooLong
+    );
 
-    pub provider_config: Account<'info, KycProviderConfig>,
+    Ok(())
 }
@@ -1,16 +1,24 @@
+// `#[program]` emits its IDL instructions, which still call the deprecated
+// `AccountInfo::realloc`, outside the annotated module, so an allow scoped to
+// the module does not reach them. Drop this once Anchor moves to `resize`.
+#![allow(deprecated)]
+
 use anchor_lang::prelude::*;
 
 // Import our modular components
 mod account_structs;
 mod error;
+mod events;
 mod instructions;
 mod kyc;
+mod kyc_instructions;
 mod state;
 mod utils;
 
 // Re-export commonly used types
 pub use account_structs::*;
 pub use error::RwaError;
+pub use events::*;
 pub use kyc::*;
 pub use state::*;
 
@@ -29,9 +37,9 @@ declare_id!("Guhyo3fAg6Qys962ngVbsidvzEWsBiGmZ3XYMyo73MfE");
 /// - Global Access: Enable worldwide art investment with small amounts
 ///
 /// ## Hackathon-Friendly KYC Flow:
-/// 1. **Simple Registration**: Users register with minimal info (email, country)
+/// 1. **Simple Registration**: Users register with just their country code
 /// 2. **Auto-Verification**: Demo mode allows instant verification
-/// 3. **Mock Verification**: Simulate email/document verification
+/// 3. **Off-Chain PII**: Only a hashed commitment to the KYC dossier is stored
 /// 4. **Admin Override**: Admins can manually verify users
 /// 5. **Compliance Ready**: Structure supports real KYC providers
 #[program]
@@ -39,34 +47,146 @@ pub mod rwa {
     use super::*;
 
     /// Initialize the platform with default configuration
-    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
-        instructions::initialize(ctx)
+    ///
+    /// `treasury` is the wallet whose token accounts receive platform fees.
+    /// `default_royalty_bps` is the royalty of Token-2022 vaults that do not
+    /// set their own.
+    pub fn initialize(
+        ctx: Context<Initialize>,
+        treasury: Pubkey,
+        default_royalty_bps: u16,
+    ) -> Result<()> {
+        instructions::initialize(ctx, treasury, default_royalty_bps)
     }
 
     /// Fractionalize an NFT into tradeable shares (SIMPLIFIED FOR HACKATHON)
     ///
     /// This instruction takes an NFT and creates fractional ownership tokens.
     /// Uses standard SPL Token for simplicity and speed.
+    /// The NFT must be a Metaplex master edition in a verified collection
+    /// approved by the platform. Programmable NFTs are moved with the token
+    /// metadata `Transfer`, which needs the optional token record accounts.
+    ///
+    /// `total_fractions` is in base units of a mint with `fractional_decimals`
+    /// decimals, while `price_per_fraction` is per whole share. The optional
+    /// `sale_terms` bound the primary sale with start and end timestamps, hold
+    /// proceeds in escrow until a soft cap is met, price the first fractions
+    /// sold in tiers, or run the sale as a Dutch auction down to
+    /// `price_per_fraction`.
     pub fn fractionalize(
         ctx: Context<Fractionalize>,
         total_fractions: u64,
         price_per_fraction: u64,
+        fractional_decimals: u8,
+        sale_terms: SaleTerms,
     ) -> Result<()> {
-        instructions::fractionalize(ctx, total_fractions, price_per_fraction)
+        instructions::fractionalize(
+            ctx,
+            total_fractions,
+            price_per_fraction,
+            fractional_decimals,
+            sale_terms,
+        )
+    }
+
+    /// Fractionalize an NFT into Token-2022 shares guarded by the KYC transfer hook
+    ///
+    /// Secondary transfers of the shares are only possible between wallets
+    /// that pass KYC, enforced by the `kyc-transfer-hook` program.
+    /// A non-zero `royalty_bps` (defaults to the platform royalty) is charged
+    /// on secondary transfers through the Token-2022 transfer fee extension.
+    pub fn fractionalize_token_2022(
+        ctx: Context<FractionalizeToken2022>,
+        total_fractions: u64,
+        price_per_fraction: u64,
+        fractional_decimals: u8,
+        sale_terms: SaleTerms,
+        royalty_bps: Option<u16>,
+    ) -> Result<()> {
+        instructions::fractionalize_token_2022(
+            ctx,
+            total_fractions,
+            price_per_fraction,
+            fractional_decimals,
+            sale_terms,
+            royalty_bps,
+        )
+    }
+
+    /// Pay out withheld transfer-fee royalties to the creator and treasury
+    ///
+    /// Permissionless. Pass holder token accounts with withheld fees as
+    /// remaining accounts.
+    pub fn harvest_royalties<'info>(
+        ctx: Context<'_, '_, '_, 'info, HarvestRoyalties<'info>>,
+    ) -> Result<()> {
+        instructions::harvest_royalties(ctx)
     }
 
     /// Buy fractional tokens (SIMPLIFIED FOR HACKATHON)
     ///
     /// This instruction allows KYC-verified users to purchase fractional tokens.
-    /// Uses standard SPL Token transfers for simplicity.
-    pub fn buy_fractions(ctx: Context<BuyFractions>, num_fractions: u64) -> Result<()> {
+    /// Token-2022 vaults must pass the transfer hook's extra accounts as
+    /// remaining accounts.
+    pub fn buy_fractions<'info>(
+        ctx: Context<'_, '_, '_, 'info, BuyFractions<'info>>,
+        num_fractions: u64,
+    ) -> Result<()> {
         instructions::buy_fractions(ctx, num_fractions)
     }
 
+    /// Close a vault's primary sale once its window lapses or it sells out
+    ///
+    /// Permissionless. Sets `sale_ended_at` on the vault.
+    pub fn close_sale(ctx: Context<CloseSale>) -> Result<()> {
+        instructions::close_sale(ctx)
+    }
+
+    /// Change the base price of an active sale (creator only)
+    ///
+    /// Fractions within the vault's pricing tiers keep their tier price.
+    pub fn update_price(ctx: Context<UpdatePrice>, new_price_per_fraction: u64) -> Result<()> {
+        instructions::update_price(ctx, new_price_per_fraction)
+    }
+
+    /// Claim the escrowed proceeds of a soft-capped or uniform clearing price
+    /// sale (creator only)
+    ///
+    /// Available once the soft cap is met, or once the auction closes.
+    pub fn claim_proceeds(ctx: Context<ClaimProceeds>) -> Result<()> {
+        instructions::claim_proceeds(ctx)
+    }
+
+    /// Burn the fractions bought in a sale that ended below its soft cap for
+    /// a full refund from escrow
+    pub fn refund_fractions(ctx: Context<RefundFractions>) -> Result<()> {
+        instructions::refund_fractions(ctx)
+    }
+
+    /// Refund an early buyer of a uniform clearing price auction down to the
+    /// clearing price once the auction closes
+    pub fn claim_auction_refund(ctx: Context<ClaimAuctionRefund>) -> Result<()> {
+        instructions::claim_auction_refund(ctx)
+    }
+
+    /// Return the NFT of a sale that ended below its soft cap to the creator
+    pub fn reclaim_nft(ctx: Context<ReclaimNft>) -> Result<()> {
+        instructions::reclaim_nft(ctx)
+    }
+
+    /// Cancel a vault before any fractions are sold: burn the fractions,
+    /// return the NFT to the creator and close the vault's accounts
+    pub fn cancel_vault(ctx: Context<CancelVault>) -> Result<()> {
+        instructions::cancel_vault(ctx)
+    }
+
     /// Redeem NFT by burning all fractional tokens (SIMPLIFIED FOR HACKATHON)
     ///
     /// This instruction allows a user who owns all fractional tokens to redeem the original NFT.
-    pub fn redeem(ctx: Context<Redeem>) -> Result<()> {
+    /// Programmable NFTs are returned with the token metadata `Transfer`.
+    /// For royalty vaults, royalties still withheld count towards the redeemer's
+    /// shares; pass holder token accounts withholding them as remaining accounts.
+    pub fn redeem<'info>(ctx: Context<'_, '_, '_, 'info, Redeem<'info>>) -> Result<()> {
         instructions::redeem(ctx)
     }
 
@@ -74,29 +194,120 @@ pub mod rwa {
     ///
     /// This instruction creates a simple KYC account for a user with minimal requirements.
     /// Perfect for hackathon demos where you want to show the flow without barriers.
-    pub fn register_kyc(ctx: Context<RegisterKyc>) -> Result<()> {
-        instructions::register_kyc(ctx)
+    /// The user supplies an ISO 3166-1 alpha-2 country code for jurisdiction checks.
+    pub fn register_kyc(ctx: Context<RegisterKyc>, country: String) -> Result<()> {
+        instructions::register_kyc(ctx, country)
     }
 
-    /// Verify a user's KYC status (admin only)
+    /// Verify a user's KYC status (KYC officer only)
     ///
     /// This instruction allows platform admins to manually verify users.
     /// This is the main verification method for hackathon demos.
+    /// Only a commitment to the off-chain dossier and the officer's signature
+    /// over it are stored. The signature must be checked by an Ed25519 program
+    /// instruction placed right before this one.
     pub fn verify_kyc(
         ctx: Context<VerifyKyc>,
         verification_method: VerificationMethod,
         verification_level: u8,
+        pii_commitment: [u8; 32],
     ) -> Result<()> {
-        instructions::verify_kyc(ctx, verification_method, verification_level)
+        instructions::verify_kyc(ctx, verification_method, verification_level, pii_commitment)
     }
 
-    /// Update platform configuration (admin only)
+    /// Create the registry of trusted KYC provider keys (super admin only)
+    pub fn initialize_kyc_attestor_registry(
+        ctx: Context<InitializeKycAttestorRegistry>,
+    ) -> Result<()> {
+        instructions::initialize_kyc_attestor_registry(ctx)
+    }
+
+    /// Trust a KYC provider's Ed25519 key for attestations (KYC officer only)
+    pub fn add_kyc_attestor(ctx: Context<ManageKycAttestors>, attestor: Pubkey) -> Result<()> {
+        instructions::add_kyc_attestor(ctx, attestor)
+    }
+
+    /// Stop trusting a KYC provider's key (KYC officer only)
+    pub fn remove_kyc_attestor(ctx: Context<ManageKycAttestors>, attestor: Pubkey) -> Result<()> {
+        instructions::remove_kyc_attestor(ctx, attestor)
+    }
+
+    /// Verify the caller's KYC from a trusted provider's Ed25519-signed attestation
+    ///
+    /// Must be preceded by an Ed25519 program instruction carrying the
+    /// provider's signature over (user, level, expiry, nonce), so verification
+    /// does not need a platform key online.
+    pub fn submit_kyc_attestation(
+        ctx: Context<SubmitKycAttestation>,
+        verification_level: u8,
+        expires_at: i64,
+        nonce: u64,
+    ) -> Result<()> {
+        instructions::submit_kyc_attestation(ctx, verification_level, expires_at, nonce)
+    }
+
+    /// Check whether a wallet passes KYC for a transaction type (read-only)
+    ///
+    /// Other programs can depend on this crate with the `cpi` feature and call
+    /// `rwa::cpi::check_kyc`; the `KycCheckResult` comes back through
+    /// `set_return_data`.
+    pub fn check_kyc(
+        ctx: Context<CheckKyc>,
+        transaction_type: TransactionType,
+    ) -> Result<KycCheckResult> {
+        instructions::check_kyc(ctx, transaction_type)
+    }
+
+    /// Record an audit of a user's off-chain KYC dossier against their
+    /// on-chain commitment, opened off-chain (KYC officer only)
+    pub fn audit_kyc_commitment(
+        ctx: Context<AuditKycCommitment>,
+        pii_commitment: [u8; 32],
+    ) -> Result<()> {
+        instructions::audit_kyc_commitment(ctx, pii_commitment)
+    }
+
+    /// Reject a user's KYC application with a reason code (KYC officer only)
+    pub fn reject_kyc(ctx: Context<UpdateKycStatus>, reason: KycReasonCode) -> Result<()> {
+        instructions::reject_kyc(ctx, reason)
+    }
+
+    /// Suspend a user's KYC with a reason code (KYC officer only)
+    ///
+    /// Suspended users can neither buy nor redeem until verified again.
+    pub fn suspend_kyc(ctx: Context<UpdateKycStatus>, reason: KycReasonCode) -> Result<()> {
+        instructions::suspend_kyc(ctx, reason)
+    }
+
+    /// Permanently revoke a user's KYC with a reason code (KYC officer only)
+    pub fn revoke_kyc(ctx: Context<UpdateKycStatus>, reason: KycReasonCode) -> Result<()> {
+        instructions::revoke_kyc(ctx, reason)
+    }
+
+    /// Reset the caller's KYC record to request re-verification
+    ///
+    /// Lets users renew an expired verification without closing their KYC account.
+    pub fn request_kyc_reverification(ctx: Context<RequestKycReverification>) -> Result<()> {
+        instructions::request_kyc_reverification(ctx)
+    }
+
+    /// Set how long KYC verifications stay valid in seconds, 0 = never (KYC officer only)
+    pub fn update_kyc_validity_period(
+        ctx: Context<UpdateKycPolicy>,
+        kyc_validity_period: i64,
+    ) -> Result<()> {
+        instructions::update_kyc_validity_period(ctx, kyc_validity_period)
+    }
+
+    /// Update platform configuration (fee manager only)
     pub fn update_platform_config(
         ctx: Context<UpdatePlatformConfig>,
         platform_fee_numerator: u16,
         platform_fee_denominator: u16,
         min_investment_amount: u64,
         max_investment_amount: u64,
+        treasury: Pubkey,
+        default_royalty_bps: u16,
     ) -> Result<()> {
         instructions::update_platform_config(
             ctx,
@@ -104,6 +315,221 @@ pub mod rwa {
             platform_fee_denominator,
             min_investment_amount,
             max_investment_amount,
+            treasury,
+            default_royalty_bps,
+        )
+    }
+
+    /// Update the per-vault purchase ceilings for KYC levels 1-3 (KYC officer only)
+    pub fn update_kyc_level_limits(
+        ctx: Context<UpdateKycLevelLimits>,
+        kyc_level_limits: [u64; 3],
+    ) -> Result<()> {
+        instructions::update_kyc_level_limits(ctx, kyc_level_limits)
+    }
+
+    /// Add a payment mint (e.g. USDC) to the platform allowlist (fee manager only)
+    pub fn add_payment_mint(
+        ctx: Context<UpdatePlatformConfig>,
+        payment_mint: Pubkey,
+    ) -> Result<()> {
+        instructions::add_payment_mint(ctx, payment_mint)
+    }
+
+    /// Remove a payment mint from the platform allowlist (fee manager only)
+    pub fn remove_payment_mint(
+        ctx: Context<UpdatePlatformConfig>,
+        payment_mint: Pubkey,
+    ) -> Result<()> {
+        instructions::remove_payment_mint(ctx, payment_mint)
+    }
+
+    /// Approve a verified Metaplex collection whose NFTs may be fractionalized (super admin only)
+    pub fn add_approved_collection(
+        ctx: Context<ManageAdmin>,
+        collection_mint: Pubkey,
+    ) -> Result<()> {
+        instructions::add_approved_collection(ctx, collection_mint)
+    }
+
+    /// Remove a collection from the approved list (super admin only)
+    pub fn remove_approved_collection(
+        ctx: Context<ManageAdmin>,
+        collection_mint: Pubkey,
+    ) -> Result<()> {
+        instructions::remove_approved_collection(ctx, collection_mint)
+    }
+
+    /// Create the jurisdiction blocklist/allowlist consulted on purchases (super admin only)
+    pub fn initialize_jurisdiction_config(
+        ctx: Context<InitializeJurisdictionConfig>,
+        mode: JurisdictionMode,
+    ) -> Result<()> {
+        instructions::initialize_jurisdiction_config(ctx, mode)
+    }
+
+    /// Switch the jurisdiction list between blocklist and allowlist (KYC officer only)
+    pub fn set_jurisdiction_mode(
+        ctx: Context<ManageJurisdictions>,
+        mode: JurisdictionMode,
+    ) -> Result<()> {
+        instructions::set_jurisdiction_mode(ctx, mode)
+    }
+
+    /// Add a country code to the jurisdiction list (KYC officer only)
+    pub fn add_jurisdiction(ctx: Context<ManageJurisdictions>, country: String) -> Result<()> {
+        instructions::add_jurisdiction(ctx, country)
+    }
+
+    /// Remove a country code from the jurisdiction list (KYC officer only)
+    pub fn remove_jurisdiction(ctx: Context<ManageJurisdictions>, country: String) -> Result<()> {
+        instructions::remove_jurisdiction(ctx, country)
+    }
+
+    /// Pause the whole platform (pauser only)
+    pub fn pause_platform(ctx: Context<SetPlatformStatus>) -> Result<()> {
+        instructions::pause_platform(ctx)
+    }
+
+    /// Unpause the platform (pauser only)
+    pub fn unpause_platform(ctx: Context<SetPlatformStatus>) -> Result<()> {
+        instructions::unpause_platform(ctx)
+    }
+
+    /// Halt or resume purchases, redemption and royalty harvests for a single vault
+    /// (pauser only)
+    pub fn set_vault_halted(ctx: Context<SetVaultHalted>, is_halted: bool) -> Result<()> {
+        instructions::set_vault_halted(ctx, is_halted)
+    }
+
+    /// Freeze a holder's fractional token account (pauser only)
+    ///
+    /// Signed by the vault PDA, which is the fractional mint's freeze authority.
+    pub fn freeze_holder_account(ctx: Context<FreezeHolderAccount>) -> Result<()> {
+        instructions::freeze_holder_account(ctx)
+    }
+
+    /// Thaw a holder's fractional token account (pauser only)
+    pub fn thaw_holder_account(ctx: Context<FreezeHolderAccount>) -> Result<()> {
+        instructions::thaw_holder_account(ctx)
+    }
+
+    /// Propose a new super admin (super admin only)
+    ///
+    /// Takes effect once the proposed admin calls `accept_admin`.
+    pub fn propose_admin(ctx: Context<ManageAdmin>, new_admin: Pubkey) -> Result<()> {
+        instructions::propose_admin(ctx, new_admin)
+    }
+
+    /// Accept a proposed super admin transfer (proposed admin only)
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        instructions::accept_admin(ctx)
+    }
+
+    /// Grant a delegated role (KYC officer, fee manager, pauser) to an authority (super admin only)
+    pub fn grant_role(ctx: Context<ManageAdmin>, authority: Pubkey, role: AdminRole) -> Result<()> {
+        instructions::grant_role(ctx, authority, role)
+    }
+
+    /// Revoke a delegated role from an authority (super admin only)
+    pub fn revoke_role(
+        ctx: Context<ManageAdmin>,
+        authority: Pubkey,
+        role: AdminRole,
+    ) -> Result<()> {
+        instructions::revoke_role(ctx, authority, role)
+    }
+
+    /// Create the enhanced KYC provider configuration (super admin only)
+    pub fn initialize_kyc_provider_config(
+        ctx: Context<InitializeKycProviderConfig>,
+        active_providers: Vec<KycProvider>,
+        default_provider: KycProvider,
+        requirements: KycRequirements,
+    ) -> Result<()> {
+        kyc_instructions::initialize_kyc_provider_config(
+            ctx,
+            active_providers,
+            default_provider,
+            requirements,
+        )
+    }
+
+    /// Start an enhanced KYC verification with an external provider
+    ///
+    /// The user's contact details are only recorded as a salted hash.
+    pub fn initiate_kyc_verification(
+        ctx: Context<InitiateKycVerification>,
+        provider: KycProvider,
+        contact_commitment: [u8; 32],
+    ) -> Result<()> {
+        kyc_instructions::initiate_kyc_verification(ctx, provider, contact_commitment)
+    }
+
+    /// Record a provider's verification result (KYC officer only)
+    pub fn process_kyc_result(
+        ctx: Context<ProcessKycResult>,
+        verification_id: String,
+        status: KycStatus,
+        risk_score: Option<u8>,
+        compliance_data: ComplianceFlags,
+        provider_metadata: Option<String>,
+    ) -> Result<()> {
+        kyc_instructions::process_kyc_result(
+            ctx,
+            verification_id,
+            status,
+            risk_score,
+            compliance_data,
+            provider_metadata,
+        )
+    }
+
+    /// Manually verify or reject an enhanced KYC account (KYC officer only)
+    pub fn manual_kyc_verification(
+        ctx: Context<ManualKycVerification>,
+        is_verified: bool,
+        risk_score: Option<u8>,
+        compliance_data: ComplianceFlags,
+        notes: Option<String>,
+    ) -> Result<()> {
+        kyc_instructions::manual_kyc_verification(
+            ctx,
+            is_verified,
+            risk_score,
+            compliance_data,
+            notes,
+        )
+    }
+
+    /// Suspend an enhanced KYC verification (KYC officer only)
+    pub fn suspend_kyc_verification(
+        ctx: Context<SuspendKycVerification>,
+        reason: String,
+    ) -> Result<()> {
+        kyc_instructions::suspend_kyc_verification(ctx, reason)
+    }
+
+    /// Restart an expired or failed enhanced KYC verification
+    pub fn refresh_kyc_verification(
+        ctx: Context<RefreshKycVerification>,
+        provider: Option<KycProvider>,
+    ) -> Result<()> {
+        kyc_instructions::refresh_kyc_verification(ctx, provider)
+    }
+
+    /// Update the enhanced KYC provider configuration (KYC officer only)
+    pub fn update_kyc_provider_config(
+        ctx: Context<UpdateKycProviderConfig>,
+        active_providers: Vec<KycProvider>,
+        default_provider: KycProvider,
+        requirements: KycRequirements,
+    ) -> Result<()> {
+        kyc_instructions::update_kyc_provider_config(
+            ctx,
+            active_providers,
+            default_provider,
+            requirements,
         )
     }
 }
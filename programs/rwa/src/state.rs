@@ -1,4 +1,16 @@
 use anchor_lang::prelude::*;
+use mpl_token_metadata::accounts::Metadata;
+use mpl_token_metadata::types::TokenStandard;
+use mpl_token_metadata::{MAX_CREATOR_LIMIT, MAX_URI_LENGTH};
+
+use crate::utils::{
+    calculate_dutch_auction_price, calculate_purchase_cost, calculate_remaining_sale_duration,
+};
+
+/// Program ID of the `kyc-transfer-hook` program in this workspace, attached to
+/// Token-2022 fractional mints to enforce KYC on every transfer
+pub const KYC_TRANSFER_HOOK_PROGRAM_ID: Pubkey =
+    pubkey!("8ouWSqJEg5JiLtPQXRhWD9F9Qjq2F8osKcvbDCshqpvG");
 
 /// Vault account that stores the original NFT and manages fractional token sales
 ///
@@ -18,13 +30,13 @@ pub struct Vault {
     /// The mint address of the fractional tokens created from the NFT
     pub fractional_token_mint: Pubkey,
 
-    /// Total number of fractional shares created
+    /// Total number of fractional shares created, in base units of the fractional mint
     pub total_fractions: u64,
 
-    /// Price per fractional share in lamports (or smallest unit of payment token)
+    /// Price per whole fractional share in lamports (or smallest unit of payment token)
     pub price_per_fraction: u64,
 
-    /// Number of fractional shares sold so far
+    /// Number of fractional shares sold so far, in base units of the fractional mint
     pub fractions_sold: u64,
 
     /// Whether the sale is currently active
@@ -38,6 +50,132 @@ pub struct Vault {
 
     /// Creator's payment account for receiving proceeds from sales
     pub creator_payment_account: Pubkey,
+
+    /// Mint of the token buyers must pay with (e.g. USDC)
+    pub payment_mint: Pubkey,
+
+    /// Whether the admin has halted this vault (emergency freeze)
+    pub is_halted: bool,
+
+    /// Token program owning the fractional mint (SPL Token or Token-2022)
+    pub fractional_token_program: Pubkey,
+
+    /// Royalty charged on secondary transfers through the Token-2022 transfer
+    /// fee extension (basis points, 0 = no royalty)
+    pub royalty_bps: u16,
+
+    /// Verified Metaplex collection the original NFT belongs to
+    pub collection_mint: Pubkey,
+
+    /// Metadata URI of the original NFT
+    pub metadata_uri: String,
+
+    /// Creators listed in the original NFT's metadata
+    pub creators: Vec<NftCreator>,
+
+    /// Whether the original NFT is a programmable NFT, moved through the
+    /// token metadata `Transfer` instead of a plain token transfer
+    pub is_programmable: bool,
+
+    /// Decimals of the fractional mint, so shares can be bought in parts
+    pub fractional_decimals: u8,
+
+    /// Optional: Timestamp before which the primary sale is closed
+    pub sale_starts_at: Option<i64>,
+
+    /// Optional: Timestamp after which the primary sale is closed
+    pub sale_ends_at: Option<i64>,
+
+    /// Optional: Soft cap in the payment mint's smallest unit; proceeds stay
+    /// in the vault's escrow until it is met
+    pub min_raise: Option<u64>,
+
+    /// Total paid by buyers of a soft-capped sale, net of refunds
+    pub amount_raised: u64,
+
+    /// Optional: Tiered schedule pricing the first fractions sold, in order;
+    /// fractions past the last tier sell at `price_per_fraction`
+    pub price_tiers: Vec<PriceTier>,
+
+    /// Optional: Dutch auction decaying to `price_per_fraction` as the floor
+    pub dutch_auction: Option<DutchAuction>,
+
+    /// Lowest Dutch auction price paid so far, the clearing price once the
+    /// auction closes (0 before the first purchase)
+    pub clearing_price: u64,
+
+    /// Escrowed proceeds already claimed by the creator, including the platform fee
+    pub proceeds_claimed: u64,
+}
+
+/// Creator of the original NFT, as listed in its Metaplex metadata
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Debug)]
+pub struct NftCreator {
+    /// Creator wallet
+    pub address: Pubkey,
+
+    /// Whether the creator signed the metadata
+    pub verified: bool,
+
+    /// Share of royalties in percent
+    pub share: u8,
+}
+
+/// Tier of a vault's pricing schedule, e.g. early-bird shares
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Debug)]
+pub struct PriceTier {
+    /// Fractions sold at this tier's price, in base units of the fractional mint
+    pub fractions: u64,
+
+    /// Price per whole fractional share within this tier
+    pub price_per_fraction: u64,
+}
+
+/// How a Dutch auction's price falls from its start price to the floor
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AuctionDecay {
+    /// Falls continuously over the auction's duration
+    Linear,
+
+    /// Falls in `steps` equal drops spread evenly over the auction's duration
+    Stepwise { steps: u16 },
+}
+
+/// Dutch auction terms of a vault's primary sale
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Debug)]
+pub struct DutchAuction {
+    /// Price per whole fraction when the auction opens
+    pub start_price: u64,
+
+    /// Seconds over which the price decays to the floor
+    pub duration: i64,
+
+    /// Shape of the price decay
+    pub decay: AuctionDecay,
+
+    /// Whether all buyers pay the final clearing price, holding payments in
+    /// escrow so early buyers can claim the difference once the auction closes
+    pub uniform_clearing_price: bool,
+}
+
+/// Optional terms of a vault's primary sale, chosen at fractionalization
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, PartialEq, Debug)]
+pub struct SaleTerms {
+    /// Timestamp the sale opens at
+    pub starts_at: Option<i64>,
+
+    /// Timestamp the sale closes at
+    pub ends_at: Option<i64>,
+
+    /// Soft cap in the payment mint's smallest unit; proceeds stay in the
+    /// vault's escrow until it is met
+    pub min_raise: Option<u64>,
+
+    /// Tiered pricing for the first fractions sold, before the base price
+    pub price_tiers: Vec<PriceTier>,
+
+    /// Dutch auction decaying to the base price as the floor
+    pub dutch_auction: Option<DutchAuction>,
 }
 
 impl Vault {
@@ -52,7 +190,51 @@ impl Vault {
         1 +  // is_sale_active
         8 +  // created_at
         9 +  // sale_ended_at (Option<i64>)
-        32; // creator_payment_account
+        32 + // creator_payment_account
+        32 + // payment_mint
+        1 +  // is_halted
+        32 + // fractional_token_program
+        2 +  // royalty_bps
+        32 + // collection_mint
+        4 + MAX_URI_LENGTH + // metadata_uri
+        4 + (32 + 1 + 1) * MAX_CREATOR_LIMIT + // creators
+        1 + // is_programmable
+        1 + // fractional_decimals
+        9 + // sale_starts_at (Option<i64>)
+        9 + // sale_ends_at (Option<i64>)
+        9 + // min_raise (Option<u64>)
+        8 + // amount_raised
+        4 + (8 + 8) * Self::MAX_PRICE_TIERS + // price_tiers
+        1 + 8 + 8 + 3 + 1 + // dutch_auction (Option<DutchAuction>)
+        8 + // clearing_price
+        8; // proceeds_claimed
+
+    /// Denominator for basis-point royalties
+    pub const ROYALTY_BPS_DENOMINATOR: u16 = 10_000;
+
+    /// Maximum fractional mint decimals, keeping a whole share within a u64
+    pub const MAX_FRACTIONAL_DECIMALS: u8 = 9;
+
+    /// Maximum number of tiers in a pricing schedule
+    pub const MAX_PRICE_TIERS: usize = 5;
+
+    /// Record the original NFT's collection, metadata URI, creators and token standard
+    pub fn record_original_nft(&mut self, metadata: &Metadata, collection_mint: Pubkey) {
+        self.collection_mint = collection_mint;
+        self.metadata_uri = metadata.uri.trim_end_matches('\0').to_string();
+        self.creators = metadata
+            .creators
+            .iter()
+            .flatten()
+            .map(|creator| NftCreator {
+                address: creator.address,
+                verified: creator.verified,
+                share: creator.share,
+            })
+            .collect();
+        self.is_programmable =
+            metadata.token_standard == Some(TokenStandard::ProgrammableNonFungible);
+    }
 
     /// Check if all fractions have been sold
     pub fn is_fully_sold(&self) -> bool {
@@ -64,62 +246,200 @@ impl Vault {
         self.total_fractions.saturating_sub(self.fractions_sold)
     }
 
+    /// Check if the sale window has opened at `now`
+    pub fn has_sale_started(&self, now: i64) -> bool {
+        self.sale_starts_at.is_none_or(|starts_at| now >= starts_at)
+    }
+
+    /// Check if the sale window has lapsed at `now`
+    pub fn has_sale_window_lapsed(&self, now: i64) -> bool {
+        calculate_remaining_sale_duration(self, now) == Some(0)
+    }
+
+    /// Validate the soft cap against the sale window and the vault's total value
+    pub fn validate_soft_cap(&self) -> Result<()> {
+        if let Some(min_raise) = self.min_raise {
+            require!(
+                self.sale_ends_at.is_some(),
+                crate::error::RwaError::SoftCapRequiresSaleEnd
+            );
+            require!(
+                min_raise > 0 && min_raise <= self.total_value()?,
+                crate::error::RwaError::InvalidSoftCap
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Validate the pricing schedule against the number of fractions for sale
+    pub fn validate_price_tiers(&self) -> Result<()> {
+        require!(
+            self.price_tiers.len() <= Self::MAX_PRICE_TIERS,
+            crate::error::RwaError::InvalidPriceTiers
+        );
+        let mut tiered_fractions: u64 = 0;
+        for tier in &self.price_tiers {
+            require!(
+                tier.fractions > 0 && tier.price_per_fraction > 0,
+                crate::error::RwaError::InvalidPriceTiers
+            );
+            tiered_fractions = tiered_fractions
+                .checked_add(tier.fractions)
+                .ok_or(crate::error::RwaError::MathOverflow)?;
+        }
+        require!(
+            tiered_fractions <= self.total_fractions,
+            crate::error::RwaError::InvalidPriceTiers
+        );
+
+        Ok(())
+    }
+
+    /// Validate the Dutch auction (if any) against the floor price and the
+    /// other sale terms
+    pub fn validate_dutch_auction(&self) -> Result<()> {
+        if let Some(auction) = &self.dutch_auction {
+            let steps_valid = match auction.decay {
+                AuctionDecay::Linear => true,
+                AuctionDecay::Stepwise { steps } => steps > 0,
+            };
+            require!(
+                auction.start_price > self.price_per_fraction
+                    && auction.duration > 0
+                    && steps_valid
+                    && self.price_tiers.is_empty()
+                    && self.min_raise.is_none(),
+                crate::error::RwaError::InvalidDutchAuction
+            );
+            require!(
+                !auction.uniform_clearing_price || self.sale_ends_at.is_some(),
+                crate::error::RwaError::ClearingPriceRequiresSaleEnd
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Check if payments are held in the vault's escrow rather than paid out
+    /// on purchase
+    pub fn uses_escrow(&self) -> bool {
+        self.min_raise.is_some() || self.has_uniform_clearing_price()
+    }
+
+    /// Check if the sale is a Dutch auction settling at a uniform clearing price
+    pub fn has_uniform_clearing_price(&self) -> bool {
+        self.dutch_auction
+            .as_ref()
+            .is_some_and(|auction| auction.uniform_clearing_price)
+    }
+
+    /// Dutch auction price per whole fraction at `now`, if the sale is an auction
+    ///
+    /// The auction starts with the sale window, or at fractionalization.
+    pub fn auction_price(&self, now: i64) -> Option<u64> {
+        self.dutch_auction.as_ref().map(|auction| {
+            calculate_dutch_auction_price(
+                auction,
+                self.price_per_fraction,
+                self.sale_starts_at.unwrap_or(self.created_at),
+                now,
+            )
+        })
+    }
+
+    /// Check if the soft cap (if any) has been met
+    pub fn is_soft_cap_met(&self) -> bool {
+        self.min_raise
+            .is_none_or(|min_raise| self.amount_raised >= min_raise)
+    }
+
+    /// Check if the sale ended below its soft cap, so buyers can be refunded
+    pub fn is_refunding(&self, now: i64) -> bool {
+        !self.is_soft_cap_met() && self.has_sale_window_lapsed(now)
+    }
+
+    /// Number of base units in one whole fractional share
+    pub fn base_units_per_share(&self) -> u64 {
+        10u64.pow(self.fractional_decimals as u32)
+    }
+
+    /// Calculate the price of the next `num_fractions` base units for sale at
+    /// `now`, across pricing tiers or at the Dutch auction price, rounded up
+    pub fn purchase_cost(&self, num_fractions: u64, now: i64) -> Result<u64> {
+        match self.auction_price(now) {
+            Some(price) => {
+                calculate_purchase_cost(0, num_fractions, &[], price, self.fractional_decimals)
+            }
+            None => self.cost_of_fractions(self.fractions_sold, num_fractions),
+        }
+    }
+
+    /// Calculate the price of `num_fractions` base units at the auction's
+    /// clearing price, rounded up
+    pub fn clearing_cost(&self, num_fractions: u64) -> Result<u64> {
+        calculate_purchase_cost(
+            0,
+            num_fractions,
+            &[],
+            self.clearing_price,
+            self.fractional_decimals,
+        )
+    }
+
+    /// Calculate the price of `num_fractions` base units starting at the
+    /// `first_fraction`-th fraction sold
+    fn cost_of_fractions(&self, first_fraction: u64, num_fractions: u64) -> Result<u64> {
+        calculate_purchase_cost(
+            first_fraction,
+            num_fractions,
+            &self.price_tiers,
+            self.price_per_fraction,
+            self.fractional_decimals,
+        )
+    }
+
     /// Calculate the total value of the vault based on current sales
     pub fn total_value(&self) -> Result<u64> {
-        self.total_fractions
-            .checked_mul(self.price_per_fraction)
-            .ok_or(crate::error::RwaError::MathOverflow.into())
+        self.cost_of_fractions(0, self.total_fractions)
     }
 
     /// Calculate the value of sold fractions
     pub fn sold_value(&self) -> Result<u64> {
-        self.fractions_sold
-            .checked_mul(self.price_per_fraction)
-            .ok_or(crate::error::RwaError::MathOverflow.into())
+        self.cost_of_fractions(0, self.fractions_sold)
     }
 }
 
-/// KYC (Know Your Customer) account for regulatory compliance
+/// Investor position tracking a wallet's cumulative purchases in a vault
 ///
-/// This account stores user verification status required for purchasing
-/// fractional tokens, ensuring compliance with financial regulations.
+/// Used to enforce per-wallet investment caps across multiple purchases
+/// rather than just per transaction.
 #[account]
-pub struct KycAccount {
-    /// The user's wallet address
-    pub user: Pubkey,
+pub struct InvestorPosition {
+    /// The investor's wallet address
+    pub owner: Pubkey,
 
-    /// Whether the user has been verified through KYC process
-    pub is_verified: bool,
+    /// The vault this position belongs to
+    pub vault: Pubkey,
 
-    /// Timestamp when KYC registration was initiated
-    pub registered_at: i64,
+    /// Total number of fractions bought from the primary sale, in base units
+    pub fractions_purchased: u64,
 
-    /// Timestamp when KYC verification was completed (if verified)
-    pub verified_at: Option<i64>,
+    /// Total amount paid (including platform fees) in the vault's payment mint
+    pub total_paid: u64,
 
-    /// Optional: Additional metadata for KYC process
-    pub metadata: Option<String>,
+    /// Timestamp of the most recent purchase
+    pub last_purchase_at: i64,
 }
 
-impl KycAccount {
-    /// Calculate the space required for the KycAccount
+impl InvestorPosition {
+    /// Calculate the space required for the InvestorPosition account
     pub const LEN: usize = 8 + // discriminator
-        32 + // user
-        1 +  // is_verified
-        8 +  // registered_at
-        9 +  // verified_at (Option<i64>)
-        4 +  // metadata length prefix
-        256; // metadata string (max 256 chars)
-
-    /// Check if the KYC account is ready for verification
-    pub fn is_pending_verification(&self) -> bool {
-        !self.is_verified && self.verified_at.is_none()
-    }
-
-    /// Check if the KYC account has been verified
-    pub fn is_verified(&self) -> bool {
-        self.is_verified && self.verified_at.is_some()
-    }
+        32 + // owner
+        32 + // vault
+        8 +  // fractions_purchased
+        8 +  // total_paid
+        8; // last_purchase_at
 }
 
 /// Platform configuration account for managing platform-wide settings
@@ -166,6 +486,47 @@ pub struct PlatformConfig {
 
     /// Created at timestamp
     pub created_at: i64,
+
+    /// Payment mints (stablecoins) vaults are allowed to sell fractions for
+    pub accepted_payment_mints: Vec<Pubkey>,
+
+    /// Admin proposed by the current admin, pending acceptance
+    pub pending_admin: Option<Pubkey>,
+
+    /// Delegated admin roles (the `admin` itself implicitly holds every role)
+    pub role_assignments: Vec<RoleAssignment>,
+
+    /// Seconds a KYC verification stays valid (0 = never expires)
+    pub kyc_validity_period: i64,
+
+    /// Maximum cumulative fractions per vault for KYC levels 1-3 (0 = unlimited)
+    pub kyc_level_limits: [u64; 3],
+
+    /// Verified Metaplex collections whose NFTs may be fractionalized
+    pub approved_collections: Vec<Pubkey>,
+}
+
+/// Administrative roles that can be delegated on the platform
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AdminRole {
+    /// Full control, held only by `PlatformConfig.admin`
+    SuperAdmin,
+    /// Can verify users' KYC
+    KycOfficer,
+    /// Can update fees, investment limits, treasury and payment mints
+    FeeManager,
+    /// Can pause the platform, halt vaults and freeze holder accounts
+    Pauser,
+}
+
+/// A role granted to a specific authority
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Debug)]
+pub struct RoleAssignment {
+    /// The authority holding the role
+    pub authority: Pubkey,
+
+    /// The role granted
+    pub role: AdminRole,
 }
 
 impl PlatformConfig {
@@ -183,7 +544,126 @@ impl PlatformConfig {
         8 +  // min_investment_amount
         8 +  // max_investment_amount
         8 +  // updated_at
-        8; // created_at
+        8 +  // created_at
+        4 +  // accepted_payment_mints length
+        32 * Self::MAX_ACCEPTED_PAYMENT_MINTS + // accepted_payment_mints
+        33 + // pending_admin (Option<Pubkey>)
+        4 +  // role_assignments length
+        (32 + 1) * Self::MAX_ROLE_ASSIGNMENTS + // role_assignments
+        8 +  // kyc_validity_period
+        8 * 3 + // kyc_level_limits
+        4 +  // approved_collections length
+        32 * Self::MAX_APPROVED_COLLECTIONS; // approved_collections
+
+    /// Maximum number of delegated role assignments
+    pub const MAX_ROLE_ASSIGNMENTS: usize = 16;
+
+    /// Check if an authority holds a role
+    ///
+    /// The super admin implicitly holds every role.
+    pub fn has_role(&self, authority: &Pubkey, role: AdminRole) -> bool {
+        if self.admin == *authority {
+            return true;
+        }
+
+        role != AdminRole::SuperAdmin
+            && self
+                .role_assignments
+                .iter()
+                .any(|assignment| assignment.authority == *authority && assignment.role == role)
+    }
+
+    /// Maximum number of payment mints on the allowlist
+    pub const MAX_ACCEPTED_PAYMENT_MINTS: usize = 8;
+
+    /// Maximum number of approved NFT collections
+    pub const MAX_APPROVED_COLLECTIONS: usize = 16;
+
+    /// Validate a purchase against the configured investment limits
+    ///
+    /// `num_fractions` must meet the per-transaction minimum and the buyer's
+    /// cumulative position (including this purchase) must not exceed the maximum.
+    /// Limits are in whole shares while purchases are in base units, with
+    /// `base_units_per_share` per share, so the minimum needs whole shares too.
+    pub fn validate_investment(
+        &self,
+        num_fractions: u64,
+        cumulative_fractions: u64,
+        base_units_per_share: u64,
+    ) -> Result<()> {
+        require!(
+            num_fractions
+                >= self
+                    .min_investment_amount
+                    .saturating_mul(base_units_per_share),
+            crate::error::RwaError::InvestmentBelowMinimum
+        );
+        require!(
+            cumulative_fractions
+                <= self
+                    .max_investment_amount
+                    .saturating_mul(base_units_per_share),
+            crate::error::RwaError::InvestmentAboveMaximum
+        );
+
+        Ok(())
+    }
+
+    /// Validate a buyer's cumulative position in a vault against the ceiling
+    /// for their KYC verification level (in whole shares of `base_units_per_share`)
+    pub fn validate_kyc_level_limit(
+        &self,
+        verification_level: u8,
+        cumulative_fractions: u64,
+        base_units_per_share: u64,
+    ) -> Result<()> {
+        let limit = self.kyc_level_limits[verification_level.clamp(1, 3) as usize - 1];
+
+        require!(
+            limit == 0 || cumulative_fractions <= limit.saturating_mul(base_units_per_share),
+            crate::error::RwaError::KycLevelLimitExceeded
+        );
+
+        Ok(())
+    }
+
+    /// Calculate the KYC expiry timestamp for a verification completed at `now`
+    pub fn kyc_expiry_from(&self, now: i64) -> Result<i64> {
+        if self.kyc_validity_period == 0 {
+            return Ok(0);
+        }
+
+        now.checked_add(self.kyc_validity_period)
+            .ok_or(crate::error::RwaError::MathOverflow.into())
+    }
+
+    /// Check if a payment mint is on the platform allowlist
+    pub fn accepts_payment_mint(&self, mint: &Pubkey) -> bool {
+        self.accepted_payment_mints.contains(mint)
+    }
+
+    /// Check if an NFT collection is approved for fractionalization
+    pub fn approves_collection(&self, collection_mint: &Pubkey) -> bool {
+        self.approved_collections.contains(collection_mint)
+    }
+
+    /// Validate that an NFT belongs to a verified, approved collection
+    ///
+    /// Returns the collection mint.
+    pub fn validate_nft_collection(&self, metadata: &Metadata) -> Result<Pubkey> {
+        let collection = metadata
+            .collection
+            .as_ref()
+            .filter(|collection| collection.verified)
+            .ok_or(crate::error::RwaError::NftCollectionNotVerified)?;
+
+        require!(
+            self.approves_collection(&collection.key),
+            crate::error::RwaError::NftCollectionNotApproved
+        );
+
+        Ok(collection.key)
+    }
 
     /// Validate that platform and creator fees sum to 100%
     pub fn validate_fee_structure(&self) -> bool {
@@ -198,6 +678,22 @@ impl PlatformConfig {
             .ok_or(crate::error::RwaError::MathOverflow.into())
     }
 
+    /// Calculate the platform fee taken from a fraction purchase
+    ///
+    /// Uses `platform_fee_numerator / platform_fee_denominator`, which is the
+    /// ratio the admin manages through `update_platform_config`.
+    pub fn calculate_purchase_fee(&self, total_amount: u64) -> Result<u64> {
+        require!(
+            self.platform_fee_denominator > 0,
+            crate::error::RwaError::InvalidPlatformFee
+        );
+
+        total_amount
+            .checked_mul(self.platform_fee_numerator as u64)
+            .and_then(|amount| amount.checked_div(self.platform_fee_denominator as u64))
+            .ok_or(crate::error::RwaError::MathOverflow.into())
+    }
+
     /// Calculate creator fee amount from total payment
     pub fn calculate_creator_fee(&self, total_amount: u64) -> Result<u64> {
         total_amount
@@ -1,240 +1,254 @@
-use anchor_lang::prelude::*;
-use anchor_spl::token::TokenAccount;
+//! Utility functions for the Fractional Art Investment Platform
 
-use crate::state::Vault;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::instruction::Instruction;
+use mpl_token_metadata::types::DataV2;
+use mpl_token_metadata::{MAX_NAME_LENGTH, MAX_SYMBOL_LENGTH, MAX_URI_LENGTH};
 
-/// Utility functions for the Fractional Art Investment Platform
+use crate::state::{AuctionDecay, DutchAuction, PriceTier, Vault};
 
-/// Calculate the royalty amount for a given transfer amount
+/// Calculate the total cost for purchasing fractional tokens
+///
+/// Each fraction is priced by the tier it falls in, counted from the first
+/// fraction sold, and fractions past the last tier at `price_per_fraction`.
+/// Rounds up once over the whole purchase, so buying a part of a share never
+/// costs less than its exact share of the price.
 ///
 /// # Arguments
-/// * `amount` - The transfer amount
-/// * `royalty_numerator` - The royalty numerator (e.g., 500 for 5%)
-/// * `royalty_denominator` - The royalty denominator (e.g., 10000 for 100%)
+/// * `first_fraction` - Fractions sold before this purchase, in base units
+/// * `num_fractions` - The number of fractions to purchase, in base units
+/// * `price_tiers` - Tiered prices of the first fractions sold
+/// * `price_per_fraction` - The price per whole fraction past the tiers
+/// * `fractional_decimals` - Decimals of the fractional mint
 ///
 /// # Returns
-/// * `Result<u64>` - The calculated royalty amount
-pub fn calculate_royalty_amount(
-    amount: u64,
-    royalty_numerator: u16,
-    royalty_denominator: u16,
+/// * `Result<u64>` - The total cost
+pub fn calculate_purchase_cost(
+    first_fraction: u64,
+    num_fractions: u64,
+    price_tiers: &[PriceTier],
+    price_per_fraction: u64,
+    fractional_decimals: u8,
 ) -> Result<u64> {
-    amount
-        .checked_mul(royalty_numerator as u64)
-        .and_then(|result| result.checked_div(royalty_denominator as u64))
-        .ok_or(crate::error::RwaError::MathOverflow.into())
+    let end = first_fraction
+        .checked_add(num_fractions)
+        .ok_or(crate::error::RwaError::MathOverflow)?;
+    let mut next = first_fraction;
+    let mut tier_start: u64 = 0;
+    let mut cost: u128 = 0;
+    for tier in price_tiers {
+        let tier_end = tier_start.saturating_add(tier.fractions);
+        if next < tier_end.min(end) {
+            let in_tier = tier_end.min(end) - next;
+            cost = cost
+                .checked_add(in_tier as u128 * tier.price_per_fraction as u128)
+                .ok_or(crate::error::RwaError::MathOverflow)?;
+            next += in_tier;
+        }
+        tier_start = tier_end;
+    }
+    let cost = cost
+        .checked_add((end - next) as u128 * price_per_fraction as u128)
+        .map(|value| value.div_ceil(10u128.pow(fractional_decimals as u32)))
+        .ok_or(crate::error::RwaError::MathOverflow)?;
+
+    u64::try_from(cost).map_err(|_| crate::error::RwaError::MathOverflow.into())
 }
 
-/// Calculate the net amount after deducting royalty
+/// Calculate a Dutch auction's price per whole fraction at `now`
+///
+/// The price falls from the start price to `floor_price` over the auction's
+/// duration, continuously or in equal steps, and stays at the floor after.
+/// Partial drops round down, so the price never falls early.
 ///
 /// # Arguments
-/// * `amount` - The original amount
-/// * `royalty_numerator` - The royalty numerator
-/// * `royalty_denominator` - The royalty denominator
+/// * `auction` - The auction terms
+/// * `floor_price` - The price per whole fraction the auction ends at
+/// * `started_at` - Timestamp the auction opened at
+/// * `now` - Current timestamp
 ///
 /// # Returns
-/// * `Result<u64>` - The net amount after royalty deduction
-pub fn calculate_net_amount(
-    amount: u64,
-    royalty_numerator: u16,
-    royalty_denominator: u16,
-) -> Result<u64> {
-    let royalty_amount = calculate_royalty_amount(amount, royalty_numerator, royalty_denominator)?;
-    amount
-        .checked_sub(royalty_amount)
-        .ok_or(crate::error::RwaError::MathOverflow.into())
+/// * `u64` - The price per whole fraction
+pub fn calculate_dutch_auction_price(
+    auction: &DutchAuction,
+    floor_price: u64,
+    started_at: i64,
+    now: i64,
+) -> u64 {
+    let elapsed = now.saturating_sub(started_at).clamp(0, auction.duration) as u128;
+    let duration = auction.duration as u128;
+    let total_drop = auction.start_price.saturating_sub(floor_price) as u128;
+
+    let drop = match auction.decay {
+        AuctionDecay::Linear => total_drop * elapsed / duration,
+        AuctionDecay::Stepwise { steps } => {
+            let steps = steps as u128;
+            total_drop * (elapsed * steps / duration) / steps
+        }
+    };
+
+    auction.start_price - drop as u64
 }
 
-/// Validate that a vault is ready for redemption
+/// Validate royalty fee parameters
 ///
 /// # Arguments
-/// * `vault` - The vault account
-/// * `redeemer_token_account` - The redeemer's fractional token account
+/// * `numerator` - Royalty numerator
+/// * `denominator` - Royalty denominator
 ///
 /// # Returns
 /// * `Result<()>` - Success if validation passes
-pub fn validate_redemption_eligibility(
-    vault: &Vault,
-    redeemer_token_account: &TokenAccount,
-) -> Result<()> {
+pub fn validate_royalty_fee(numerator: u16, denominator: u16) -> Result<()> {
+    require!(denominator > 0, crate::error::RwaError::InvalidRoyaltyFee);
     require!(
-        redeemer_token_account.amount == vault.total_fractions,
-        crate::error::RwaError::InsufficientTokens
+        numerator <= denominator,
+        crate::error::RwaError::InvalidRoyaltyFee
     );
 
-    require!(
-        vault.is_fully_sold(),
-        crate::error::RwaError::VaultNotReadyForRedemption
-    );
+    // Additional validation: royalty should not exceed 50% (5000 basis points)
+    require!(numerator <= 5000, crate::error::RwaError::InvalidRoyaltyFee);
 
     Ok(())
 }
 
-/// Calculate the percentage of vault ownership
+/// Prefix of fractional share token names, followed by the original NFT's name
+pub const FRACTIONAL_NAME_PREFIX: &str = "Frac: ";
+
+/// Build the Metaplex metadata of a fractional mint from the original NFT's
 ///
 /// # Arguments
-/// * `fractional_amount` - The amount of fractional tokens owned
-/// * `total_fractions` - The total number of fractional tokens
+/// * `name` - The original NFT's name
+/// * `symbol` - The original NFT's symbol
+/// * `uri` - The original NFT's metadata URI
 ///
 /// # Returns
-/// * `Result<f64>` - The ownership percentage
-pub fn calculate_ownership_percentage(fractional_amount: u64, total_fractions: u64) -> Result<f64> {
-    if total_fractions == 0 {
-        return Ok(0.0);
+/// * `DataV2` - `Frac: <name>` with the original symbol and URI, truncated to
+///   the Metaplex length limits
+pub fn fractional_token_metadata(name: &str, symbol: &str, uri: &str) -> DataV2 {
+    let name = format!("{}{}", FRACTIONAL_NAME_PREFIX, name.trim_end_matches('\0'));
+
+    DataV2 {
+        name: truncate_utf8(&name, MAX_NAME_LENGTH).to_string(),
+        symbol: truncate_utf8(symbol.trim_end_matches('\0'), MAX_SYMBOL_LENGTH).to_string(),
+        uri: truncate_utf8(uri.trim_end_matches('\0'), MAX_URI_LENGTH).to_string(),
+        seller_fee_basis_points: 0,
+        creators: None,
+        collection: None,
+        uses: None,
     }
-
-    let percentage = (fractional_amount as f64 / total_fractions as f64) * 100.0;
-    Ok(percentage)
 }
 
-/// Generate PDA seeds for vault-related accounts
-///
-/// # Arguments
-/// * `nft_mint` - The original NFT mint address
-///
-/// # Returns
-/// * `Vec<Vec<u8>>` - The PDA seeds
-pub fn generate_vault_seeds(nft_mint: &Pubkey) -> Vec<Vec<u8>> {
-    vec![b"vault".to_vec(), nft_mint.to_bytes().to_vec()]
-}
+/// Truncate a string to at most `max_len` bytes on a character boundary
+fn truncate_utf8(value: &str, max_len: usize) -> &str {
+    if value.len() <= max_len {
+        return value;
+    }
 
-/// Generate PDA seeds for fractional mint accounts
-///
-/// # Arguments
-/// * `nft_mint` - The original NFT mint address
-///
-/// # Returns
-/// * `Vec<Vec<u8>>` - The PDA seeds
-pub fn generate_fractional_mint_seeds(nft_mint: &Pubkey) -> Vec<Vec<u8>> {
-    vec![b"fractional_mint".to_vec(), nft_mint.to_bytes().to_vec()]
+    let mut end = max_len;
+    while !value.is_char_boundary(end) {
+        end -= 1;
+    }
+    &value[..end]
 }
 
-/// Generate PDA seeds for KYC accounts
+/// Extract the signer, signature and message from an Ed25519 program instruction
 ///
-/// # Arguments
-/// * `user` - The user's wallet address
-///
-/// # Returns
-/// * `Vec<Vec<u8>>` - The PDA seeds
-pub fn generate_kyc_seeds(user: &Pubkey) -> Vec<Vec<u8>> {
-    vec![b"kyc".to_vec(), user.to_bytes().to_vec()]
-}
-
-/// Validate that a vault sale is active and has available fractions
+/// Only single-signature instructions that carry their own data are accepted,
+/// so the offsets cannot point into another instruction.
 ///
 /// # Arguments
-/// * `vault` - The vault account
-/// * `requested_fractions` - The number of fractions requested
+/// * `ix` - An instruction loaded from the instructions sysvar
 ///
 /// # Returns
-/// * `Result<()>` - Success if validation passes
-pub fn validate_sale_availability(vault: &Vault, requested_fractions: u64) -> Result<()> {
-    require!(vault.is_sale_active, crate::error::RwaError::SaleNotActive);
-    require!(
-        requested_fractions > 0,
-        crate::error::RwaError::InvalidAmount
+/// * `Result<(Pubkey, [u8; 64], Vec<u8>)>` - (signer, signature, message)
+pub fn parse_ed25519_instruction(ix: &Instruction) -> Result<(Pubkey, [u8; 64], Vec<u8>)> {
+    const HEADER_LEN: usize = 2;
+    const OFFSETS_LEN: usize = 14;
+    const CURRENT_INSTRUCTION: u16 = u16::MAX;
+
+    require_keys_eq!(
+        ix.program_id,
+        ed25519_program::ID,
+        crate::error::RwaError::InvalidAttestationSignature
     );
+
+    let data = &ix.data;
     require!(
-        vault.fractions_sold + requested_fractions <= vault.total_fractions,
-        crate::error::RwaError::InsufficientFractions
+        data.len() >= HEADER_LEN + OFFSETS_LEN && data[0] == 1,
+        crate::error::RwaError::InvalidAttestationSignature
     );
 
-    Ok(())
-}
-
-/// Calculate the total cost for purchasing fractional tokens
-///
-/// # Arguments
-/// * `num_fractions` - The number of fractions to purchase
-/// * `price_per_fraction` - The price per fraction
-///
-/// # Returns
-/// * `Result<u64>` - The total cost
-pub fn calculate_purchase_cost(num_fractions: u64, price_per_fraction: u64) -> Result<u64> {
-    num_fractions
-        .checked_mul(price_per_fraction)
-        .ok_or(crate::error::RwaError::MathOverflow.into())
-}
-
-/// Calculate the distribution amounts for a payment
-///
-/// # Arguments
-/// * `total_amount` - The total payment amount
-/// * `creator_fee_bps` - Creator fee in basis points
-/// * `platform_fee_bps` - Platform fee in basis points
-///
-/// # Returns
-/// * `Result<(u64, u64)>` - (creator_amount, platform_amount)
-pub fn calculate_payment_distribution(
-    total_amount: u64,
-    creator_fee_bps: u16,
-    platform_fee_bps: u16,
-) -> Result<(u64, u64)> {
-    let creator_amount = total_amount
-        .checked_mul(creator_fee_bps as u64)
-        .and_then(|amount| amount.checked_div(10000))
-        .ok_or(crate::error::RwaError::MathOverflow)?;
+    let read_u16 = |index: usize| {
+        let start = HEADER_LEN + index * 2;
+        u16::from_le_bytes([data[start], data[start + 1]])
+    };
+    let signature_offset = read_u16(0) as usize;
+    let signature_ix = read_u16(1);
+    let public_key_offset = read_u16(2) as usize;
+    let public_key_ix = read_u16(3);
+    let message_offset = read_u16(4) as usize;
+    let message_size = read_u16(5) as usize;
+    let message_ix = read_u16(6);
 
-    let platform_amount = total_amount
-        .checked_sub(creator_amount)
-        .ok_or(crate::error::RwaError::MathOverflow)?;
+    require!(
+        signature_ix == CURRENT_INSTRUCTION
+            && public_key_ix == CURRENT_INSTRUCTION
+            && message_ix == CURRENT_INSTRUCTION,
+        crate::error::RwaError::InvalidAttestationSignature
+    );
 
-    Ok((creator_amount, platform_amount))
+    let signature: [u8; 64] = data
+        .get(signature_offset..signature_offset + 64)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or(crate::error::RwaError::InvalidAttestationSignature)?;
+    let public_key: [u8; 32] = data
+        .get(public_key_offset..public_key_offset + 32)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or(crate::error::RwaError::InvalidAttestationSignature)?;
+    let message = data
+        .get(message_offset..message_offset + message_size)
+        .ok_or(crate::error::RwaError::InvalidAttestationSignature)?
+        .to_vec();
+
+    Ok((Pubkey::new_from_array(public_key), signature, message))
 }
 
-/// Format a timestamp for display
+/// Calculate the remaining sale duration (if applicable)
 ///
 /// # Arguments
-/// * `timestamp` - Unix timestamp
+/// * `vault` - The vault account
+/// * `now` - Current Unix timestamp
 ///
 /// # Returns
-/// * `String` - Formatted timestamp string
-pub fn format_timestamp(timestamp: i64) -> String {
-    // Simple formatting - in production, you might want to use a proper date library
-    format!("{}", timestamp)
+/// * `Option<i64>` - Remaining seconds until sale ends (if time-limited)
+pub fn calculate_remaining_sale_duration(vault: &Vault, now: i64) -> Option<i64> {
+    vault
+        .sale_ends_at
+        .map(|ends_at| ends_at.saturating_sub(now).max(0))
 }
 
-/// Validate royalty fee parameters
+/// Validate an optional primary sale window at fractionalization
 ///
 /// # Arguments
-/// * `numerator` - Royalty numerator
-/// * `denominator` - Royalty denominator
+/// * `sale_starts_at` - Optional timestamp the sale opens at
+/// * `sale_ends_at` - Optional timestamp the sale closes at
+/// * `now` - Current Unix timestamp
 ///
 /// # Returns
-/// * `Result<()>` - Success if validation passes
-pub fn validate_royalty_fee(numerator: u16, denominator: u16) -> Result<()> {
-    require!(denominator > 0, crate::error::RwaError::InvalidRoyaltyFee);
-    require!(
-        numerator <= denominator,
-        crate::error::RwaError::InvalidRoyaltyFee
-    );
-
-    // Additional validation: royalty should not exceed 50% (5000 basis points)
-    require!(numerator <= 5000, crate::error::RwaError::InvalidRoyaltyFee);
+/// * `Result<()>` - Success if the window ends in the future, after it starts
+pub fn validate_sale_window(
+    sale_starts_at: Option<i64>,
+    sale_ends_at: Option<i64>,
+    now: i64,
+) -> Result<()> {
+    if let Some(ends_at) = sale_ends_at {
+        require!(ends_at > now, crate::error::RwaError::InvalidSaleWindow);
+        require!(
+            sale_starts_at.is_none_or(|starts_at| starts_at < ends_at),
+            crate::error::RwaError::InvalidSaleWindow
+        );
+    }
 
     Ok(())
 }
-
-/// Check if a vault is eligible for redemption
-///
-/// # Arguments
-/// * `vault` - The vault account
-///
-/// # Returns
-/// * `bool` - True if eligible for redemption
-pub fn is_vault_redemption_eligible(vault: &Vault) -> bool {
-    vault.is_fully_sold() && vault.is_sale_active
-}
-
-// /// Calculate the remaining sale duration (if applicable)
-// ///
-// /// # Arguments
-// /// * `vault` - The vault account
-// ///
-// /// # Returns
-// /// * `Option<i64>` - Remaining seconds until sale ends (if time-limited)
-// pub fn calculate_remaining_sale_duration(vault: &Vault) -> Option<i64> {
-//     // This is a placeholder for future time-limited sales
-//     // Currently, sales don't have time limits
-//     None
-// }
@@ -1,26 +1,295 @@
 import * as anchor from '@coral-xyz/anchor'
 import { Program } from '@coral-xyz/anchor'
 import { Rwa } from '../target/types/rwa'
-import { PublicKey, Keypair } from '@solana/web3.js'
+import { KycTransferHook } from '../target/types/kyc_transfer_hook'
+import {
+  PublicKey,
+  Keypair,
+  Ed25519Program,
+  SYSVAR_INSTRUCTIONS_PUBKEY,
+  Transaction,
+  TransactionInstruction,
+  SystemProgram,
+  SYSVAR_RENT_PUBKEY,
+  sendAndConfirmTransaction
+} from '@solana/web3.js'
 import {
   createMint,
   createAccount,
   mintTo,
   getAccount,
-  getAssociatedTokenAddress
+  getMint,
+  getTokenMetadata,
+  getAssociatedTokenAddress,
+  getAssociatedTokenAddressSync,
+  createTransferCheckedWithTransferHookInstruction,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID
 } from '@solana/spl-token'
 import { expect } from 'chai'
+import { createHash } from 'crypto'
 
 describe('rwa', () => {
   anchor.setProvider(anchor.AnchorProvider.env())
 
   const program = anchor.workspace.rwa as Program<Rwa>
+  const transferHook = anchor.workspace
+    .kycTransferHook as Program<KycTransferHook>
   const provider = anchor.getProvider()
 
+  const TOKEN_METADATA_PROGRAM_ID = new PublicKey(
+    'metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s'
+  )
+
+  const metadataPda = (mint: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [
+        Buffer.from('metadata'),
+        TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+        mint.toBuffer()
+      ],
+      TOKEN_METADATA_PROGRAM_ID
+    )[0]
+
+  const masterEditionPda = (mint: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [
+        Buffer.from('metadata'),
+        TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+        mint.toBuffer(),
+        Buffer.from('edition')
+      ],
+      TOKEN_METADATA_PROGRAM_ID
+    )[0]
+
+  const borshString = (value: string) => {
+    const bytes = Buffer.from(value)
+    const length = Buffer.alloc(4)
+    length.writeUInt32LE(bytes.length)
+    return Buffer.concat([length, bytes])
+  }
+  const account = (
+    pubkey: PublicKey,
+    isSigner = false,
+    isWritable = false
+  ) => ({ pubkey, isSigner, isWritable })
+
+  // Sale terms of a fixed-price sale with no window, soft cap or tiers
+  const openSale = {
+    startsAt: null,
+    endsAt: null,
+    minRaise: null,
+    priceTiers: [],
+    dutchAuction: null
+  }
+
+  // Metaplex master edition NFT minted to the creator, verified into
+  // `collection` (pass null to create a collection NFT)
+  const createNft = async (
+    name: string,
+    collection: PublicKey | null = collectionMint
+  ): Promise<[PublicKey, PublicKey]> => {
+    const mint = await createMint(
+      provider.connection,
+      creator,
+      creator.publicKey,
+      creator.publicKey,
+      0
+    )
+    const tokenAccount = await createAccount(
+      provider.connection,
+      creator,
+      mint,
+      creator.publicKey
+    )
+    await mintTo(provider.connection, creator, mint, tokenAccount, creator, 1)
+
+    const tx = new Transaction().add(
+      new TransactionInstruction({
+        programId: TOKEN_METADATA_PROGRAM_ID,
+        keys: [
+          account(metadataPda(mint), false, true),
+          account(mint),
+          account(creator.publicKey, true),
+          account(creator.publicKey, true, true),
+          account(creator.publicKey, true),
+          account(SystemProgram.programId),
+          account(SYSVAR_RENT_PUBKEY)
+        ],
+        data: Buffer.concat([
+          Buffer.from([33]), // CreateMetadataAccountV3
+          borshString(name),
+          borshString('ART'),
+          borshString(`https://example.com/${encodeURIComponent(name)}.json`),
+          Buffer.from([0xf4, 0x01]), // 500 bps seller fee
+          Buffer.from([1, 1, 0, 0, 0]), // one creator
+          creator.publicKey.toBuffer(),
+          Buffer.from([1, 100]), // verified, 100% share
+          collection
+            ? Buffer.concat([Buffer.from([1, 0]), collection.toBuffer()])
+            : Buffer.from([0]),
+          Buffer.from([0, 1, 0]) // no uses, mutable, no collection details
+        ])
+      }),
+      new TransactionInstruction({
+        programId: TOKEN_METADATA_PROGRAM_ID,
+        keys: [
+          account(masterEditionPda(mint), false, true),
+          account(mint, false, true),
+          account(creator.publicKey, true),
+          account(creator.publicKey, true),
+          account(creator.publicKey, true, true),
+          account(metadataPda(mint), false, true),
+          account(TOKEN_PROGRAM_ID),
+          account(SystemProgram.programId),
+          account(SYSVAR_RENT_PUBKEY)
+        ],
+        // CreateMasterEditionV3 with a max supply of 0
+        data: Buffer.from([17, 1, 0, 0, 0, 0, 0, 0, 0, 0])
+      })
+    )
+    if (collection) {
+      tx.add(
+        new TransactionInstruction({
+          programId: TOKEN_METADATA_PROGRAM_ID,
+          keys: [
+            account(metadataPda(mint), false, true),
+            account(creator.publicKey, true, true),
+            account(creator.publicKey, true, true),
+            account(collection),
+            account(metadataPda(collection)),
+            account(masterEditionPda(collection))
+          ],
+          data: Buffer.from([18]) // VerifyCollection
+        })
+      )
+    }
+    await sendAndConfirmTransaction(provider.connection, tx, [creator])
+
+    return [mint, tokenAccount]
+  }
+
+  const tokenRecordPda = (mint: PublicKey, tokenAccount: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [
+        Buffer.from('metadata'),
+        TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+        mint.toBuffer(),
+        Buffer.from('token_record'),
+        tokenAccount.toBuffer()
+      ],
+      TOKEN_METADATA_PROGRAM_ID
+    )[0]
+
+  // Programmable NFT minted to the creator's associated token account and
+  // verified into the approved collection, without a rule set
+  const createPnft = async (name: string): Promise<[PublicKey, PublicKey]> => {
+    const mint = Keypair.generate()
+    const tokenAccount = getAssociatedTokenAddressSync(
+      mint.publicKey,
+      creator.publicKey
+    )
+    const metadata = metadataPda(mint.publicKey)
+    const masterEdition = masterEditionPda(mint.publicKey)
+
+    const tx = new Transaction().add(
+      new TransactionInstruction({
+        programId: TOKEN_METADATA_PROGRAM_ID,
+        keys: [
+          account(metadata, false, true),
+          account(masterEdition, false, true),
+          account(mint.publicKey, true, true),
+          account(creator.publicKey, true),
+          account(creator.publicKey, true, true),
+          account(creator.publicKey, true),
+          account(SystemProgram.programId),
+          account(SYSVAR_INSTRUCTIONS_PUBKEY),
+          account(TOKEN_PROGRAM_ID)
+        ],
+        data: Buffer.concat([
+          Buffer.from([42, 0]), // CreateV1
+          borshString(name),
+          borshString('ART'),
+          borshString(`https://example.com/${encodeURIComponent(name)}.json`),
+          Buffer.from([0xf4, 0x01]), // 500 bps seller fee
+          Buffer.from([1, 1, 0, 0, 0]), // one creator
+          creator.publicKey.toBuffer(),
+          Buffer.from([1, 100]), // verified, 100% share
+          Buffer.from([0, 1, 4]), // primary sale pending, mutable, pNFT
+          Buffer.from([1, 0]), // unverified collection
+          collectionMint.toBuffer(),
+          Buffer.from([0, 0, 0]), // no uses, collection details or rule set
+          Buffer.from([0, 1, 0]) // default decimals, print supply of zero
+        ])
+      }),
+      new TransactionInstruction({
+        programId: TOKEN_METADATA_PROGRAM_ID,
+        keys: [
+          account(tokenAccount, false, true),
+          account(creator.publicKey),
+          account(metadata),
+          account(masterEdition, false, true),
+          account(tokenRecordPda(mint.publicKey, tokenAccount), false, true),
+          account(mint.publicKey, false, true),
+          account(creator.publicKey, true),
+          account(TOKEN_METADATA_PROGRAM_ID),
+          account(creator.publicKey, true, true),
+          account(SystemProgram.programId),
+          account(SYSVAR_INSTRUCTIONS_PUBKEY),
+          account(TOKEN_PROGRAM_ID),
+          account(ASSOCIATED_TOKEN_PROGRAM_ID),
+          account(TOKEN_METADATA_PROGRAM_ID),
+          account(TOKEN_METADATA_PROGRAM_ID)
+        ],
+        // MintV1 of a single token, without authorization data
+        data: Buffer.from([43, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0])
+      }),
+      new TransactionInstruction({
+        programId: TOKEN_METADATA_PROGRAM_ID,
+        keys: [
+          account(creator.publicKey, true),
+          account(TOKEN_METADATA_PROGRAM_ID),
+          account(metadata, false, true),
+          account(collectionMint),
+          account(metadataPda(collectionMint), false, true),
+          account(masterEditionPda(collectionMint)),
+          account(SystemProgram.programId),
+          account(SYSVAR_INSTRUCTIONS_PUBKEY)
+        ],
+        data: Buffer.from([52, 1]) // Verify a collection item
+      })
+    )
+    await sendAndConfirmTransaction(provider.connection, tx, [creator, mint])
+
+    return [mint.publicKey, tokenAccount]
+  }
+
+  // Only a salted commitment to each user's off-chain KYC dossier is stored
+  const kycDossier = (user: PublicKey) => {
+    const dossierHash = createHash('sha256')
+      .update(`dossier:${user.toString()}`)
+      .digest()
+    const salt = createHash('sha256').update(`salt:${user.toString()}`).digest()
+    const commitment = createHash('sha256')
+      .update(Buffer.concat([dossierHash, salt]))
+      .digest()
+    return { dossierHash, salt, commitment }
+  }
+
+  // Verifier's Ed25519 signature over `user || commitment`, checked by the
+  // program from the instruction before `verify_kyc`
+  const kycAttestation = (user: PublicKey, verifier: Keypair) =>
+    Ed25519Program.createInstructionWithPrivateKey({
+      privateKey: verifier.secretKey,
+      message: Buffer.concat([user.toBuffer(), kycDossier(user).commitment])
+    })
+
   // Test accounts
   let creator: Keypair
   let buyer: Keypair
   let admin: Keypair
+  let collectionMint: PublicKey // Metaplex collection approved by the platform
   let originalNftMint: PublicKey
   let paymentMint: PublicKey // USDC mint for payments
   let creatorNftTokenAccount: PublicKey
@@ -51,14 +320,11 @@ describe('rwa', () => {
     // Wait for airdrops to confirm
     await new Promise((resolve) => setTimeout(resolve, 1000))
 
-    // Create NFT mint (representing the artwork)
-    originalNftMint = await createMint(
-      provider.connection,
-      creator,
-      creator.publicKey,
-      null,
-      0 // NFTs have 0 decimals
-    )
+    // Create the collection and the NFT representing the artwork
+    collectionMint = (await createNft('Impressionists', null))[0]
+    const originalNft = await createNft('Sunflowers')
+    originalNftMint = originalNft[0]
+    creatorNftTokenAccount = originalNft[1]
 
     // Create payment mint (USDC)
     paymentMint = await createMint(
@@ -70,13 +336,6 @@ describe('rwa', () => {
     )
 
     // Create token accounts
-    creatorNftTokenAccount = await createAccount(
-      provider.connection,
-      creator,
-      originalNftMint,
-      creator.publicKey
-    )
-
     buyerPaymentAccount = await createAccount(
       provider.connection,
       buyer,
@@ -109,16 +368,6 @@ describe('rwa', () => {
     console.log('ORIGINAL NFT MINT')
     console.log(`originalNftMint: ${originalNftMint.toString()}`)
 
-    // Mint NFT to creator
-    await mintTo(
-      provider.connection,
-      creator,
-      originalNftMint,
-      creatorNftTokenAccount,
-      creator,
-      1 // Mint 1 NFT
-    )
-
     // Mint USDC to buyer for payments
     await mintTo(
       provider.connection,
@@ -137,7 +386,7 @@ describe('rwa', () => {
     )
 
     await program.methods
-      .initialize()
+      .initialize(admin.publicKey, 0)
       .accounts({
         admin: admin.publicKey
       })
@@ -149,6 +398,49 @@ describe('rwa', () => {
     )
     expect(platformConfig.admin.toString()).to.equal(admin.publicKey.toString())
     expect(platformConfig.isActive).to.be.true
+    expect(platformConfig.treasury.toString()).to.equal(
+      admin.publicKey.toString()
+    )
+    expect(platformConfig.defaultRoyaltyBps).to.equal(0)
+
+    await program.methods
+      .initializeJurisdictionConfig({ blocklist: {} })
+      .accounts({
+        admin: admin.publicKey
+      })
+      .signers([admin])
+      .rpc()
+
+    await program.methods
+      .addApprovedCollection(collectionMint)
+      .accounts({
+        admin: admin.publicKey
+      })
+      .signers([admin])
+      .rpc()
+  })
+
+  it('Accepts a payment mint', async () => {
+    const [platformConfigPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('platform_config')],
+      program.programId
+    )
+
+    await program.methods
+      .addPaymentMint(paymentMint)
+      .accounts({
+        admin: admin.publicKey,
+        platformConfig: platformConfigPda
+      })
+      .signers([admin])
+      .rpc()
+
+    const platformConfig = await program.account.platformConfig.fetch(
+      platformConfigPda
+    )
+    expect(
+      platformConfig.acceptedPaymentMints.map((mint) => mint.toString())
+    ).to.include(paymentMint.toString())
   })
 
   it('Registers KYC for users', async () => {
@@ -164,7 +456,7 @@ describe('rwa', () => {
 
     // Register creator KYC
     await program.methods
-      .registerKyc()
+      .registerKyc('US')
       .accounts({
         user: creator.publicKey
       })
@@ -173,7 +465,7 @@ describe('rwa', () => {
 
     // Register buyer KYC
     await program.methods
-      .registerKyc()
+      .registerKyc('US')
       .accounts({
         user: buyer.publicKey
       })
@@ -187,8 +479,9 @@ describe('rwa', () => {
 
     expect(creatorKyc.user.toString()).to.equal(creator.publicKey.toString())
     expect(buyerKyc.user.toString()).to.equal(buyer.publicKey.toString())
-    expect(creatorKyc.isVerified).to.be.false
-    expect(buyerKyc.isVerified).to.be.false
+    expect(creatorKyc.status).to.deep.equal({ pending: {} })
+    expect(buyerKyc.status).to.deep.equal({ pending: {} })
+    expect(Buffer.from(buyerKyc.country).toString()).to.equal('US')
   })
 
   it('Verifies KYC for users', async () => {
@@ -209,23 +502,33 @@ describe('rwa', () => {
 
     // Verify creator KYC
     await program.methods
-      .verifyKyc({ adminApproval: {} }, 2)
+      .verifyKyc(
+        { adminApproval: {} },
+        2,
+        [...kycDossier(creator.publicKey).commitment]
+      )
       .accounts({
         admin: admin.publicKey,
         kycAccount: creatorKycPda,
         platformConfig: platformConfigPda
       })
+      .preInstructions([kycAttestation(creator.publicKey, admin)])
       .signers([admin])
       .rpc()
 
     // Verify buyer KYC
     await program.methods
-      .verifyKyc({ emailVerification: {} }, 1)
+      .verifyKyc(
+        { emailVerification: {} },
+        1,
+        [...kycDossier(buyer.publicKey).commitment]
+      )
       .accounts({
         admin: admin.publicKey,
         kycAccount: buyerKycPda,
         platformConfig: platformConfigPda
       })
+      .preInstructions([kycAttestation(buyer.publicKey, admin)])
       .signers([admin])
       .rpc()
 
@@ -234,8 +537,8 @@ describe('rwa', () => {
     )
     const buyerKyc = await program.account.simpleKycAccount.fetch(buyerKycPda)
 
-    expect(creatorKyc.isVerified).to.be.true
-    expect(buyerKyc.isVerified).to.be.true
+    expect(creatorKyc.status).to.deep.equal({ verified: {} })
+    expect(buyerKyc.status).to.deep.equal({ verified: {} })
     expect(creatorKyc.verificationLevel).to.equal(2)
     expect(buyerKyc.verificationLevel).to.equal(1)
   })
@@ -265,12 +568,13 @@ describe('rwa', () => {
     const pricePerFraction = new anchor.BN(50) // 50 USDC per fraction
 
     await program.methods
-      .fractionalize(totalFractions, pricePerFraction)
+      .fractionalize(totalFractions, pricePerFraction, 0, openSale)
       .accounts({
         creator: creator.publicKey,
         originalNftMint: originalNftMint,
         fractionalTokenMint: fractionalTokenMint.publicKey,
         creatorNftAccount: creatorNftTokenAccount,
+        paymentMint: paymentMint,
         creatorPaymentAccount: creatorPaymentAccount
       })
       .signers([creator, fractionalTokenMint])
@@ -287,6 +591,7 @@ describe('rwa', () => {
     expect(vault.creatorPaymentAccount.toString()).to.equal(
       creatorPaymentAccount.toString()
     )
+    expect(vault.paymentMint.toString()).to.equal(paymentMint.toString())
 
     // Check that NFT was transferred to vault
     const vaultNftAccountInfo = await getAccount(
@@ -341,7 +646,7 @@ describe('rwa', () => {
           [Buffer.from('simple_kyc'), buyer.publicKey.toBuffer()],
           program.programId
         )[0],
-        platformAuthority: admin.publicKey
+        treasuryPaymentAccount: platformPaymentAccount
       })
       .signers([buyer])
       .rpc()
@@ -370,6 +675,10 @@ describe('rwa', () => {
       provider.connection,
       creatorPaymentAccount
     )
+    const platformPaymentAccountInfo = await getAccount(
+      provider.connection,
+      platformPaymentAccount
+    )
 
     // Buyer should have less USDC
     expect(parseInt(buyerPaymentAccountInfo.amount.toString())).to.be.lessThan(
@@ -380,6 +689,15 @@ describe('rwa', () => {
     expect(
       parseInt(creatorPaymentAccountInfo.amount.toString())
     ).to.be.greaterThan(0)
+
+    // Treasury should have received the platform fee (5% by default)
+    const platformFee = totalCost.muln(5).divn(100)
+    expect(platformPaymentAccountInfo.amount.toString()).to.equal(
+      platformFee.toString()
+    )
+    expect(creatorPaymentAccountInfo.amount.toString()).to.equal(
+      totalCost.sub(platformFee).toString()
+    )
   })
 
   it('Redeems NFT by burning all fractional tokens', async () => {
@@ -425,7 +743,7 @@ describe('rwa', () => {
             [Buffer.from('simple_kyc'), buyer.publicKey.toBuffer()],
             program.programId
           )[0],
-          platformAuthority: admin.publicKey // Assuming admin is the authority
+          treasuryPaymentAccount: platformPaymentAccount
         })
         .signers([buyer])
         .rpc()
@@ -443,6 +761,52 @@ describe('rwa', () => {
       buyer.publicKey
     )
 
+    // Burning the full supply of a look-alike mint does not release the NFT
+    const { totalFractions } = await program.account.vault.fetch(vaultPda)
+    const fakeMint = await createMint(
+      provider.connection,
+      buyer,
+      buyer.publicKey,
+      null,
+      0
+    )
+    const fakeAccount = await createAccount(
+      provider.connection,
+      buyer,
+      fakeMint,
+      buyer.publicKey
+    )
+    await mintTo(
+      provider.connection,
+      buyer,
+      fakeMint,
+      fakeAccount,
+      buyer,
+      BigInt(totalFractions.toString())
+    )
+    try {
+      await program.methods
+        .redeem()
+        .accounts({
+          redeemer: buyer.publicKey,
+          vault: vaultPda,
+          originalNftMint: originalNftMint,
+          fractionalTokenMint: fakeMint,
+          redeemerFractionalAccount: fakeAccount,
+          vaultFractionalAccount: vaultFractionalAccount,
+          vaultNftAccount: vaultNftAccount,
+          kycAccount: PublicKey.findProgramAddressSync(
+            [Buffer.from('simple_kyc'), buyer.publicKey.toBuffer()],
+            program.programId
+          )[0]
+        })
+        .signers([buyer])
+        .rpc()
+      expect.fail('Expected a foreign fractional mint to be refused')
+    } catch (error) {
+      expect(error.message).to.include('InvalidFractionalMint')
+    }
+
     await program.methods
       .redeem()
       .accounts({
@@ -476,29 +840,7 @@ describe('rwa', () => {
   })
 
   it('Requires KYC verification to buy fractions', async () => {
-    const newNftMint = await createMint(
-      provider.connection,
-      creator,
-      creator.publicKey,
-      null,
-      0
-    )
-
-    const newCreatorNftAccount = await createAccount(
-      provider.connection,
-      creator,
-      newNftMint,
-      creator.publicKey
-    )
-
-    await mintTo(
-      provider.connection,
-      creator,
-      newNftMint,
-      newCreatorNftAccount,
-      creator,
-      1
-    )
+    const [newNftMint, newCreatorNftAccount] = await createNft('Starry Night')
 
     const [newVaultPda] = PublicKey.findProgramAddressSync(
       [Buffer.from('vault'), newNftMint.toBuffer()],
@@ -521,12 +863,13 @@ describe('rwa', () => {
 
     // Fractionalize the NFT
     await program.methods
-      .fractionalize(new anchor.BN(1000), new anchor.BN(100))
+      .fractionalize(new anchor.BN(1000), new anchor.BN(100), 0, openSale)
       .accounts({
         creator: creator.publicKey,
         originalNftMint: newNftMint,
         fractionalTokenMint: newFractionalTokenMint.publicKey,
         creatorNftAccount: newCreatorNftAccount,
+        paymentMint: paymentMint,
         creatorPaymentAccount: creatorPaymentAccount
       })
       .signers([creator, newFractionalTokenMint])
@@ -564,7 +907,7 @@ describe('rwa', () => {
     )
 
     await program.methods
-      .registerKyc()
+      .registerKyc('US')
       .accounts({
         user: unverifiedBuyer.publicKey
       })
@@ -585,7 +928,7 @@ describe('rwa', () => {
             [Buffer.from('simple_kyc'), unverifiedBuyer.publicKey.toBuffer()],
             program.programId
           )[0],
-          platformAuthority: admin.publicKey
+          treasuryPaymentAccount: platformPaymentAccount
         })
         .signers([unverifiedBuyer])
         .rpc()
@@ -597,4 +940,2957 @@ describe('rwa', () => {
       expect(error.message).to.include('KycNotVerified')
     }
   })
+
+  it('Rejects payment in a mint the vault does not accept', async () => {
+    const [vaultPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('vault'), originalNftMint.toBuffer()],
+      program.programId
+    )
+
+    const vault = await program.account.vault.fetch(vaultPda)
+
+    // A token the buyer minted themselves
+    const fakeMint = await createMint(
+      provider.connection,
+      buyer,
+      buyer.publicKey,
+      null,
+      6
+    )
+    const fakePaymentAccount = await createAccount(
+      provider.connection,
+      buyer,
+      fakeMint,
+      buyer.publicKey
+    )
+    await mintTo(
+      provider.connection,
+      buyer,
+      fakeMint,
+      fakePaymentAccount,
+      buyer,
+      1000000 * Math.pow(10, 6)
+    )
+
+    try {
+      await program.methods
+        .buyFractions(new anchor.BN(1))
+        .accounts({
+          buyer: buyer.publicKey,
+          vault: vaultPda,
+          fractionalTokenMint: vault.fractionalTokenMint,
+          vaultFractionalAccount: await getAssociatedTokenAddress(
+            vault.fractionalTokenMint,
+            vaultPda,
+            true
+          ),
+          buyerPaymentAccount: fakePaymentAccount,
+          creatorPaymentAccount: creatorPaymentAccount,
+          kycAccount: PublicKey.findProgramAddressSync(
+            [Buffer.from('simple_kyc'), buyer.publicKey.toBuffer()],
+            program.programId
+          )[0],
+          treasuryPaymentAccount: platformPaymentAccount
+        })
+        .signers([buyer])
+        .rpc()
+
+      expect.fail('Expected transaction to fail due to wrong payment mint')
+    } catch (error) {
+      expect(error.message).to.include('InvalidPaymentMint')
+    }
+  })
+
+  it('Enforces investment limits across purchases', async () => {
+    const [platformConfigPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('platform_config')],
+      program.programId
+    )
+
+    const [nftMint, creatorNftAccount] = await createNft('Water Lilies')
+
+    const [vaultPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('vault'), nftMint.toBuffer()],
+      program.programId
+    )
+    const fractionalTokenMint = Keypair.generate()
+
+    await program.methods
+      .fractionalize(new anchor.BN(1000), new anchor.BN(100), 0, openSale)
+      .accounts({
+        creator: creator.publicKey,
+        originalNftMint: nftMint,
+        fractionalTokenMint: fractionalTokenMint.publicKey,
+        creatorNftAccount: creatorNftAccount,
+        paymentMint: paymentMint,
+        creatorPaymentAccount: creatorPaymentAccount
+      })
+      .signers([creator, fractionalTokenMint])
+      .rpc()
+
+    // Tighten limits: at least 10 fractions per purchase, at most 50 per wallet
+    await program.methods
+      .updatePlatformConfig(
+        5,
+        100,
+        new anchor.BN(10),
+        new anchor.BN(50),
+        admin.publicKey,
+        0
+      )
+      .accounts({
+        admin: admin.publicKey,
+        platformConfig: platformConfigPda
+      })
+      .signers([admin])
+      .rpc()
+
+    const buy = (numFractions: number) =>
+      program.methods
+        .buyFractions(new anchor.BN(numFractions))
+        .accounts({
+          buyer: buyer.publicKey,
+          vault: vaultPda,
+          fractionalTokenMint: fractionalTokenMint.publicKey,
+          vaultFractionalAccount: getAssociatedTokenAddressSync(
+            fractionalTokenMint.publicKey,
+            vaultPda,
+            true
+          ),
+          buyerPaymentAccount: buyerPaymentAccount,
+          creatorPaymentAccount: creatorPaymentAccount,
+          kycAccount: PublicKey.findProgramAddressSync(
+            [Buffer.from('simple_kyc'), buyer.publicKey.toBuffer()],
+            program.programId
+          )[0],
+          treasuryPaymentAccount: platformPaymentAccount
+        })
+        .signers([buyer])
+        .rpc()
+
+    try {
+      await buy(5)
+      expect.fail('Expected transaction to fail below the minimum')
+    } catch (error) {
+      expect(error.message).to.include('InvestmentBelowMinimum')
+    }
+
+    await buy(30)
+
+    try {
+      // 30 + 30 exceeds the 50 fraction cap across purchases
+      await buy(30)
+      expect.fail('Expected transaction to fail above the cumulative maximum')
+    } catch (error) {
+      expect(error.message).to.include('InvestmentAboveMaximum')
+    }
+
+    const [positionPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from('position'),
+        vaultPda.toBuffer(),
+        buyer.publicKey.toBuffer()
+      ],
+      program.programId
+    )
+    const position = await program.account.investorPosition.fetch(positionPda)
+    expect(position.fractionsPurchased.toString()).to.equal('30')
+    expect(position.totalPaid.toString()).to.equal('3000')
+
+    // Restore the default limits
+    await program.methods
+      .updatePlatformConfig(
+        5,
+        100,
+        new anchor.BN(1),
+        new anchor.BN(10000),
+        admin.publicKey,
+        0
+      )
+      .accounts({
+        admin: admin.publicKey,
+        platformConfig: platformConfigPda
+      })
+      .signers([admin])
+      .rpc()
+  })
+
+  it('Pauses and unpauses the platform', async () => {
+    const [platformConfigPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('platform_config')],
+      program.programId
+    )
+
+    await program.methods
+      .pausePlatform()
+      .accounts({
+        admin: admin.publicKey,
+        platformConfig: platformConfigPda
+      })
+      .signers([admin])
+      .rpc()
+
+    let platformConfig = await program.account.platformConfig.fetch(
+      platformConfigPda
+    )
+    expect(platformConfig.isActive).to.be.false
+
+    const newUser = Keypair.generate()
+    await provider.connection.requestAirdrop(
+      newUser.publicKey,
+      2 * anchor.web3.LAMPORTS_PER_SOL
+    )
+    await new Promise((resolve) => setTimeout(resolve, 1000))
+
+    try {
+      await program.methods
+        .registerKyc('US')
+        .accounts({
+          user: newUser.publicKey
+        })
+        .signers([newUser])
+        .rpc()
+
+      expect.fail('Expected transaction to fail while the platform is paused')
+    } catch (error) {
+      expect(error.message).to.include('PlatformNotActive')
+    }
+
+    await program.methods
+      .unpausePlatform()
+      .accounts({
+        admin: admin.publicKey,
+        platformConfig: platformConfigPda
+      })
+      .signers([admin])
+      .rpc()
+
+    platformConfig = await program.account.platformConfig.fetch(
+      platformConfigPda
+    )
+    expect(platformConfig.isActive).to.be.true
+  })
+
+  it('Halts a vault and freezes a holder account', async () => {
+    const [vaultPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('vault'), originalNftMint.toBuffer()],
+      program.programId
+    )
+
+    const vault = await program.account.vault.fetch(vaultPda)
+    const buyerFractionalAccount = await getAssociatedTokenAddress(
+      vault.fractionalTokenMint,
+      buyer.publicKey
+    )
+
+    await program.methods
+      .setVaultHalted(true)
+      .accounts({
+        admin: admin.publicKey,
+        vault: vaultPda
+      })
+      .signers([admin])
+      .rpc()
+
+    expect((await program.account.vault.fetch(vaultPda)).isHalted).to.be.true
+
+    await program.methods
+      .freezeHolderAccount()
+      .accounts({
+        admin: admin.publicKey,
+        vault: vaultPda,
+        fractionalTokenMint: vault.fractionalTokenMint,
+        holderFractionalAccount: buyerFractionalAccount
+      })
+      .signers([admin])
+      .rpc()
+
+    let buyerFractionalAccountInfo = await getAccount(
+      provider.connection,
+      buyerFractionalAccount
+    )
+    expect(buyerFractionalAccountInfo.isFrozen).to.be.true
+
+    await program.methods
+      .thawHolderAccount()
+      .accounts({
+        admin: admin.publicKey,
+        vault: vaultPda,
+        fractionalTokenMint: vault.fractionalTokenMint,
+        holderFractionalAccount: buyerFractionalAccount
+      })
+      .signers([admin])
+      .rpc()
+
+    buyerFractionalAccountInfo = await getAccount(
+      provider.connection,
+      buyerFractionalAccount
+    )
+    expect(buyerFractionalAccountInfo.isFrozen).to.be.false
+
+    await program.methods
+      .setVaultHalted(false)
+      .accounts({
+        admin: admin.publicKey,
+        vault: vaultPda
+      })
+      .signers([admin])
+      .rpc()
+
+    expect((await program.account.vault.fetch(vaultPda)).isHalted).to.be.false
+  })
+
+  it('Delegates the KYC officer role', async () => {
+    const [platformConfigPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('platform_config')],
+      program.programId
+    )
+
+    const kycOfficer = Keypair.generate()
+    const user = Keypair.generate()
+    await provider.connection.requestAirdrop(
+      user.publicKey,
+      2 * anchor.web3.LAMPORTS_PER_SOL
+    )
+    await new Promise((resolve) => setTimeout(resolve, 1000))
+
+    await program.methods
+      .registerKyc('US')
+      .accounts({
+        user: user.publicKey
+      })
+      .signers([user])
+      .rpc()
+
+    const [userKycPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('simple_kyc'), user.publicKey.toBuffer()],
+      program.programId
+    )
+
+    const verify = () =>
+      program.methods
+        .verifyKyc(
+          { adminApproval: {} },
+          1,
+          [...kycDossier(user.publicKey).commitment]
+        )
+        .accounts({
+          admin: kycOfficer.publicKey,
+          kycAccount: userKycPda,
+          platformConfig: platformConfigPda
+        })
+        .preInstructions([kycAttestation(user.publicKey, kycOfficer)])
+        .signers([kycOfficer])
+        .rpc()
+
+    try {
+      await verify()
+      expect.fail('Expected transaction to fail without the KYC officer role')
+    } catch (error) {
+      expect(error.message).to.include('UnauthorizedAccess')
+    }
+
+    await program.methods
+      .grantRole(kycOfficer.publicKey, { kycOfficer: {} })
+      .accounts({
+        admin: admin.publicKey
+      })
+      .signers([admin])
+      .rpc()
+
+    await verify()
+
+    const userKyc = await program.account.simpleKycAccount.fetch(userKycPda)
+    expect(userKyc.status).to.deep.equal({ verified: {} })
+
+    await program.methods
+      .revokeRole(kycOfficer.publicKey, { kycOfficer: {} })
+      .accounts({
+        admin: admin.publicKey
+      })
+      .signers([admin])
+      .rpc()
+
+    const platformConfig = await program.account.platformConfig.fetch(
+      platformConfigPda
+    )
+    expect(platformConfig.roleAssignments).to.have.length(0)
+  })
+
+  it('Transfers the admin authority in two steps', async () => {
+    const [platformConfigPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('platform_config')],
+      program.programId
+    )
+
+    const newAdmin = Keypair.generate()
+
+    await program.methods
+      .proposeAdmin(newAdmin.publicKey)
+      .accounts({
+        admin: admin.publicKey
+      })
+      .signers([admin])
+      .rpc()
+
+    let platformConfig = await program.account.platformConfig.fetch(
+      platformConfigPda
+    )
+    expect(platformConfig.admin.toString()).to.equal(admin.publicKey.toString())
+    expect(platformConfig.pendingAdmin.toString()).to.equal(
+      newAdmin.publicKey.toString()
+    )
+
+    // Only the proposed admin can accept
+    try {
+      await program.methods
+        .acceptAdmin()
+        .accounts({
+          newAdmin: buyer.publicKey
+        })
+        .signers([buyer])
+        .rpc()
+
+      expect.fail('Expected transaction to fail for a non-proposed admin')
+    } catch (error) {
+      expect(error.message).to.include('UnauthorizedAccess')
+    }
+
+    await program.methods
+      .acceptAdmin()
+      .accounts({
+        newAdmin: newAdmin.publicKey
+      })
+      .signers([newAdmin])
+      .rpc()
+
+    platformConfig = await program.account.platformConfig.fetch(
+      platformConfigPda
+    )
+    expect(platformConfig.admin.toString()).to.equal(
+      newAdmin.publicKey.toString()
+    )
+    expect(platformConfig.pendingAdmin).to.be.null
+
+    // Hand the authority back so later tests keep using `admin`
+    await program.methods
+      .proposeAdmin(admin.publicKey)
+      .accounts({
+        admin: newAdmin.publicKey
+      })
+      .signers([newAdmin])
+      .rpc()
+
+    await program.methods
+      .acceptAdmin()
+      .accounts({
+        newAdmin: admin.publicKey
+      })
+      .signers([admin])
+      .rpc()
+  })
+
+  it('Emits typed events', async () => {
+    const user = Keypair.generate()
+    await provider.connection.requestAirdrop(
+      user.publicKey,
+      2 * anchor.web3.LAMPORTS_PER_SOL
+    )
+    await new Promise((resolve) => setTimeout(resolve, 1000))
+
+    let registeredEvent = null
+    const listener = program.addEventListener('kycRegistered', (event) => {
+      registeredEvent = event
+    })
+
+    await program.methods
+      .registerKyc('US')
+      .accounts({
+        user: user.publicKey
+      })
+      .signers([user])
+      .rpc()
+
+    await new Promise((resolve) => setTimeout(resolve, 1000))
+    await program.removeEventListener(listener)
+
+    expect(registeredEvent).to.not.be.null
+    expect(registeredEvent.user.toString()).to.equal(user.publicKey.toString())
+    expect(registeredEvent.timestamp.toNumber()).to.be.greaterThan(0)
+  })
+
+  it('Runs the enhanced KYC provider flow', async () => {
+    const [providerConfigPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('kyc_provider_config')],
+      program.programId
+    )
+
+    await program.methods
+      .initializeKycProviderConfig(
+        [{ manual: {} }, { jumio: {} }],
+        { jumio: {} },
+        {
+          expirationDays: 365,
+          requireAddressVerification: false,
+          requirePepScreening: false
+        }
+      )
+      .accounts({
+        admin: admin.publicKey
+      })
+      .signers([admin])
+      .rpc()
+
+    const user = Keypair.generate()
+    await provider.connection.requestAirdrop(
+      user.publicKey,
+      2 * anchor.web3.LAMPORTS_PER_SOL
+    )
+    await new Promise((resolve) => setTimeout(resolve, 1000))
+
+    const [enhancedKycPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('kyc'), user.publicKey.toBuffer()],
+      program.programId
+    )
+
+    const contactCommitment = [
+      ...createHash('sha256').update('salt:investor@example.com').digest()
+    ]
+
+    await program.methods
+      .pausePlatform()
+      .accounts({ admin: admin.publicKey })
+      .signers([admin])
+      .rpc()
+    try {
+      await program.methods
+        .initiateKycVerification({ jumio: {} }, contactCommitment)
+        .accounts({
+          user: user.publicKey
+        })
+        .signers([user])
+        .rpc()
+
+      expect.fail('Expected KYC initiation to fail while paused')
+    } catch (error) {
+      expect(error.message).to.include('PlatformNotActive')
+    }
+    await program.methods
+      .unpausePlatform()
+      .accounts({ admin: admin.publicKey })
+      .signers([admin])
+      .rpc()
+
+    await program.methods
+      .initiateKycVerification({ jumio: {} }, contactCommitment)
+      .accounts({
+        user: user.publicKey
+      })
+      .signers([user])
+      .rpc()
+
+    let enhancedKyc = await program.account.enhancedKycAccount.fetch(
+      enhancedKycPda
+    )
+    expect(enhancedKyc.status).to.deep.equal({ inProgress: {} })
+    expect(enhancedKyc.verificationAttempts).to.equal(1)
+
+    const processResult = (riskScore: number, amlCleared: boolean) =>
+      program.methods
+        .processKycResult(
+          enhancedKyc.providerVerificationId,
+          { verified: {} },
+          riskScore,
+          {
+            identityVerified: true,
+            addressVerified: true,
+            amlCleared,
+            sanctionsCleared: true,
+            pepCleared: true
+          },
+          null
+        )
+        .accounts({
+          admin: admin.publicKey,
+          kycAccount: enhancedKycPda,
+          providerConfig: providerConfigPda
+        })
+        .signers([admin])
+        .rpc()
+
+    // A verified result needs every mandatory screening and a low enough risk
+    try {
+      await processResult(10, false)
+      expect.fail('Expected a verification without AML clearance to fail')
+    } catch (error) {
+      expect(error.message).to.include('KycComplianceIncomplete')
+    }
+    try {
+      await processResult(90, true)
+      expect.fail('Expected a high-risk verification to fail')
+    } catch (error) {
+      expect(error.message).to.include('KycRiskLevelTooHigh')
+    }
+
+    await processResult(10, true)
+
+    enhancedKyc = await program.account.enhancedKycAccount.fetch(enhancedKycPda)
+    expect(enhancedKyc.status).to.deep.equal({ verified: {} })
+    expect(enhancedKyc.expiresAt).to.not.be.null
+
+    // Enhanced KYC is administered by KYC officers
+    try {
+      await program.methods
+        .suspendKycVerification('AML hit')
+        .accounts({
+          admin: buyer.publicKey,
+          kycAccount: enhancedKycPda,
+          providerConfig: providerConfigPda
+        })
+        .signers([buyer])
+        .rpc()
+
+      expect.fail('Expected a non-officer suspension to fail')
+    } catch (error) {
+      expect(error.message).to.include('UnauthorizedAccess')
+    }
+
+    await program.methods
+      .suspendKycVerification('AML hit')
+      .accounts({
+        admin: admin.publicKey,
+        kycAccount: enhancedKycPda,
+        providerConfig: providerConfigPda
+      })
+      .signers([admin])
+      .rpc()
+
+    enhancedKyc = await program.account.enhancedKycAccount.fetch(enhancedKycPda)
+    expect(enhancedKyc.status).to.deep.equal({ suspended: {} })
+  })
+
+  it('Rejects expired KYC and allows re-verification', async () => {
+    const [platformConfigPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('platform_config')],
+      program.programId
+    )
+    const [vaultPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('vault'), originalNftMint.toBuffer()],
+      program.programId
+    )
+
+    // Verifications expire after two seconds
+    await program.methods
+      .updateKycValidityPeriod(new anchor.BN(2))
+      .accounts({
+        admin: admin.publicKey
+      })
+      .signers([admin])
+      .rpc()
+
+    const user = Keypair.generate()
+    await provider.connection.requestAirdrop(
+      user.publicKey,
+      2 * anchor.web3.LAMPORTS_PER_SOL
+    )
+    await new Promise((resolve) => setTimeout(resolve, 1000))
+
+    const userPaymentAccount = await createAccount(
+      provider.connection,
+      user,
+      paymentMint,
+      user.publicKey
+    )
+
+    const [userKycPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('simple_kyc'), user.publicKey.toBuffer()],
+      program.programId
+    )
+
+    await program.methods
+      .registerKyc('US')
+      .accounts({
+        user: user.publicKey
+      })
+      .signers([user])
+      .rpc()
+
+    await program.methods
+      .verifyKyc(
+        { adminApproval: {} },
+        1,
+        [...kycDossier(user.publicKey).commitment]
+      )
+      .accounts({
+        admin: admin.publicKey,
+        kycAccount: userKycPda,
+        platformConfig: platformConfigPda
+      })
+      .preInstructions([kycAttestation(user.publicKey, admin)])
+      .signers([admin])
+      .rpc()
+
+    let userKyc = await program.account.simpleKycAccount.fetch(userKycPda)
+    expect(userKyc.expiresAt.toNumber()).to.be.greaterThan(0)
+
+    await new Promise((resolve) => setTimeout(resolve, 3000))
+
+    const vault = await program.account.vault.fetch(vaultPda)
+
+    try {
+      await program.methods
+        .buyFractions(new anchor.BN(1))
+        .accounts({
+          buyer: user.publicKey,
+          vault: vaultPda,
+          fractionalTokenMint: vault.fractionalTokenMint,
+          vaultFractionalAccount: getAssociatedTokenAddressSync(
+            vault.fractionalTokenMint,
+            vaultPda,
+            true
+          ),
+          buyerPaymentAccount: userPaymentAccount,
+          creatorPaymentAccount: creatorPaymentAccount,
+          kycAccount: userKycPda,
+          treasuryPaymentAccount: platformPaymentAccount
+        })
+        .signers([user])
+        .rpc()
+
+      expect.fail('Expected transaction to fail due to expired KYC')
+    } catch (error) {
+      expect(error.message).to.include('KycExpired')
+    }
+
+    await program.methods
+      .requestKycReverification()
+      .accounts({
+        user: user.publicKey
+      })
+      .signers([user])
+      .rpc()
+
+    userKyc = await program.account.simpleKycAccount.fetch(userKycPda)
+    expect(userKyc.status).to.deep.equal({ pending: {} })
+    expect(userKyc.expiresAt.toString()).to.equal('0')
+
+    // Restore non-expiring verifications
+    await program.methods
+      .updateKycValidityPeriod(new anchor.BN(0))
+      .accounts({
+        admin: admin.publicKey
+      })
+      .signers([admin])
+      .rpc()
+  })
+
+  it('Suspends and revokes a verified KYC', async () => {
+    const [platformConfigPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('platform_config')],
+      program.programId
+    )
+    const [vaultPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('vault'), originalNftMint.toBuffer()],
+      program.programId
+    )
+    const [buyerKycPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('simple_kyc'), buyer.publicKey.toBuffer()],
+      program.programId
+    )
+
+    await program.methods
+      .suspendKyc({ amlHit: {} })
+      .accounts({
+        admin: admin.publicKey,
+        kycAccount: buyerKycPda,
+        platformConfig: platformConfigPda
+      })
+      .signers([admin])
+      .rpc()
+
+    let buyerKyc = await program.account.simpleKycAccount.fetch(buyerKycPda)
+    expect(buyerKyc.status).to.deep.equal({ suspended: {} })
+    expect(buyerKyc.statusReason).to.deep.equal({ amlHit: {} })
+
+    const vault = await program.account.vault.fetch(vaultPda)
+
+    try {
+      await program.methods
+        .buyFractions(new anchor.BN(1))
+        .accounts({
+          buyer: buyer.publicKey,
+          vault: vaultPda,
+          fractionalTokenMint: vault.fractionalTokenMint,
+          vaultFractionalAccount: getAssociatedTokenAddressSync(
+            vault.fractionalTokenMint,
+            vaultPda,
+            true
+          ),
+          buyerPaymentAccount: buyerPaymentAccount,
+          creatorPaymentAccount: creatorPaymentAccount,
+          kycAccount: buyerKycPda,
+          treasuryPaymentAccount: platformPaymentAccount
+        })
+        .signers([buyer])
+        .rpc()
+
+      expect.fail('Expected transaction to fail due to suspended KYC')
+    } catch (error) {
+      expect(error.message).to.include('KycSuspended')
+    }
+
+    try {
+      await program.methods
+        .requestKycReverification()
+        .accounts({
+          user: buyer.publicKey
+        })
+        .signers([buyer])
+        .rpc()
+
+      expect.fail('Expected a suspended user not to reset their own KYC')
+    } catch (error) {
+      expect(error.message).to.include('KycSuspended')
+    }
+
+    // A KYC officer lifts the suspension by verifying the user again
+    await program.methods
+      .verifyKyc(
+        { adminApproval: {} },
+        2,
+        [...kycDossier(buyer.publicKey).commitment]
+      )
+      .accounts({
+        admin: admin.publicKey,
+        kycAccount: buyerKycPda,
+        platformConfig: platformConfigPda
+      })
+      .preInstructions([kycAttestation(buyer.publicKey, admin)])
+      .signers([admin])
+      .rpc()
+
+    buyerKyc = await program.account.simpleKycAccount.fetch(buyerKycPda)
+    expect(buyerKyc.status).to.deep.equal({ verified: {} })
+    expect(buyerKyc.statusReason).to.be.null
+
+    const user = Keypair.generate()
+    await provider.connection.requestAirdrop(
+      user.publicKey,
+      2 * anchor.web3.LAMPORTS_PER_SOL
+    )
+    await new Promise((resolve) => setTimeout(resolve, 1000))
+
+    const [userKycPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('simple_kyc'), user.publicKey.toBuffer()],
+      program.programId
+    )
+
+    await program.methods
+      .registerKyc('US')
+      .accounts({
+        user: user.publicKey
+      })
+      .signers([user])
+      .rpc()
+
+    await program.methods
+      .revokeKyc({ fraudSuspected: {} })
+      .accounts({
+        admin: admin.publicKey,
+        kycAccount: userKycPda,
+        platformConfig: platformConfigPda
+      })
+      .signers([admin])
+      .rpc()
+
+    try {
+      await program.methods
+        .verifyKyc(
+          { adminApproval: {} },
+          1,
+          [...kycDossier(user.publicKey).commitment]
+        )
+        .accounts({
+          admin: admin.publicKey,
+          kycAccount: userKycPda,
+          platformConfig: platformConfigPda
+        })
+        .preInstructions([kycAttestation(user.publicKey, admin)])
+        .signers([admin])
+        .rpc()
+
+      expect.fail('Expected a revoked KYC not to be verified again')
+    } catch (error) {
+      expect(error.message).to.include('KycRevoked')
+    }
+  })
+
+  it('Caps purchases by KYC verification level', async () => {
+    const [nftMint, creatorNftAccount] = await createNft('The Kiss')
+
+    const [vaultPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('vault'), nftMint.toBuffer()],
+      program.programId
+    )
+    const fractionalTokenMint = Keypair.generate()
+
+    await program.methods
+      .fractionalize(new anchor.BN(1000), new anchor.BN(100), 0, openSale)
+      .accounts({
+        creator: creator.publicKey,
+        originalNftMint: nftMint,
+        fractionalTokenMint: fractionalTokenMint.publicKey,
+        creatorNftAccount: creatorNftAccount,
+        paymentMint: paymentMint,
+        creatorPaymentAccount: creatorPaymentAccount
+      })
+      .signers([creator, fractionalTokenMint])
+      .rpc()
+
+    // Level 1 up to 5 fractions per vault, level 2 up to 20, level 3 unlimited
+    const levelLimits = [new anchor.BN(5), new anchor.BN(20), new anchor.BN(0)]
+
+    // Compliance limits are set by KYC officers, not by any wallet
+    try {
+      await program.methods
+        .updateKycLevelLimits(levelLimits)
+        .accounts({
+          admin: buyer.publicKey
+        })
+        .signers([buyer])
+        .rpc()
+
+      expect.fail('Expected a non-officer to be rejected')
+    } catch (error) {
+      expect(error.message).to.include('UnauthorizedAccess')
+    }
+
+    await program.methods
+      .updateKycLevelLimits(levelLimits)
+      .accounts({
+        admin: admin.publicKey
+      })
+      .signers([admin])
+      .rpc()
+
+    const buy = (numFractions: number) =>
+      program.methods
+        .buyFractions(new anchor.BN(numFractions))
+        .accounts({
+          buyer: buyer.publicKey,
+          vault: vaultPda,
+          fractionalTokenMint: fractionalTokenMint.publicKey,
+          vaultFractionalAccount: getAssociatedTokenAddressSync(
+            fractionalTokenMint.publicKey,
+            vaultPda,
+            true
+          ),
+          buyerPaymentAccount: buyerPaymentAccount,
+          creatorPaymentAccount: creatorPaymentAccount,
+          kycAccount: PublicKey.findProgramAddressSync(
+            [Buffer.from('simple_kyc'), buyer.publicKey.toBuffer()],
+            program.programId
+          )[0],
+          treasuryPaymentAccount: platformPaymentAccount
+        })
+        .signers([buyer])
+        .rpc()
+
+    // The buyer holds a level 2 verification
+    await buy(20)
+
+    try {
+      await buy(1)
+      expect.fail('Expected transaction to fail above the level 2 ceiling')
+    } catch (error) {
+      expect(error.message).to.include('KycLevelLimitExceeded')
+    }
+
+    // Restore unlimited purchases for every level
+    await program.methods
+      .updateKycLevelLimits([
+        new anchor.BN(0),
+        new anchor.BN(0),
+        new anchor.BN(0)
+      ])
+      .accounts({
+        admin: admin.publicKey
+      })
+      .signers([admin])
+      .rpc()
+  })
+
+  it('Blocks purchases from restricted jurisdictions', async () => {
+    const [platformConfigPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('platform_config')],
+      program.programId
+    )
+    const [vaultPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('vault'), originalNftMint.toBuffer()],
+      program.programId
+    )
+
+    const user = Keypair.generate()
+    await provider.connection.requestAirdrop(
+      user.publicKey,
+      2 * anchor.web3.LAMPORTS_PER_SOL
+    )
+    await new Promise((resolve) => setTimeout(resolve, 1000))
+
+    const userPaymentAccount = await createAccount(
+      provider.connection,
+      user,
+      paymentMint,
+      user.publicKey
+    )
+
+    const [userKycPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('simple_kyc'), user.publicKey.toBuffer()],
+      program.programId
+    )
+
+    try {
+      await program.methods
+        .registerKyc('usa')
+        .accounts({
+          user: user.publicKey
+        })
+        .signers([user])
+        .rpc()
+
+      expect.fail('Expected registration to fail with a malformed country')
+    } catch (error) {
+      expect(error.message).to.include('InvalidCountryCode')
+    }
+
+    await program.methods
+      .registerKyc('KP')
+      .accounts({
+        user: user.publicKey
+      })
+      .signers([user])
+      .rpc()
+
+    await program.methods
+      .verifyKyc(
+        { adminApproval: {} },
+        1,
+        [...kycDossier(user.publicKey).commitment]
+      )
+      .accounts({
+        admin: admin.publicKey,
+        kycAccount: userKycPda,
+        platformConfig: platformConfigPda
+      })
+      .preInstructions([kycAttestation(user.publicKey, admin)])
+      .signers([admin])
+      .rpc()
+
+    await program.methods
+      .addJurisdiction('KP')
+      .accounts({
+        admin: admin.publicKey
+      })
+      .signers([admin])
+      .rpc()
+
+    const vault = await program.account.vault.fetch(vaultPda)
+
+    try {
+      await program.methods
+        .buyFractions(new anchor.BN(1))
+        .accounts({
+          buyer: user.publicKey,
+          vault: vaultPda,
+          fractionalTokenMint: vault.fractionalTokenMint,
+          vaultFractionalAccount: getAssociatedTokenAddressSync(
+            vault.fractionalTokenMint,
+            vaultPda,
+            true
+          ),
+          buyerPaymentAccount: userPaymentAccount,
+          creatorPaymentAccount: creatorPaymentAccount,
+          kycAccount: userKycPda,
+          treasuryPaymentAccount: platformPaymentAccount
+        })
+        .signers([user])
+        .rpc()
+
+      expect.fail('Expected transaction to fail from a blocked jurisdiction')
+    } catch (error) {
+      expect(error.message).to.include('JurisdictionRestricted')
+    }
+
+    await program.methods
+      .removeJurisdiction('KP')
+      .accounts({
+        admin: admin.publicKey
+      })
+      .signers([admin])
+      .rpc()
+
+    const [jurisdictionConfigPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('jurisdiction_config')],
+      program.programId
+    )
+    const jurisdictionConfig = await program.account.jurisdictionConfig.fetch(
+      jurisdictionConfigPda
+    )
+    expect(jurisdictionConfig.mode).to.deep.equal({ blocklist: {} })
+    expect(jurisdictionConfig.countries).to.be.empty
+  })
+
+  it('Audits a KYC dossier against its on-chain commitment', async () => {
+    const [creatorKycPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('simple_kyc'), creator.publicKey.toBuffer()],
+      program.programId
+    )
+    // The super admin holds every role, including KYC officer
+    const auditor = admin
+    const { dossierHash, commitment } = kycDossier(creator.publicKey)
+
+    const creatorKyc = await program.account.simpleKycAccount.fetch(
+      creatorKycPda
+    )
+    expect(Buffer.from(creatorKyc.piiCommitment).equals(commitment)).to.be
+      .true
+    expect(creatorKyc.attestedBy.toString()).to.equal(
+      admin.publicKey.toString()
+    )
+
+    // The auditor opens the commitment off-chain and only submits the
+    // commitment it checked, never the dossier hash or salt
+    await program.methods
+      .auditKycCommitment([...commitment])
+      .accounts({
+        auditor: auditor.publicKey,
+        kycAccount: creatorKycPda
+      })
+      .signers([auditor])
+      .rpc()
+
+    try {
+      const wrongSalt = createHash('sha256')
+        .update(Buffer.concat([dossierHash, Buffer.alloc(32)]))
+        .digest()
+      await program.methods
+        .auditKycCommitment([...wrongSalt])
+        .accounts({
+          auditor: auditor.publicKey,
+          kycAccount: creatorKycPda
+        })
+        .signers([auditor])
+        .rpc()
+
+      expect.fail('Expected the audit to fail with the wrong salt')
+    } catch (error) {
+      expect(error.message).to.include('PiiCommitmentMismatch')
+    }
+
+    // Only KYC officers may audit dossiers
+    const outsider = Keypair.generate()
+    try {
+      await program.methods
+        .auditKycCommitment([...commitment])
+        .accounts({
+          auditor: outsider.publicKey,
+          kycAccount: creatorKycPda
+        })
+        .signers([outsider])
+        .rpc()
+
+      expect.fail('Expected an audit by a non-officer to fail')
+    } catch (error) {
+      expect(error.message).to.include('UnauthorizedAccess')
+    }
+  })
+
+  it('Verifies KYC from an Ed25519-signed provider attestation', async () => {
+    const kycProvider = Keypair.generate()
+    const user = Keypair.generate()
+    await provider.connection.requestAirdrop(
+      user.publicKey,
+      2 * anchor.web3.LAMPORTS_PER_SOL
+    )
+    await new Promise((resolve) => setTimeout(resolve, 1000))
+
+    const [userKycPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('simple_kyc'), user.publicKey.toBuffer()],
+      program.programId
+    )
+
+    await program.methods
+      .initializeKycAttestorRegistry()
+      .accounts({
+        admin: admin.publicKey
+      })
+      .signers([admin])
+      .rpc()
+
+    await program.methods
+      .addKycAttestor(kycProvider.publicKey)
+      .accounts({
+        admin: admin.publicKey
+      })
+      .signers([admin])
+      .rpc()
+
+    await program.methods
+      .registerKyc('US')
+      .accounts({
+        user: user.publicKey
+      })
+      .signers([user])
+      .rpc()
+
+    // domain || user || level || expires_at (i64 LE) || nonce (u64 LE)
+    const attestationMessage = (
+      level: number,
+      expiresAt: anchor.BN,
+      nonce: anchor.BN
+    ) =>
+      Buffer.concat([
+        Buffer.from('rwa-kyc-attestation'),
+        user.publicKey.toBuffer(),
+        Buffer.from([level]),
+        expiresAt.toArrayLike(Buffer, 'le', 8),
+        nonce.toArrayLike(Buffer, 'le', 8)
+      ])
+
+    const submit = (
+      signer: Keypair,
+      level: number,
+      expiresAt: anchor.BN,
+      nonce: anchor.BN
+    ) =>
+      program.methods
+        .submitKycAttestation(level, expiresAt, nonce)
+        .accounts({
+          user: user.publicKey,
+          instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY
+        })
+        .preInstructions([
+          Ed25519Program.createInstructionWithPrivateKey({
+            privateKey: signer.secretKey,
+            message: attestationMessage(level, expiresAt, nonce)
+          })
+        ])
+        .signers([user])
+        .rpc()
+
+    const expiresAt = new anchor.BN(Math.floor(Date.now() / 1000) + 3600)
+
+    try {
+      await submit(Keypair.generate(), 2, expiresAt, new anchor.BN(1))
+      expect.fail('Expected an attestation from an untrusted key to fail')
+    } catch (error) {
+      expect(error.message).to.include('AttestorNotTrusted')
+    }
+
+    await submit(kycProvider, 2, expiresAt, new anchor.BN(1))
+
+    const userKyc = await program.account.simpleKycAccount.fetch(userKycPda)
+    expect(userKyc.status).to.deep.equal({ verified: {} })
+    expect(userKyc.verificationMethod).to.deep.equal({
+      providerAttestation: {}
+    })
+    expect(userKyc.verificationLevel).to.equal(2)
+    expect(userKyc.expiresAt.toString()).to.equal(expiresAt.toString())
+    expect(userKyc.attestedBy.toString()).to.equal(
+      kycProvider.publicKey.toString()
+    )
+
+    try {
+      await submit(kycProvider, 2, expiresAt, new anchor.BN(1))
+      expect.fail('Expected a replayed attestation to fail')
+    } catch (error) {
+      expect(error.message).to.include('AttestationNonceReused')
+    }
+  })
+
+  it('Reports KYC status through the read-only check', async () => {
+    const verified = await program.methods
+      .checkKyc({ purchase: {} })
+      .accounts({
+        wallet: buyer.publicKey
+      })
+      .view()
+
+    expect(verified.passed).to.be.true
+    expect(verified.failure).to.be.null
+    expect(verified.wallet.toString()).to.equal(buyer.publicKey.toString())
+
+    const unregistered = await program.methods
+      .checkKyc({ redemption: {} })
+      .accounts({
+        wallet: Keypair.generate().publicKey
+      })
+      .view()
+
+    expect(unregistered.passed).to.be.false
+    expect(unregistered.failure).to.deep.equal({ notRegistered: {} })
+  })
+
+  it('Limits Token-2022 fraction transfers to verified wallets', async () => {
+    const [nftMint, creatorNftAccount] = await createNft('Irises')
+
+    const [vaultPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('vault'), nftMint.toBuffer()],
+      program.programId
+    )
+    const fractionalTokenMint = Keypair.generate()
+
+    await program.methods
+      .fractionalizeToken2022(
+        new anchor.BN(1000),
+        new anchor.BN(10),
+        0,
+        openSale,
+        0
+      )
+      .accounts({
+        creator: creator.publicKey,
+        originalNftMint: nftMint,
+        fractionalTokenMint: fractionalTokenMint.publicKey,
+        creatorNftAccount: creatorNftAccount,
+        paymentMint: paymentMint,
+        creatorPaymentAccount: creatorPaymentAccount
+      })
+      .signers([creator, fractionalTokenMint])
+      .rpc()
+
+    const vault = await program.account.vault.fetch(vaultPda)
+    expect(vault.fractionalTokenProgram.toString()).to.equal(
+      TOKEN_2022_PROGRAM_ID.toString()
+    )
+
+    await transferHook.methods
+      .initializeExtraAccountMetaList()
+      .accounts({
+        payer: creator.publicKey,
+        mint: fractionalTokenMint.publicKey
+      })
+      .signers([creator])
+      .rpc()
+
+    const kycPda = (wallet: PublicKey) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from('simple_kyc'), wallet.toBuffer()],
+        program.programId
+      )[0]
+    const vaultFractionalAccount = getAssociatedTokenAddressSync(
+      fractionalTokenMint.publicKey,
+      vaultPda,
+      true,
+      TOKEN_2022_PROGRAM_ID
+    )
+    const buyerFractionalAccount = getAssociatedTokenAddressSync(
+      fractionalTokenMint.publicKey,
+      buyer.publicKey,
+      false,
+      TOKEN_2022_PROGRAM_ID
+    )
+
+    // Primary sale: the vault releases fractions through the hook
+    await program.methods
+      .buyFractions(new anchor.BN(10))
+      .accounts({
+        buyer: buyer.publicKey,
+        vault: vaultPda,
+        fractionalTokenMint: fractionalTokenMint.publicKey,
+        vaultFractionalAccount: vaultFractionalAccount,
+        buyerPaymentAccount: buyerPaymentAccount,
+        creatorPaymentAccount: creatorPaymentAccount,
+        kycAccount: kycPda(buyer.publicKey),
+        treasuryPaymentAccount: platformPaymentAccount
+      })
+      .remainingAccounts(
+        [
+          PublicKey.findProgramAddressSync(
+            [
+              Buffer.from('extra-account-metas'),
+              fractionalTokenMint.publicKey.toBuffer()
+            ],
+            transferHook.programId
+          )[0],
+          program.programId,
+          kycPda(vaultPda),
+          kycPda(buyer.publicKey),
+          PublicKey.findProgramAddressSync(
+            [Buffer.from('jurisdiction_config')],
+            program.programId
+          )[0],
+          transferHook.programId
+        ].map((pubkey) => ({ pubkey, isSigner: false, isWritable: false }))
+      )
+      .signers([buyer])
+      .rpc()
+
+    const secondaryTransfer = async (recipient: PublicKey) => {
+      const destination = await createAccount(
+        provider.connection,
+        buyer,
+        fractionalTokenMint.publicKey,
+        recipient,
+        undefined,
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      )
+      const ix = await createTransferCheckedWithTransferHookInstruction(
+        provider.connection,
+        buyerFractionalAccount,
+        fractionalTokenMint.publicKey,
+        destination,
+        buyer.publicKey,
+        BigInt(1),
+        0,
+        [],
+        'confirmed',
+        TOKEN_2022_PROGRAM_ID
+      )
+      await sendAndConfirmTransaction(
+        provider.connection,
+        new Transaction().add(ix),
+        [buyer]
+      )
+      return destination
+    }
+
+    try {
+      await secondaryTransfer(Keypair.generate().publicKey)
+      expect.fail('Expected transfer to an unverified wallet to fail')
+    } catch (error) {
+      // TransferHookError::ReceiverNotVerified
+      expect(error.message).to.include('0x1771')
+    }
+
+    // The creator passed KYC, so the hook lets the transfer through
+    const creatorFractionalAccount = await secondaryTransfer(creator.publicKey)
+    const creatorFractions = await getAccount(
+      provider.connection,
+      creatorFractionalAccount,
+      'confirmed',
+      TOKEN_2022_PROGRAM_ID
+    )
+    expect(creatorFractions.amount.toString()).to.equal('1')
+  })
+
+  it('Charges and harvests royalties on secondary transfers', async () => {
+    const [nftMint, creatorNftAccount] = await createNft('Wheatfield')
+
+    const [vaultPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('vault'), nftMint.toBuffer()],
+      program.programId
+    )
+    const fractionalTokenMint = Keypair.generate()
+    const fractionalAccount = (owner: PublicKey) =>
+      getAssociatedTokenAddressSync(
+        fractionalTokenMint.publicKey,
+        owner,
+        true,
+        TOKEN_2022_PROGRAM_ID
+      )
+
+    // 5% royalty on every secondary transfer
+    await program.methods
+      .fractionalizeToken2022(
+        new anchor.BN(1000),
+        new anchor.BN(1),
+        0,
+        openSale,
+        500
+      )
+      .accounts({
+        creator: creator.publicKey,
+        originalNftMint: nftMint,
+        fractionalTokenMint: fractionalTokenMint.publicKey,
+        creatorNftAccount: creatorNftAccount,
+        paymentMint: paymentMint,
+        creatorPaymentAccount: creatorPaymentAccount
+      })
+      .signers([creator, fractionalTokenMint])
+      .rpc()
+
+    const vault = await program.account.vault.fetch(vaultPda)
+    expect(vault.royaltyBps).to.equal(500)
+
+    await transferHook.methods
+      .initializeExtraAccountMetaList()
+      .accounts({
+        payer: creator.publicKey,
+        mint: fractionalTokenMint.publicKey
+      })
+      .signers([creator])
+      .rpc()
+
+    // The primary sale itself is royalty-free
+    await program.methods
+      .buyFractions(new anchor.BN(400))
+      .accounts({
+        buyer: buyer.publicKey,
+        vault: vaultPda,
+        fractionalTokenMint: fractionalTokenMint.publicKey,
+        vaultFractionalAccount: fractionalAccount(vaultPda),
+        buyerPaymentAccount: buyerPaymentAccount,
+        creatorPaymentAccount: creatorPaymentAccount,
+        kycAccount: PublicKey.findProgramAddressSync(
+          [Buffer.from('simple_kyc'), buyer.publicKey.toBuffer()],
+          program.programId
+        )[0],
+        treasuryPaymentAccount: platformPaymentAccount
+      })
+      .signers([buyer])
+      .rpc()
+
+    const balance = async (owner: PublicKey) =>
+      (
+        await getAccount(
+          provider.connection,
+          fractionalAccount(owner),
+          'confirmed',
+          TOKEN_2022_PROGRAM_ID
+        )
+      ).amount.toString()
+    expect(await balance(buyer.publicKey)).to.equal('400')
+
+    // Secondary sale to the creator withholds 5% in the receiving account
+    await createAccount(
+      provider.connection,
+      buyer,
+      fractionalTokenMint.publicKey,
+      creator.publicKey,
+      undefined,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    )
+    const ix = await createTransferCheckedWithTransferHookInstruction(
+      provider.connection,
+      fractionalAccount(buyer.publicKey),
+      fractionalTokenMint.publicKey,
+      fractionalAccount(creator.publicKey),
+      buyer.publicKey,
+      BigInt(400),
+      0,
+      [],
+      'confirmed',
+      TOKEN_2022_PROGRAM_ID
+    )
+    await sendAndConfirmTransaction(
+      provider.connection,
+      new Transaction().add(ix),
+      [buyer]
+    )
+    expect(await balance(creator.publicKey)).to.equal('380')
+
+    const harvest = () =>
+      program.methods
+        .harvestRoyalties()
+        .accounts({
+          payer: buyer.publicKey,
+          vault: vaultPda,
+          treasury: admin.publicKey
+        })
+        .remainingAccounts([
+          {
+            pubkey: fractionalAccount(creator.publicKey),
+            isSigner: false,
+            isWritable: true
+          }
+        ])
+        .signers([buyer])
+        .rpc()
+
+    // Royalty shares only go to verified wallets, the treasury included
+    try {
+      await harvest()
+      expect.fail('Expected harvest to an unverified treasury to fail')
+    } catch (error) {
+      expect(error.message).to.include('AccountNotInitialized')
+    }
+
+    await program.methods
+      .registerKyc('US')
+      .accounts({
+        user: admin.publicKey
+      })
+      .signers([admin])
+      .rpc()
+    await program.methods
+      .verifyKyc(
+        { adminApproval: {} },
+        1,
+        [...kycDossier(admin.publicKey).commitment]
+      )
+      .accounts({
+        admin: admin.publicKey,
+        kycAccount: PublicKey.findProgramAddressSync(
+          [Buffer.from('simple_kyc'), admin.publicKey.toBuffer()],
+          program.programId
+        )[0],
+        platformConfig: PublicKey.findProgramAddressSync(
+          [Buffer.from('platform_config')],
+          program.programId
+        )[0]
+      })
+      .preInstructions([kycAttestation(admin.publicKey, admin)])
+      .signers([admin])
+      .rpc()
+
+    // A halted vault keeps its royalties withheld
+    const setHalted = (isHalted: boolean) =>
+      program.methods
+        .setVaultHalted(isHalted)
+        .accounts({
+          admin: admin.publicKey,
+          vault: vaultPda
+        })
+        .signers([admin])
+        .rpc()
+    await setHalted(true)
+    try {
+      await harvest()
+      expect.fail('Expected harvest from a halted vault to fail')
+    } catch (error) {
+      expect(error.message).to.include('VaultHalted')
+    }
+    await setHalted(false)
+
+    await harvest()
+
+    // 20 withheld: 5% platform share to the treasury, the rest to the creator
+    expect(await balance(creator.publicKey)).to.equal('399')
+    expect(await balance(admin.publicKey)).to.equal('1')
+    expect(await balance(vaultPda)).to.equal('600')
+
+    try {
+      await program.methods
+        .harvestRoyalties()
+        .accounts({
+          payer: buyer.publicKey,
+          vault: vaultPda,
+          treasury: admin.publicKey
+        })
+        .signers([buyer])
+        .rpc()
+      expect.fail('Expected harvest without withheld fees to fail')
+    } catch (error) {
+      expect(error.message).to.include('NoRoyaltiesToHarvest')
+    }
+  })
+
+  it('Names fractional tokens after the original NFT', async () => {
+    const [vaultPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('vault'), originalNftMint.toBuffer()],
+      program.programId
+    )
+    const vault = await program.account.vault.fetch(vaultPda)
+
+    const metadata = await provider.connection.getAccountInfo(
+      metadataPda(vault.fractionalTokenMint)
+    )
+    expect(metadata.owner.toString()).to.equal(
+      TOKEN_METADATA_PROGRAM_ID.toString()
+    )
+
+    // key (1) | update_authority (32) | mint (32) | name | symbol | uri
+    const readString = (offset: number): [string, number] => {
+      const length = metadata.data.readUInt32LE(offset)
+      const end = offset + 4 + length
+      return [metadata.data.subarray(offset + 4, end).toString(), end]
+    }
+    const updateAuthority = new PublicKey(metadata.data.subarray(1, 33))
+    const [name, symbolOffset] = readString(65)
+    const [symbol, uriOffset] = readString(symbolOffset)
+    const [uri] = readString(uriOffset)
+
+    expect(updateAuthority.toString()).to.equal(vaultPda.toString())
+    expect(name.replace(/\0/g, '')).to.equal('Frac: Sunflowers')
+    expect(symbol.replace(/\0/g, '')).to.equal('ART')
+    expect(uri.replace(/\0/g, '')).to.equal(
+      'https://example.com/Sunflowers.json'
+    )
+  })
+
+  it('Only fractionalizes NFTs from approved collections', async () => {
+    const [vaultPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('vault'), originalNftMint.toBuffer()],
+      program.programId
+    )
+    const vault = await program.account.vault.fetch(vaultPda)
+    expect(vault.collectionMint.toString()).to.equal(collectionMint.toString())
+    expect(vault.metadataUri).to.equal('https://example.com/Sunflowers.json')
+    expect(vault.creators).to.have.length(1)
+    expect(vault.creators[0].address.toString()).to.equal(
+      creator.publicKey.toString()
+    )
+    expect(vault.creators[0].share).to.equal(100)
+
+    const fractionalize = (nftMint: PublicKey, nftAccount: PublicKey) => {
+      const fractionalTokenMint = Keypair.generate()
+      return program.methods
+        .fractionalize(new anchor.BN(1000), new anchor.BN(100), 0, openSale)
+        .accounts({
+          creator: creator.publicKey,
+          originalNftMint: nftMint,
+          fractionalTokenMint: fractionalTokenMint.publicKey,
+          creatorNftAccount: nftAccount,
+          paymentMint: paymentMint,
+          creatorPaymentAccount: creatorPaymentAccount
+        })
+        .signers([creator, fractionalTokenMint])
+        .rpc()
+    }
+
+    // A verified member of a collection the platform never approved
+    const [otherCollection] = await createNft('Forgeries', null)
+    const [unapprovedMint, unapprovedAccount] = await createNft(
+      'Unapproved',
+      otherCollection
+    )
+    try {
+      await fractionalize(unapprovedMint, unapprovedAccount)
+      expect.fail('Expected an unapproved collection to be refused')
+    } catch (error) {
+      expect(error.message).to.include('NftCollectionNotApproved')
+    }
+
+    // An NFT outside any collection
+    const [looseMint, looseAccount] = await createNft('Loose', null)
+    try {
+      await fractionalize(looseMint, looseAccount)
+      expect.fail('Expected an NFT without a collection to be refused')
+    } catch (error) {
+      expect(error.message).to.include('NftCollectionNotVerified')
+    }
+  })
+
+  it('Fractionalizes and redeems a programmable NFT', async () => {
+    const [pnftMint, creatorPnftAccount] = await createPnft('Water Lilies')
+    const [vaultPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('vault'), pnftMint.toBuffer()],
+      program.programId
+    )
+    const vaultNftAccount = getAssociatedTokenAddressSync(
+      pnftMint,
+      vaultPda,
+      true
+    )
+    const buyerNftAccount = getAssociatedTokenAddressSync(
+      pnftMint,
+      buyer.publicKey
+    )
+    const fractionalTokenMint = Keypair.generate()
+
+    // Without token records the pNFT cannot leave the creator's frozen account
+    const fractionalize = () =>
+      program.methods
+        .fractionalize(new anchor.BN(10), new anchor.BN(1), 0, openSale)
+        .accounts({
+          creator: creator.publicKey,
+          originalNftMint: pnftMint,
+          fractionalTokenMint: fractionalTokenMint.publicKey,
+          creatorNftAccount: creatorPnftAccount,
+          paymentMint: paymentMint,
+          creatorPaymentAccount: creatorPaymentAccount
+        })
+    try {
+      await fractionalize().signers([creator, fractionalTokenMint]).rpc()
+      expect.fail('Expected a pNFT without token records to be refused')
+    } catch (error) {
+      expect(error.message).to.include('MissingProgrammableNftAccounts')
+    }
+
+    await fractionalize()
+      .accounts({
+        creatorNftTokenRecord: tokenRecordPda(pnftMint, creatorPnftAccount),
+        vaultNftTokenRecord: tokenRecordPda(pnftMint, vaultNftAccount),
+        sysvarInstructions: SYSVAR_INSTRUCTIONS_PUBKEY
+      })
+      .signers([creator, fractionalTokenMint])
+      .rpc()
+
+    const vault = await program.account.vault.fetch(vaultPda)
+    expect(vault.isProgrammable).to.equal(true)
+    const vaultNft = await getAccount(provider.connection, vaultNftAccount)
+    expect(vaultNft.amount.toString()).to.equal('1')
+    expect(vaultNft.isFrozen).to.equal(true)
+
+    const kycAccount = PublicKey.findProgramAddressSync(
+      [Buffer.from('simple_kyc'), buyer.publicKey.toBuffer()],
+      program.programId
+    )[0]
+    await program.methods
+      .buyFractions(new anchor.BN(10))
+      .accounts({
+        buyer: buyer.publicKey,
+        vault: vaultPda,
+        fractionalTokenMint: fractionalTokenMint.publicKey,
+        vaultFractionalAccount: getAssociatedTokenAddressSync(
+          fractionalTokenMint.publicKey,
+          vaultPda,
+          true
+        ),
+        buyerPaymentAccount: buyerPaymentAccount,
+        creatorPaymentAccount: creatorPaymentAccount,
+        kycAccount,
+        treasuryPaymentAccount: platformPaymentAccount
+      })
+      .signers([buyer])
+      .rpc()
+
+    await program.methods
+      .redeem()
+      .accounts({
+        redeemer: buyer.publicKey,
+        vault: vaultPda,
+        originalNftMint: pnftMint,
+        fractionalTokenMint: fractionalTokenMint.publicKey,
+        redeemerFractionalAccount: getAssociatedTokenAddressSync(
+          fractionalTokenMint.publicKey,
+          buyer.publicKey
+        ),
+        vaultNftAccount,
+        originalNftMetadata: metadataPda(pnftMint),
+        originalNftMasterEdition: masterEditionPda(pnftMint),
+        redeemerNftTokenRecord: tokenRecordPda(pnftMint, buyerNftAccount),
+        vaultNftTokenRecord: tokenRecordPda(pnftMint, vaultNftAccount),
+        sysvarInstructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+        kycAccount
+      })
+      .signers([buyer])
+      .rpc()
+
+    // The pNFT stays frozen in the redeemer's wallet, as pNFTs always are
+    const buyerNft = await getAccount(provider.connection, buyerNftAccount)
+    expect(buyerNft.amount.toString()).to.equal('1')
+    expect(buyerNft.isFrozen).to.equal(true)
+  })
+
+  it('Sells partial shares with fractional decimals', async () => {
+    const [nftMint, creatorNftAccount] = await createNft('Haystacks')
+    const [vaultPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('vault'), nftMint.toBuffer()],
+      program.programId
+    )
+    const fractionalTokenMint = Keypair.generate()
+
+    // 1,000 shares with 6 decimals at 3 payment units per whole share
+    await program.methods
+      .fractionalize(
+        new anchor.BN(1_000_000_000),
+        new anchor.BN(3),
+        6,
+        openSale
+      )
+      .accounts({
+        creator: creator.publicKey,
+        originalNftMint: nftMint,
+        fractionalTokenMint: fractionalTokenMint.publicKey,
+        creatorNftAccount: creatorNftAccount,
+        paymentMint: paymentMint,
+        creatorPaymentAccount: creatorPaymentAccount
+      })
+      .signers([creator, fractionalTokenMint])
+      .rpc()
+
+    const mint = await getMint(
+      provider.connection,
+      fractionalTokenMint.publicKey
+    )
+    expect(mint.decimals).to.equal(6)
+    const vault = await program.account.vault.fetch(vaultPda)
+    expect(vault.fractionalDecimals).to.equal(6)
+
+    const buy = (numFractions: number) =>
+      program.methods
+        .buyFractions(new anchor.BN(numFractions))
+        .accounts({
+          buyer: buyer.publicKey,
+          vault: vaultPda,
+          fractionalTokenMint: fractionalTokenMint.publicKey,
+          vaultFractionalAccount: getAssociatedTokenAddressSync(
+            fractionalTokenMint.publicKey,
+            vaultPda,
+            true
+          ),
+          buyerPaymentAccount: buyerPaymentAccount,
+          creatorPaymentAccount: creatorPaymentAccount,
+          kycAccount: PublicKey.findProgramAddressSync(
+            [Buffer.from('simple_kyc'), buyer.publicKey.toBuffer()],
+            program.programId
+          )[0],
+          treasuryPaymentAccount: platformPaymentAccount
+        })
+        .signers([buyer])
+        .rpc()
+    const paymentBalance = async () =>
+      (await getAccount(provider.connection, buyerPaymentAccount)).amount
+
+    // A quarter share falls short of the 1-share minimum investment
+    try {
+      await buy(250_000)
+
+      expect.fail('Expected a sub-share purchase to miss the minimum')
+    } catch (error) {
+      expect(error.message).to.include('InvestmentBelowMinimum')
+    }
+
+    // One and a quarter shares cost 3.75, rounded up to 4
+    const before = await paymentBalance()
+    await buy(1_250_000)
+    expect((before - (await paymentBalance())).toString()).to.equal('4')
+
+    // Even a single base unit past a whole share is never free
+    await buy(1_000_001)
+    expect((before - (await paymentBalance())).toString()).to.equal('8')
+
+    const buyerShares = await getAccount(
+      provider.connection,
+      getAssociatedTokenAddressSync(
+        fractionalTokenMint.publicKey,
+        buyer.publicKey
+      )
+    )
+    expect(buyerShares.amount.toString()).to.equal('2250001')
+  })
+
+  it('Enforces the sale window and closes lapsed sales', async () => {
+    const now = await provider.connection.getBlockTime(
+      await provider.connection.getSlot()
+    )
+    const kycAccount = PublicKey.findProgramAddressSync(
+      [Buffer.from('simple_kyc'), buyer.publicKey.toBuffer()],
+      program.programId
+    )[0]
+
+    const createVault = async (
+      name: string,
+      startsAt: number | null,
+      endsAt: number | null
+    ) => {
+      const [nftMint, creatorNftAccount] = await createNft(name)
+      const [vaultPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from('vault'), nftMint.toBuffer()],
+        program.programId
+      )
+      const fractionalTokenMint = Keypair.generate()
+      await program.methods
+        .fractionalize(
+          new anchor.BN(100),
+          new anchor.BN(1),
+          0,
+          {
+            startsAt: startsAt === null ? null : new anchor.BN(startsAt),
+            endsAt: endsAt === null ? null : new anchor.BN(endsAt),
+            minRaise: null,
+            priceTiers: [],
+            dutchAuction: null
+          }
+        )
+        .accounts({
+          creator: creator.publicKey,
+          originalNftMint: nftMint,
+          fractionalTokenMint: fractionalTokenMint.publicKey,
+          creatorNftAccount: creatorNftAccount,
+          paymentMint: paymentMint,
+          creatorPaymentAccount: creatorPaymentAccount
+        })
+        .signers([creator, fractionalTokenMint])
+        .rpc()
+
+      const buy = () =>
+        program.methods
+          .buyFractions(new anchor.BN(1))
+          .accounts({
+            buyer: buyer.publicKey,
+            vault: vaultPda,
+            fractionalTokenMint: fractionalTokenMint.publicKey,
+            vaultFractionalAccount: getAssociatedTokenAddressSync(
+              fractionalTokenMint.publicKey,
+              vaultPda,
+              true
+            ),
+            buyerPaymentAccount: buyerPaymentAccount,
+            creatorPaymentAccount: creatorPaymentAccount,
+            kycAccount,
+            treasuryPaymentAccount: platformPaymentAccount
+          })
+          .signers([buyer])
+          .rpc()
+      const closeSale = () =>
+        program.methods.closeSale().accounts({ vault: vaultPda }).rpc()
+
+      return { vaultPda, buy, closeSale }
+    }
+
+    // A window ending in the past is refused up front
+    try {
+      await createVault('Olive Trees', null, now - 60)
+      expect.fail('Expected a lapsed sale window to be refused')
+    } catch (error) {
+      expect(error.message).to.include('InvalidSaleWindow')
+    }
+
+    // Purchases wait for the sale to open
+    const scheduled = await createVault('Almond Blossom', now + 3600, null)
+    try {
+      await scheduled.buy()
+      expect.fail('Expected a purchase before the sale opens to fail')
+    } catch (error) {
+      expect(error.message).to.include('SaleNotStarted')
+    }
+
+    // A short sale can only be closed once it lapses
+    const short = await createVault('Bedroom in Arles', null, now + 5)
+    await short.buy()
+    try {
+      await short.closeSale()
+      expect.fail('Expected an open sale to stay open')
+    } catch (error) {
+      expect(error.message).to.include('SaleStillOpen')
+    }
+
+    await new Promise((resolve) => setTimeout(resolve, 8000))
+    try {
+      await short.buy()
+      expect.fail('Expected a purchase after the sale window to fail')
+    } catch (error) {
+      expect(error.message).to.include('SaleEnded')
+    }
+
+    await short.closeSale()
+    const vault = await program.account.vault.fetch(short.vaultPda)
+    expect(vault.isSaleActive).to.equal(false)
+    expect(vault.saleEndedAt.toNumber()).to.be.at.least(now + 5)
+    expect(vault.fractionsSold.toString()).to.equal('1')
+  })
+
+  it('Refunds buyers when a sale misses its soft cap', async () => {
+    const now = await provider.connection.getBlockTime(
+      await provider.connection.getSlot()
+    )
+    const kycAccount = PublicKey.findProgramAddressSync(
+      [Buffer.from('simple_kyc'), buyer.publicKey.toBuffer()],
+      program.programId
+    )[0]
+    const paymentBalance = async (tokenAccount: PublicKey) =>
+      (await getAccount(provider.connection, tokenAccount)).amount
+
+    const createVault = async (name: string, minRaise: number) => {
+      const [nftMint, creatorNftAccount] = await createNft(name)
+      const [vaultPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from('vault'), nftMint.toBuffer()],
+        program.programId
+      )
+      const escrowPaymentAccount = getAssociatedTokenAddressSync(
+        paymentMint,
+        vaultPda,
+        true
+      )
+      const fractionalTokenMint = Keypair.generate()
+      await program.methods
+        .fractionalize(new anchor.BN(100), new anchor.BN(1), 0, {
+          startsAt: null,
+          endsAt: new anchor.BN(now + 5),
+          minRaise: new anchor.BN(minRaise),
+          priceTiers: [],
+          dutchAuction: null
+        })
+        .accounts({
+          creator: creator.publicKey,
+          originalNftMint: nftMint,
+          fractionalTokenMint: fractionalTokenMint.publicKey,
+          creatorNftAccount: creatorNftAccount,
+          paymentMint: paymentMint,
+          creatorPaymentAccount: creatorPaymentAccount,
+          escrowPaymentAccount
+        })
+        .signers([creator, fractionalTokenMint])
+        .rpc()
+
+      const buy = (fractions: number) =>
+        program.methods
+          .buyFractions(new anchor.BN(fractions))
+          .accounts({
+            buyer: buyer.publicKey,
+            vault: vaultPda,
+            fractionalTokenMint: fractionalTokenMint.publicKey,
+            vaultFractionalAccount: getAssociatedTokenAddressSync(
+              fractionalTokenMint.publicKey,
+              vaultPda,
+              true
+            ),
+            buyerPaymentAccount: buyerPaymentAccount,
+            creatorPaymentAccount: creatorPaymentAccount,
+            escrowPaymentAccount,
+            kycAccount,
+            treasuryPaymentAccount: platformPaymentAccount
+          })
+          .signers([buyer])
+          .rpc()
+      const claim = () =>
+        program.methods
+          .claimProceeds()
+          .accounts({
+            creator: creator.publicKey,
+            vault: vaultPda,
+            escrowPaymentAccount,
+            creatorPaymentAccount: creatorPaymentAccount,
+            treasuryPaymentAccount: platformPaymentAccount
+          })
+          .signers([creator])
+          .rpc()
+
+      return {
+        nftMint,
+        creatorNftAccount,
+        vaultPda,
+        escrowPaymentAccount,
+        fractionalTokenMint,
+        buy,
+        claim
+      }
+    }
+
+    // A soft cap above the sale's total value is refused
+    try {
+      await createVault('Irises', 101)
+      expect.fail('Expected an unreachable soft cap to be refused')
+    } catch (error) {
+      expect(error.message).to.include('InvalidSoftCap')
+    }
+
+    // Proceeds stay in escrow until the cap is met, then go to the creator
+    const funded = await createVault('The Red Vineyard', 5)
+    const creatorBefore = await paymentBalance(creatorPaymentAccount)
+    await funded.buy(3)
+
+    // The escrow cannot be passed off as the vault's fractional account
+    try {
+      await program.methods
+        .buyFractions(new anchor.BN(3))
+        .accounts({
+          buyer: buyer.publicKey,
+          vault: funded.vaultPda,
+          fractionalTokenMint: paymentMint,
+          vaultFractionalAccount: funded.escrowPaymentAccount,
+          buyerPaymentAccount: buyerPaymentAccount,
+          creatorPaymentAccount: creatorPaymentAccount,
+          escrowPaymentAccount: funded.escrowPaymentAccount,
+          kycAccount,
+          treasuryPaymentAccount: platformPaymentAccount
+        })
+        .signers([buyer])
+        .rpc()
+      expect.fail('Expected a foreign fractional mint to be refused')
+    } catch (error) {
+      expect(error.message).to.include('InvalidFractionalMint')
+    }
+    expect(
+      (await paymentBalance(funded.escrowPaymentAccount)).toString()
+    ).to.equal('3')
+    try {
+      await funded.claim()
+      expect.fail('Expected a claim below the soft cap to fail')
+    } catch (error) {
+      expect(error.message).to.include('SoftCapNotMet')
+    }
+    await funded.buy(2)
+    await funded.claim()
+    expect(
+      (await paymentBalance(funded.escrowPaymentAccount)).toString()
+    ).to.equal('0')
+    expect(
+      (await paymentBalance(creatorPaymentAccount)) > creatorBefore
+    ).to.equal(true)
+
+    // A sale ending below its cap refunds buyers and returns the NFT
+    const missed = await createVault('Wheatfield with Crows', 50)
+    const buyerBefore = await paymentBalance(buyerPaymentAccount)
+    await missed.buy(10)
+    try {
+      await missed.claim()
+      expect.fail('Expected a claim below the soft cap to fail')
+    } catch (error) {
+      expect(error.message).to.include('SoftCapNotMet')
+    }
+
+    await new Promise((resolve) => setTimeout(resolve, 8000))
+    await program.methods
+      .refundFractions()
+      .accounts({
+        buyer: buyer.publicKey,
+        vault: missed.vaultPda,
+        fractionalTokenMint: missed.fractionalTokenMint.publicKey,
+        buyerFractionalAccount: getAssociatedTokenAddressSync(
+          missed.fractionalTokenMint.publicKey,
+          buyer.publicKey
+        ),
+        escrowPaymentAccount: missed.escrowPaymentAccount,
+        buyerPaymentAccount: buyerPaymentAccount
+      })
+      .signers([buyer])
+      .rpc()
+    expect((await paymentBalance(buyerPaymentAccount)).toString()).to.equal(
+      buyerBefore.toString()
+    )
+    const burned = await getAccount(
+      provider.connection,
+      getAssociatedTokenAddressSync(
+        missed.fractionalTokenMint.publicKey,
+        buyer.publicKey
+      )
+    )
+    expect(burned.amount.toString()).to.equal('0')
+
+    await program.methods
+      .reclaimNft()
+      .accounts({
+        creator: creator.publicKey,
+        vault: missed.vaultPda,
+        originalNftMint: missed.nftMint
+      })
+      .signers([creator])
+      .rpc()
+    const returned = await getAccount(
+      provider.connection,
+      missed.creatorNftAccount
+    )
+    expect(returned.amount.toString()).to.equal('1')
+    const vault = await program.account.vault.fetch(missed.vaultPda)
+    expect(vault.isSaleActive).to.equal(false)
+    expect(vault.fractionsSold.toString()).to.equal('0')
+  })
+
+  it('Lets the creator cancel a vault before any sale', async () => {
+    const kycAccount = PublicKey.findProgramAddressSync(
+      [Buffer.from('simple_kyc'), buyer.publicKey.toBuffer()],
+      program.programId
+    )[0]
+
+    const createVault = async (name: string, saleTerms = openSale) => {
+      const [nftMint, creatorNftAccount] = await createNft(name)
+      const [vaultPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from('vault'), nftMint.toBuffer()],
+        program.programId
+      )
+      const escrowPaymentAccount = saleTerms.minRaise
+        ? getAssociatedTokenAddressSync(paymentMint, vaultPda, true)
+        : null
+      const fractionalTokenMint = Keypair.generate()
+      await program.methods
+        .fractionalize(new anchor.BN(100), new anchor.BN(1), 0, saleTerms)
+        .accounts({
+          creator: creator.publicKey,
+          originalNftMint: nftMint,
+          fractionalTokenMint: fractionalTokenMint.publicKey,
+          creatorNftAccount: creatorNftAccount,
+          paymentMint: paymentMint,
+          creatorPaymentAccount: creatorPaymentAccount,
+          escrowPaymentAccount
+        })
+        .signers([creator, fractionalTokenMint])
+        .rpc()
+
+      const vaultFractionalAccount = getAssociatedTokenAddressSync(
+        fractionalTokenMint.publicKey,
+        vaultPda,
+        true
+      )
+      const cancel = () =>
+        program.methods
+          .cancelVault()
+          .accounts({
+            creator: creator.publicKey,
+            vault: vaultPda,
+            originalNftMint: nftMint,
+            fractionalTokenMint: fractionalTokenMint.publicKey,
+            escrowPaymentAccount,
+            creatorPaymentAccount: creatorPaymentAccount
+          })
+          .signers([creator])
+          .rpc()
+
+      return {
+        nftMint,
+        creatorNftAccount,
+        vaultPda,
+        escrowPaymentAccount,
+        fractionalTokenMint,
+        vaultFractionalAccount,
+        cancel
+      }
+    }
+
+    // Once a fraction is sold the vault cannot be cancelled
+    const sold = await createVault('The Potato Eaters')
+    await program.methods
+      .buyFractions(new anchor.BN(1))
+      .accounts({
+        buyer: buyer.publicKey,
+        vault: sold.vaultPda,
+        fractionalTokenMint: sold.fractionalTokenMint.publicKey,
+        vaultFractionalAccount: sold.vaultFractionalAccount,
+        buyerPaymentAccount: buyerPaymentAccount,
+        creatorPaymentAccount: creatorPaymentAccount,
+        kycAccount,
+        treasuryPaymentAccount: platformPaymentAccount
+      })
+      .signers([buyer])
+      .rpc()
+    try {
+      await sold.cancel()
+      expect.fail('Expected a vault with sales to refuse cancellation')
+    } catch (error) {
+      expect(error.message).to.include('FractionsAlreadySold')
+    }
+
+    // An unsold vault burns its fractions and hands the NFT back
+    const unsold = await createVault('Cafe Terrace at Night')
+    await unsold.cancel()
+
+    const returned = await getAccount(
+      provider.connection,
+      unsold.creatorNftAccount
+    )
+    expect(returned.amount.toString()).to.equal('1')
+    const mint = await getMint(
+      provider.connection,
+      unsold.fractionalTokenMint.publicKey
+    )
+    expect(mint.supply.toString()).to.equal('0')
+    expect(await provider.connection.getAccountInfo(unsold.vaultPda)).to.equal(
+      null
+    )
+    expect(
+      await provider.connection.getAccountInfo(unsold.vaultFractionalAccount)
+    ).to.equal(null)
+    expect(
+      await provider.connection.getAccountInfo(
+        getAssociatedTokenAddressSync(unsold.nftMint, unsold.vaultPda, true)
+      )
+    ).to.equal(null)
+
+    // A stray deposit cannot keep a soft-capped vault from being cancelled
+    const now = await provider.connection.getBlockTime(
+      await provider.connection.getSlot()
+    )
+    const dusted = await createVault('The Bedroom', {
+      ...openSale,
+      endsAt: new anchor.BN(now + 60),
+      minRaise: new anchor.BN(50)
+    })
+    await mintTo(
+      provider.connection,
+      admin,
+      paymentMint,
+      dusted.escrowPaymentAccount,
+      admin,
+      1
+    )
+    const creatorBalance = async () =>
+      (await getAccount(provider.connection, creatorPaymentAccount)).amount
+    const balanceBefore = await creatorBalance()
+    await dusted.cancel()
+
+    expect((await creatorBalance()) - balanceBefore).to.equal(BigInt(1))
+    expect(
+      await provider.connection.getAccountInfo(dusted.escrowPaymentAccount)
+    ).to.equal(null)
+  })
+
+  it('Prices purchases across tiers and creator price updates', async () => {
+    const kycAccount = PublicKey.findProgramAddressSync(
+      [Buffer.from('simple_kyc'), buyer.publicKey.toBuffer()],
+      program.programId
+    )[0]
+    const paymentBalance = async () =>
+      (await getAccount(provider.connection, buyerPaymentAccount)).amount
+
+    const createVault = async (
+      name: string,
+      priceTiers: { fractions: number; price: number }[]
+    ) => {
+      const [nftMint, creatorNftAccount] = await createNft(name)
+      const [vaultPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from('vault'), nftMint.toBuffer()],
+        program.programId
+      )
+      const fractionalTokenMint = Keypair.generate()
+      await program.methods
+        .fractionalize(new anchor.BN(100), new anchor.BN(10), 0, {
+          ...openSale,
+          priceTiers: priceTiers.map((tier) => ({
+            fractions: new anchor.BN(tier.fractions),
+            pricePerFraction: new anchor.BN(tier.price)
+          }))
+        })
+        .accounts({
+          creator: creator.publicKey,
+          originalNftMint: nftMint,
+          fractionalTokenMint: fractionalTokenMint.publicKey,
+          creatorNftAccount: creatorNftAccount,
+          paymentMint: paymentMint,
+          creatorPaymentAccount: creatorPaymentAccount
+        })
+        .signers([creator, fractionalTokenMint])
+        .rpc()
+
+      const buy = (fractions: number) =>
+        program.methods
+          .buyFractions(new anchor.BN(fractions))
+          .accounts({
+            buyer: buyer.publicKey,
+            vault: vaultPda,
+            fractionalTokenMint: fractionalTokenMint.publicKey,
+            vaultFractionalAccount: getAssociatedTokenAddressSync(
+              fractionalTokenMint.publicKey,
+              vaultPda,
+              true
+            ),
+            buyerPaymentAccount: buyerPaymentAccount,
+            creatorPaymentAccount: creatorPaymentAccount,
+            kycAccount,
+            treasuryPaymentAccount: platformPaymentAccount
+          })
+          .signers([buyer])
+          .rpc()
+
+      return { vaultPda, buy }
+    }
+
+    // Tiers cannot cover more fractions than the vault sells
+    try {
+      await createVault('Starry Night over the Rhone', [
+        { fractions: 60, price: 5 },
+        { fractions: 50, price: 8 }
+      ])
+      expect.fail('Expected oversized price tiers to be refused')
+    } catch (error) {
+      expect(error.message).to.include('InvalidPriceTiers')
+    }
+
+    // 10 early-bird fractions at 5, then the base price of 10
+    const tiered = await createVault('The Night Cafe', [
+      { fractions: 10, price: 5 }
+    ])
+    let before = await paymentBalance()
+    await tiered.buy(8)
+    expect((before - (await paymentBalance())).toString()).to.equal('40')
+
+    // A purchase crossing the tier boundary pays each tier's price
+    before = await paymentBalance()
+    await tiered.buy(4)
+    expect((before - (await paymentBalance())).toString()).to.equal('30')
+
+    // Only the creator can reprice the sale
+    try {
+      await program.methods
+        .updatePrice(new anchor.BN(20))
+        .accounts({ creator: buyer.publicKey, vault: tiered.vaultPda })
+        .signers([buyer])
+        .rpc()
+      expect.fail('Expected a non-creator price update to fail')
+    } catch (error) {
+      expect(error.message).to.include('OwnerMismatch')
+    }
+
+    await program.methods
+      .updatePrice(new anchor.BN(20))
+      .accounts({ creator: creator.publicKey, vault: tiered.vaultPda })
+      .signers([creator])
+      .rpc()
+    const vault = await program.account.vault.fetch(tiered.vaultPda)
+    expect(vault.pricePerFraction.toString()).to.equal('20')
+
+    before = await paymentBalance()
+    await tiered.buy(1)
+    expect((before - (await paymentBalance())).toString()).to.equal('20')
+  })
+
+  it('Runs a Dutch auction settling at a uniform clearing price', async () => {
+    const now = await provider.connection.getBlockTime(
+      await provider.connection.getSlot()
+    )
+    const kycAccount = PublicKey.findProgramAddressSync(
+      [Buffer.from('simple_kyc'), buyer.publicKey.toBuffer()],
+      program.programId
+    )[0]
+    const paymentBalance = async (tokenAccount: PublicKey) =>
+      (await getAccount(provider.connection, tokenAccount)).amount
+
+    // Starts at 100 and drops to the floor of 10 in two steps over 8 seconds
+    const createAuction = async (name: string, startPrice: number) => {
+      const [nftMint, creatorNftAccount] = await createNft(name)
+      const [vaultPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from('vault'), nftMint.toBuffer()],
+        program.programId
+      )
+      const escrowPaymentAccount = getAssociatedTokenAddressSync(
+        paymentMint,
+        vaultPda,
+        true
+      )
+      const fractionalTokenMint = Keypair.generate()
+      await program.methods
+        .fractionalize(new anchor.BN(2), new anchor.BN(10), 0, {
+          ...openSale,
+          endsAt: new anchor.BN(now + 3600),
+          dutchAuction: {
+            startPrice: new anchor.BN(startPrice),
+            duration: new anchor.BN(8),
+            decay: { stepwise: { steps: 2 } },
+            uniformClearingPrice: true
+          }
+        })
+        .accounts({
+          creator: creator.publicKey,
+          originalNftMint: nftMint,
+          fractionalTokenMint: fractionalTokenMint.publicKey,
+          creatorNftAccount: creatorNftAccount,
+          paymentMint: paymentMint,
+          creatorPaymentAccount: creatorPaymentAccount,
+          escrowPaymentAccount
+        })
+        .signers([creator, fractionalTokenMint])
+        .rpc()
+
+      const buy = () =>
+        program.methods
+          .buyFractions(new anchor.BN(1))
+          .accounts({
+            buyer: buyer.publicKey,
+            vault: vaultPda,
+            fractionalTokenMint: fractionalTokenMint.publicKey,
+            vaultFractionalAccount: getAssociatedTokenAddressSync(
+              fractionalTokenMint.publicKey,
+              vaultPda,
+              true
+            ),
+            buyerPaymentAccount: buyerPaymentAccount,
+            creatorPaymentAccount: creatorPaymentAccount,
+            escrowPaymentAccount,
+            kycAccount,
+            treasuryPaymentAccount: platformPaymentAccount
+          })
+          .signers([buyer])
+          .rpc()
+      const claimRefund = () =>
+        program.methods
+          .claimAuctionRefund()
+          .accounts({
+            buyer: buyer.publicKey,
+            vault: vaultPda,
+            escrowPaymentAccount,
+            buyerPaymentAccount: buyerPaymentAccount
+          })
+          .signers([buyer])
+          .rpc()
+
+      return { vaultPda, escrowPaymentAccount, buy, claimRefund }
+    }
+
+    // The start price must be above the floor
+    try {
+      await createAuction('Sunflowers', 10)
+      expect.fail('Expected an auction without a price drop to be refused')
+    } catch (error) {
+      expect(error.message).to.include('InvalidDutchAuction')
+    }
+
+    const auction = await createAuction('The Yellow House', 100)
+
+    // The auction schedule sets the price, not the creator
+    try {
+      await program.methods
+        .updatePrice(new anchor.BN(50))
+        .accounts({ creator: creator.publicKey, vault: auction.vaultPda })
+        .signers([creator])
+        .rpc()
+      expect.fail('Expected repricing an auction to fail')
+    } catch (error) {
+      expect(error.message).to.include('PriceSetByAuction')
+    }
+
+    // An early buyer pays the start price, a late one the floor
+    const buyerBefore = await paymentBalance(buyerPaymentAccount)
+    await auction.buy()
+    await new Promise((resolve) => setTimeout(resolve, 9000))
+    await auction.buy()
+    expect(
+      (buyerBefore - (await paymentBalance(buyerPaymentAccount))).toString()
+    ).to.equal('110')
+    expect(
+      (await paymentBalance(auction.escrowPaymentAccount)).toString()
+    ).to.equal('110')
+
+    // Refunds wait for the auction to close
+    try {
+      await auction.claimRefund()
+      expect.fail('Expected a refund before the auction closes to fail')
+    } catch (error) {
+      expect(error.message).to.include('AuctionNotClosed')
+    }
+
+    await program.methods
+      .closeSale()
+      .accounts({ vault: auction.vaultPda })
+      .rpc()
+    const vault = await program.account.vault.fetch(auction.vaultPda)
+    expect(vault.clearingPrice.toString()).to.equal('10')
+
+    // The creator collects both fractions at the clearing price
+    await program.methods
+      .claimProceeds()
+      .accounts({
+        creator: creator.publicKey,
+        vault: auction.vaultPda,
+        escrowPaymentAccount: auction.escrowPaymentAccount,
+        creatorPaymentAccount: creatorPaymentAccount,
+        treasuryPaymentAccount: platformPaymentAccount
+      })
+      .signers([creator])
+      .rpc()
+    expect(
+      (await paymentBalance(auction.escrowPaymentAccount)).toString()
+    ).to.equal('90')
+
+    // The buyer gets back everything paid above the clearing price, once
+    await auction.claimRefund()
+    expect(
+      (buyerBefore - (await paymentBalance(buyerPaymentAccount))).toString()
+    ).to.equal('20')
+    expect(
+      (await paymentBalance(auction.escrowPaymentAccount)).toString()
+    ).to.equal('0')
+    try {
+      await auction.claimRefund()
+      expect.fail('Expected a second refund to fail')
+    } catch (error) {
+      expect(error.message).to.include('NothingToRefund')
+    }
+  })
+
+  it('Stores Token-2022 share metadata on the mint', async () => {
+    const [nftMint, creatorNftAccount] = await createNft('Irises')
+
+    const [vaultPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('vault'), nftMint.toBuffer()],
+      program.programId
+    )
+    const fractionalTokenMint = Keypair.generate()
+
+    await program.methods
+      .fractionalizeToken2022(
+        new anchor.BN(1000),
+        new anchor.BN(1),
+        0,
+        openSale,
+        null
+      )
+      .accounts({
+        creator: creator.publicKey,
+        originalNftMint: nftMint,
+        fractionalTokenMint: fractionalTokenMint.publicKey,
+        creatorNftAccount: creatorNftAccount,
+        paymentMint: paymentMint,
+        creatorPaymentAccount: creatorPaymentAccount
+      })
+      .signers([creator, fractionalTokenMint])
+      .rpc()
+
+    // The metadata pointer targets the mint, which holds the metadata itself
+    const metadata = await getTokenMetadata(
+      provider.connection,
+      fractionalTokenMint.publicKey,
+      'confirmed',
+      TOKEN_2022_PROGRAM_ID
+    )
+    expect(metadata.mint.toString()).to.equal(
+      fractionalTokenMint.publicKey.toString()
+    )
+    expect(metadata.updateAuthority.toString()).to.equal(vaultPda.toString())
+    expect(metadata.name).to.equal('Frac: Irises')
+    expect(metadata.symbol).to.equal('ART')
+    expect(metadata.uri).to.equal('https://example.com/Irises.json')
+  })
+
+  it('Requires the officer to sign the verified KYC commitment', async () => {
+    const user = Keypair.generate()
+    await provider.connection.requestAirdrop(
+      user.publicKey,
+      2 * anchor.web3.LAMPORTS_PER_SOL
+    )
+    await new Promise((resolve) => setTimeout(resolve, 1000))
+
+    await program.methods
+      .registerKyc('US')
+      .accounts({
+        user: user.publicKey
+      })
+      .signers([user])
+      .rpc()
+    const [userKycPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('simple_kyc'), user.publicKey.toBuffer()],
+      program.programId
+    )
+    const [platformConfigPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('platform_config')],
+      program.programId
+    )
+
+    const verify = (commitment: Buffer, attestation: TransactionInstruction) =>
+      program.methods
+        .verifyKyc({ adminApproval: {} }, 1, [...commitment])
+        .accounts({
+          admin: admin.publicKey,
+          kycAccount: userKycPda,
+          platformConfig: platformConfigPda
+        })
+        .preInstructions([attestation])
+        .signers([admin])
+        .rpc()
+    const { commitment } = kycDossier(user.publicKey)
+
+    // Signed by someone other than the verifying officer
+    try {
+      await verify(commitment, kycAttestation(user.publicKey, buyer))
+      expect.fail('Expected a signature by another key to be rejected')
+    } catch (error) {
+      expect(error.message).to.include('InvalidAttestationSignature')
+    }
+
+    // Signed by the officer, but over another user's commitment
+    try {
+      await verify(commitment, kycAttestation(buyer.publicKey, admin))
+      expect.fail('Expected a signature over another commitment to be rejected')
+    } catch (error) {
+      expect(error.message).to.include('AttestationMismatch')
+    }
+
+    await verify(commitment, kycAttestation(user.publicKey, admin))
+    const userKyc = await program.account.simpleKycAccount.fetch(userKycPda)
+    expect(userKyc.status).to.deep.equal({ verified: {} })
+  })
+
+  it('Falls back to the platform default royalty', async () => {
+    const setDefaultRoyalty = (defaultRoyaltyBps: number) =>
+      program.methods
+        .updatePlatformConfig(
+          5,
+          100,
+          new anchor.BN(1),
+          new anchor.BN(10000),
+          admin.publicKey,
+          defaultRoyaltyBps
+        )
+        .accounts({
+          admin: admin.publicKey,
+          platformConfig: PublicKey.findProgramAddressSync(
+            [Buffer.from('platform_config')],
+            program.programId
+          )[0]
+        })
+        .signers([admin])
+        .rpc()
+
+    // The default is bounded like any vault royalty
+    try {
+      await setDefaultRoyalty(5001)
+      expect.fail('Expected a default royalty above 50% to be rejected')
+    } catch (error) {
+      expect(error.message).to.include('InvalidRoyaltyFee')
+    }
+
+    await setDefaultRoyalty(250)
+
+    const [nftMint, creatorNftAccount] = await createNft('Olive Trees')
+    const [vaultPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('vault'), nftMint.toBuffer()],
+      program.programId
+    )
+    const fractionalTokenMint = Keypair.generate()
+
+    await program.methods
+      .fractionalizeToken2022(
+        new anchor.BN(1000),
+        new anchor.BN(1),
+        0,
+        openSale,
+        null
+      )
+      .accounts({
+        creator: creator.publicKey,
+        originalNftMint: nftMint,
+        fractionalTokenMint: fractionalTokenMint.publicKey,
+        creatorNftAccount: creatorNftAccount,
+        paymentMint: paymentMint,
+        creatorPaymentAccount: creatorPaymentAccount
+      })
+      .signers([creator, fractionalTokenMint])
+      .rpc()
+
+    const vault = await program.account.vault.fetch(vaultPda)
+    expect(vault.royaltyBps).to.equal(250)
+
+    await setDefaultRoyalty(0)
+  })
+
+  it('Redeems a royalty vault after secondary transfers', async () => {
+    const [nftMint, creatorNftAccount] = await createNft('Sunflowers')
+
+    const [vaultPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('vault'), nftMint.toBuffer()],
+      program.programId
+    )
+    const fractionalTokenMint = Keypair.generate()
+    const fractionalAccount = (owner: PublicKey) =>
+      getAssociatedTokenAddressSync(
+        fractionalTokenMint.publicKey,
+        owner,
+        true,
+        TOKEN_2022_PROGRAM_ID
+      )
+
+    // 5% royalty on every secondary transfer
+    await program.methods
+      .fractionalizeToken2022(
+        new anchor.BN(100),
+        new anchor.BN(1),
+        0,
+        openSale,
+        500
+      )
+      .accounts({
+        creator: creator.publicKey,
+        originalNftMint: nftMint,
+        fractionalTokenMint: fractionalTokenMint.publicKey,
+        creatorNftAccount: creatorNftAccount,
+        paymentMint: paymentMint,
+        creatorPaymentAccount: creatorPaymentAccount
+      })
+      .signers([creator, fractionalTokenMint])
+      .rpc()
+
+    await transferHook.methods
+      .initializeExtraAccountMetaList()
+      .accounts({
+        payer: creator.publicKey,
+        mint: fractionalTokenMint.publicKey
+      })
+      .signers([creator])
+      .rpc()
+
+    const buyerKycPda = PublicKey.findProgramAddressSync(
+      [Buffer.from('simple_kyc'), buyer.publicKey.toBuffer()],
+      program.programId
+    )[0]
+    await program.methods
+      .buyFractions(new anchor.BN(100))
+      .accounts({
+        buyer: buyer.publicKey,
+        vault: vaultPda,
+        fractionalTokenMint: fractionalTokenMint.publicKey,
+        vaultFractionalAccount: fractionalAccount(vaultPda),
+        buyerPaymentAccount: buyerPaymentAccount,
+        creatorPaymentAccount: creatorPaymentAccount,
+        kycAccount: buyerKycPda,
+        treasuryPaymentAccount: platformPaymentAccount
+      })
+      .signers([buyer])
+      .rpc()
+
+    // Sell 40 shares to the creator and buy the 38 received back: each trade
+    // withholds 2 shares in the receiving account
+    await createAccount(
+      provider.connection,
+      buyer,
+      fractionalTokenMint.publicKey,
+      creator.publicKey,
+      undefined,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    )
+    const trade = async (from: Keypair, to: PublicKey, amount: number) =>
+      sendAndConfirmTransaction(
+        provider.connection,
+        new Transaction().add(
+          await createTransferCheckedWithTransferHookInstruction(
+            provider.connection,
+            fractionalAccount(from.publicKey),
+            fractionalTokenMint.publicKey,
+            fractionalAccount(to),
+            from.publicKey,
+            BigInt(amount),
+            0,
+            [],
+            'confirmed',
+            TOKEN_2022_PROGRAM_ID
+          )
+        ),
+        [from]
+      )
+    await trade(buyer, creator.publicKey, 40)
+    await trade(creator, buyer.publicKey, 38)
+
+    const buyerShares = await getAccount(
+      provider.connection,
+      fractionalAccount(buyer.publicKey),
+      'confirmed',
+      TOKEN_2022_PROGRAM_ID
+    )
+    expect(buyerShares.amount.toString()).to.equal('96')
+
+    const vaultNftAccount = getAssociatedTokenAddressSync(
+      nftMint,
+      vaultPda,
+      true
+    )
+    const redeem = (holderAccounts: PublicKey[]) =>
+      program.methods
+        .redeem()
+        .accounts({
+          redeemer: buyer.publicKey,
+          vault: vaultPda,
+          originalNftMint: nftMint,
+          fractionalTokenMint: fractionalTokenMint.publicKey,
+          redeemerFractionalAccount: fractionalAccount(buyer.publicKey),
+          vaultFractionalAccount: fractionalAccount(vaultPda),
+          vaultNftAccount,
+          kycAccount: buyerKycPda
+        })
+        .remainingAccounts(
+          holderAccounts.map((pubkey) => ({
+            pubkey,
+            isSigner: false,
+            isWritable: true
+          }))
+        )
+        .signers([buyer])
+        .rpc()
+
+    // The 2 shares withheld from the creator's purchase are still missing
+    try {
+      await redeem([])
+      expect.fail('Expected redemption without every withheld share to fail')
+    } catch (error) {
+      expect(error.message).to.include('InsufficientTokens')
+    }
+
+    await redeem([fractionalAccount(creator.publicKey)])
+
+    const mint = await getMint(
+      provider.connection,
+      fractionalTokenMint.publicKey,
+      'confirmed',
+      TOKEN_2022_PROGRAM_ID
+    )
+    expect(mint.supply.toString()).to.equal('0')
+    const buyerNft = await getAccount(
+      provider.connection,
+      getAssociatedTokenAddressSync(nftMint, buyer.publicKey)
+    )
+    expect(buyerNft.amount.toString()).to.equal('1')
+  })
 })